@@ -0,0 +1,310 @@
+// Sound effects and music. Effects get a limited number of voices; when they
+// are all busy a new sound may only replace a less important one. Big moments
+// ("stingers") duck the music while they play.
+
+use rodio::source::{SineWave, Source};
+use rodio::{OutputStream, OutputStreamHandle, Sink};
+use std::time::{Duration, Instant};
+
+use crate::music::MusicCue;
+use crate::sound_pack::SoundPack;
+use crate::BlockType;
+
+const MAX_VOICES: usize = 4;
+const VOICE_AMPLITUDE: f32 = 0.2;
+const DUCKED_MUSIC_VOLUME: f32 = 0.3;
+// Fraction of the gap to the target music volume closed every frame
+const DUCK_SMOOTHING: f32 = 0.2;
+// How much faster the music gets at full tension, and how gently it gets there
+const MAX_TENSION_SPEEDUP: f32 = 0.25;
+const TENSION_SMOOTHING: f32 = 0.02;
+// Between the sounds of a sequence, like a piece and the one after it
+const SEQUENCE_GAP: Duration = Duration::from_millis(150);
+pub const REST: f32 = 0.0;
+
+// One note of a cue or tune. A sine wave needs some frequency, so a rest is a silent one.
+pub fn note(frequency: f32, ms: u64, amplitude: f32) -> impl Source<Item = f32> {
+    let amplitude = if frequency == REST { 0.0 } else { amplitude };
+    SineWave::new(frequency.max(1.0)).take_duration(Duration::from_millis(ms)).amplify(amplitude)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SoundEvent {
+    Move,
+    Rotate,
+    Lock,
+    LineClear,
+    Item,
+    Tetris,
+    GameOver,
+    // Names a piece for players who can't easily see it
+    Piece(BlockType),
+}
+
+impl SoundEvent {
+    pub const ALL: [SoundEvent; 14] = [
+        SoundEvent::Move,
+        SoundEvent::Rotate,
+        SoundEvent::Lock,
+        SoundEvent::LineClear,
+        SoundEvent::Item,
+        SoundEvent::Tetris,
+        SoundEvent::GameOver,
+        SoundEvent::Piece(BlockType::I),
+        SoundEvent::Piece(BlockType::O),
+        SoundEvent::Piece(BlockType::T),
+        SoundEvent::Piece(BlockType::S),
+        SoundEvent::Piece(BlockType::Z),
+        SoundEvent::Piece(BlockType::J),
+        SoundEvent::Piece(BlockType::L),
+    ];
+
+    // The name a sound pack's file for it goes by
+    pub fn file_stem(self) -> &'static str {
+        match self {
+            SoundEvent::Move => "move",
+            SoundEvent::Rotate => "rotate",
+            SoundEvent::Lock => "lock",
+            SoundEvent::LineClear => "clear",
+            SoundEvent::Item => "item",
+            SoundEvent::Tetris => "tetris",
+            SoundEvent::GameOver => "game_over",
+            // A pack can put a spoken letter in these
+            SoundEvent::Piece(BlockType::I) => "piece_i",
+            SoundEvent::Piece(BlockType::O) => "piece_o",
+            SoundEvent::Piece(BlockType::T) => "piece_t",
+            SoundEvent::Piece(BlockType::S) => "piece_s",
+            SoundEvent::Piece(BlockType::Z) => "piece_z",
+            SoundEvent::Piece(BlockType::J) => "piece_j",
+            SoundEvent::Piece(BlockType::L) => "piece_l",
+        }
+    }
+
+    fn priority(self) -> u8 {
+        match self {
+            SoundEvent::Move | SoundEvent::Rotate => 0,
+            SoundEvent::Lock => 1,
+            SoundEvent::LineClear | SoundEvent::Item | SoundEvent::Piece(_) => 2,
+            SoundEvent::Tetris => 3,
+            SoundEvent::GameOver => 4,
+        }
+    }
+
+    fn ducks_music(self) -> bool {
+        matches!(self, SoundEvent::Tetris | SoundEvent::GameOver)
+    }
+
+    // (frequency in Hz, length in ms) for each note of the cue
+    pub fn notes(self) -> &'static [(f32, u64)] {
+        match self {
+            SoundEvent::Move => &[(440.0, 20)],
+            SoundEvent::Rotate => &[(660.0, 25)],
+            SoundEvent::Lock => &[(220.0, 40)],
+            SoundEvent::LineClear => &[(523.0, 60), (659.0, 80)],
+            SoundEvent::Item => &[(880.0, 50), (1175.0, 70)],
+            SoundEvent::Tetris => &[(523.0, 80), (659.0, 80), (784.0, 80), (1047.0, 200)],
+            SoundEvent::GameOver => &[(392.0, 150), (330.0, 150), (262.0, 300)],
+            // Each its own pitch and rhythm: rising S, falling Z, a long I, a doubled O...
+            SoundEvent::Piece(BlockType::I) => &[(784.0, 240)],
+            SoundEvent::Piece(BlockType::O) => &[(523.0, 80), (REST, 30), (523.0, 80)],
+            SoundEvent::Piece(BlockType::T) => &[(523.0, 70), (659.0, 70), (523.0, 70)],
+            SoundEvent::Piece(BlockType::S) => &[(440.0, 80), (587.0, 80)],
+            SoundEvent::Piece(BlockType::Z) => &[(587.0, 80), (440.0, 80)],
+            SoundEvent::Piece(BlockType::J) => &[(392.0, 60), (REST, 20), (392.0, 60), (523.0, 100)],
+            SoundEvent::Piece(BlockType::L) => &[(659.0, 60), (REST, 20), (659.0, 60), (494.0, 100)],
+        }
+    }
+
+    fn duration(self) -> Duration {
+        Duration::from_millis(self.notes().iter().map(|&(_, ms)| ms).sum())
+    }
+}
+
+struct Voice {
+    sink: Sink,
+    priority: u8,
+}
+
+pub struct Mixer {
+    // The stream has to stay alive for as long as anything plays through its handle
+    _stream: Option<OutputStream>,
+    handle: Option<OutputStreamHandle>,
+    voices: Vec<Voice>,
+    music: Option<Sink>,
+    music_cue: Option<MusicCue>,
+    // All from 0 (off) to 1 (full); master scales the other two
+    pub master_volume: f32,
+    pub effects_volume: f32,
+    pub music_volume: f32,
+    ducked_until: Option<Instant>,
+    // Replaces the built-in sounds it has files for
+    pub sound_pack: Option<SoundPack>,
+    // From 0 (calm) to 1 (about to top out); the music speeds up with it
+    pub tension: f32,
+    // Skipping through a replay plays a lot at once, all of it silently
+    pub muted: bool,
+}
+
+impl Mixer {
+    // Falls back to a silent mixer when there's no usable output device.
+    pub fn new() -> Self {
+        let (stream, handle) = match OutputStream::try_default() {
+            Ok((stream, handle)) => (Some(stream), Some(handle)),
+            Err(_) => (None, None),
+        };
+        let music = handle.as_ref().and_then(|handle| Sink::try_new(handle).ok());
+
+        Self {
+            _stream: stream,
+            handle,
+            voices: Vec::new(),
+            music,
+            music_cue: None,
+            master_volume: 1.0,
+            effects_volume: 1.0,
+            music_volume: 1.0,
+            ducked_until: None,
+            tension: 0.0,
+            sound_pack: None,
+            muted: false,
+        }
+    }
+
+    // A mixer that never touches the audio device, for headless runs.
+    pub fn silent() -> Self {
+        Self {
+            _stream: None,
+            handle: None,
+            voices: Vec::new(),
+            music: None,
+            music_cue: None,
+            master_volume: 1.0,
+            effects_volume: 1.0,
+            music_volume: 1.0,
+            ducked_until: None,
+            tension: 0.0,
+            sound_pack: None,
+            muted: false,
+        }
+    }
+
+    pub fn play(&mut self, event: SoundEvent) {
+        self.play_sequence(&[event]);
+    }
+
+    // Announces the piece that just came out, then the next one
+    pub fn announce(&mut self, piece: BlockType, next: Option<BlockType>) {
+        let mut events = vec![SoundEvent::Piece(piece)];
+        events.extend(next.map(SoundEvent::Piece));
+        self.play_sequence(&events);
+    }
+
+    // Plays the sounds one after another on a single voice, as important as the most important of them
+    fn play_sequence(&mut self, events: &[SoundEvent]) {
+        let Some(handle) = &self.handle else {
+            return;
+        };
+        if self.effects_level() <= 0.0 || self.muted {
+            return;
+        }
+        let priority = events.iter().map(|event| event.priority()).max().unwrap_or(0);
+
+        self.voices.retain(|voice| !voice.sink.empty());
+        if self.voices.len() >= MAX_VOICES {
+            let (index, lowest) = self
+                .voices
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, voice)| voice.priority)
+                .unwrap();
+            if lowest.priority >= priority {
+                return;
+            }
+            self.voices.remove(index).sink.stop();
+        }
+
+        let Ok(sink) = Sink::try_new(handle) else {
+            return;
+        };
+        let mut duration = Duration::ZERO;
+        for (index, &event) in events.iter().enumerate() {
+            if index > 0 {
+                sink.append(note(REST, SEQUENCE_GAP.as_millis() as u64, 0.0));
+                duration += SEQUENCE_GAP;
+            }
+            duration += match self.sound_pack.as_ref().and_then(|pack| pack.sound(event)) {
+                Some(sound) => {
+                    sink.append(sound.clone());
+                    sound.total_duration().unwrap_or_default()
+                }
+                None => {
+                    for &(frequency, ms) in event.notes() {
+                        sink.append(note(frequency, ms, VOICE_AMPLITUDE));
+                    }
+                    event.duration()
+                }
+            };
+        }
+        sink.set_volume(self.effects_level());
+
+        if events.iter().any(|event| event.ducks_music()) {
+            let until = Instant::now() + duration;
+            self.ducked_until = Some(self.ducked_until.map_or(until, |current| current.max(until)));
+        }
+
+        self.voices.push(Voice { sink, priority });
+    }
+
+    // Switches the music over when the cue changes. The old sink stops when it's dropped;
+    // the new one starts at its volume so any ducking carries on smoothly.
+    pub fn set_music(&mut self, cue: MusicCue) {
+        if self.music_cue == Some(cue) {
+            return;
+        }
+        self.music_cue = Some(cue);
+        let Some(handle) = &self.handle else {
+            return;
+        };
+        let Ok(sink) = Sink::try_new(handle) else {
+            return;
+        };
+        sink.set_volume(self.music.as_ref().map_or(self.music_level(), |music| music.volume()));
+        if let Some(source) = cue.source() {
+            sink.append(source);
+        }
+        self.music = Some(sink);
+    }
+
+    fn effects_level(&self) -> f32 {
+        self.master_volume * self.effects_volume
+    }
+
+    fn music_level(&self) -> f32 {
+        self.master_volume * self.music_volume
+    }
+
+    // Called every frame to ease the music volume in and out of ducking, and to
+    // carry volume changes over to the sounds already playing.
+    pub fn update(&mut self) {
+        let effects_level = self.effects_level();
+        for voice in &self.voices {
+            voice.sink.set_volume(effects_level);
+        }
+        let Some(music) = &self.music else {
+            return;
+        };
+
+        let ducked = self.ducked_until.is_some_and(|until| Instant::now() < until);
+        let target = if ducked {
+            self.music_level() * DUCKED_MUSIC_VOLUME
+        } else {
+            self.music_level()
+        };
+        let current = music.volume();
+        music.set_volume(current + (target - current) * DUCK_SMOOTHING);
+
+        let target = 1.0 + MAX_TENSION_SPEEDUP * self.tension;
+        let current = music.speed();
+        music.set_speed(current + (target - current) * TENSION_SMOOTHING);
+    }
+}
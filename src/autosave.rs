@@ -0,0 +1,74 @@
+// Crash recovery. Every few seconds of a run that could be suspended, it's
+// written to autosave.toml the same way "Save & Quit" writes it. The file is
+// removed once the run ends or is left, so one found at launch means the game
+// was closed in the middle of a run, and the start screen offers it back.
+
+use eframe::egui;
+use std::fs;
+use std::time::{Duration, Instant};
+use toml_edit::DocumentMut;
+
+use crate::file_errors::Retry;
+use crate::{data_dir, CrowsTetris, GameState};
+
+pub const AUTOSAVE_FILE: &str = "autosave.toml";
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+pub fn has_interrupted_run() -> bool {
+    data_dir::path(AUTOSAVE_FILE).is_file()
+}
+
+fn remove_autosave() {
+    fs::remove_file(data_dir::path(AUTOSAVE_FILE)).ok();
+}
+
+impl CrowsTetris {
+    // Called every frame; saves the run now and then, and clears the file away once it's over
+    pub(crate) fn autosave(&mut self) {
+        if matches!(self.state, GameState::StartScreen | GameState::GameOver) {
+            if self.autosaved_at.take().is_some() {
+                remove_autosave();
+            }
+            return;
+        }
+        let due = self.autosaved_at.is_none_or(|saved| saved.elapsed() >= AUTOSAVE_INTERVAL);
+        if self.state != GameState::Playing || !due || !self.can_suspend() || self.watching_replay() || self.counting_down() {
+            return;
+        }
+        // Never half a file, even if the crash comes while it's being written
+        let saved = data_dir::write_atomic(AUTOSAVE_FILE, &self.run_snapshot().to_string());
+        self.check_saved(saved, Retry::Autosave);
+        // A new run takes the old one's place
+        self.has_interrupted_run = false;
+        self.autosaved_at = Some(Instant::now());
+    }
+
+    // Picks the interrupted run back up after a countdown; a broken file is let go
+    pub(crate) fn restore_interrupted_run(&mut self) -> bool {
+        let document = fs::read_to_string(data_dir::path(AUTOSAVE_FILE))
+            .ok()
+            .and_then(|text| text.parse::<DocumentMut>().ok());
+        self.has_interrupted_run = false;
+        let restored = document.is_some_and(|document| self.restore_run(&document).is_some());
+        if !restored {
+            remove_autosave();
+        }
+        restored
+    }
+
+    pub(crate) fn render_interrupted_run(&mut self, ui: &mut egui::Ui) {
+        if !self.has_interrupted_run {
+            return;
+        }
+        ui.label(self.tr("The last run was interrupted."));
+        ui.horizontal(|ui| {
+            if ui.button(self.tr("Restore")).clicked() {
+                self.restore_interrupted_run();
+            }
+            if ui.button(self.tr("Discard")).clicked() {
+                remove_autosave();
+                self.has_interrupted_run = false;
+            }
+        });
+    }
+}
@@ -0,0 +1,554 @@
+// Battle modes: the player against rival boards, run by the computer, by a
+// second player on the same keyboard (WASD), by a player online, or, in the
+// battle royale, by a crowd of bots. Line clears send garbage across; garbage waiting to arrive is first cancelled by
+// the receiver's own clears, and whatever is left rises up from the bottom
+// the next time they lock a piece without clearing.
+
+use eframe::egui;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use std::time::Duration;
+
+use crate::garbage::{push_garbage_rows, GARBAGE_CELL};
+use crate::online::Message;
+use crate::profile;
+use crate::randomizer::{PieceGenerator, Randomizer};
+use crate::theme::Theme;
+use crate::{rotate_shape, Block, CrowsTetris, GameMode, Grid, GRID_HEIGHT, GRID_WIDTH};
+
+// How often the computer places a piece
+const CPU_PIECE_INTERVAL: Duration = Duration::from_millis(900);
+const RIVAL_CELL_SIZE: f32 = 4.0;
+pub const ROYALE_BOTS: usize = 24;
+// Range of how often each battle royale bot places a piece, in milliseconds
+const BOT_PIECE_INTERVAL_MS: std::ops::Range<u64> = 700..1800;
+const BOT_CELL_SIZE: f32 = 1.0;
+// Width of the incoming garbage meter beside the board, in points
+pub const GARBAGE_METER_WIDTH: f32 = 6.0;
+const GARBAGE_QUEUED_COLOR: egui::Color32 = egui::Color32::from_rgb(240, 200, 40);
+const GARBAGE_IMMINENT_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 40, 30);
+
+const PLAYER_TWO_LEFT: egui::Key = egui::Key::A;
+const PLAYER_TWO_RIGHT: egui::Key = egui::Key::D;
+const PLAYER_TWO_ROTATE: egui::Key = egui::Key::W;
+const PLAYER_TWO_DROP: egui::Key = egui::Key::S;
+
+// Placement weights: lines cleared are good, height, holes and an uneven surface are bad
+const WEIGHT_LINES: f64 = 0.76;
+const WEIGHT_HEIGHT: f64 = -0.51;
+const WEIGHT_HOLES: f64 = -0.36;
+const WEIGHT_BUMPINESS: f64 = -0.18;
+
+// Garbage rows sent for clearing 0, 1, 2, 3 or 4 lines at once
+pub(crate) fn garbage_for_lines(lines: usize) -> usize {
+    [0, 0, 1, 2, 4][lines.min(4)]
+}
+
+fn fits(grid: &Grid, shape: &[Vec<u8>], (x, y): (i32, i32)) -> bool {
+    shape.iter().enumerate().all(|(dy, row)| {
+        row.iter().enumerate().all(|(dx, &cell)| {
+            let (gx, gy) = (x + dx as i32, y + dy as i32);
+            cell == 0
+                || (gx >= 0
+                    && gx < GRID_WIDTH as i32
+                    && gy < GRID_HEIGHT as i32
+                    && (gy < 0 || grid[gy as usize][gx as usize] == 0))
+        })
+    })
+}
+
+fn place(grid: &mut Grid, shape: &[Vec<u8>], (x, y): (i32, i32), cell: u8) {
+    for (dy, row) in shape.iter().enumerate() {
+        for (dx, &filled) in row.iter().enumerate() {
+            let (gx, gy) = (x + dx as i32, y + dy as i32);
+            if filled != 0 && gy >= 0 {
+                grid[gy as usize][gx as usize] = cell;
+            }
+        }
+    }
+}
+
+fn clear_full_rows(grid: &mut Grid) -> usize {
+    let remaining: Vec<[u8; GRID_WIDTH]> = grid
+        .iter()
+        .filter(|row| row.contains(&0))
+        .copied()
+        .collect();
+    let cleared = GRID_HEIGHT - remaining.len();
+
+    *grid = [[0; GRID_WIDTH]; GRID_HEIGHT];
+    grid[cleared..].copy_from_slice(&remaining);
+    cleared
+}
+
+fn evaluate(grid: &Grid, lines: usize) -> f64 {
+    let heights: Vec<usize> = (0..GRID_WIDTH)
+        .map(|x| {
+            (0..GRID_HEIGHT)
+                .find(|&y| grid[y][x] != 0)
+                .map_or(0, |top| GRID_HEIGHT - top)
+        })
+        .collect();
+    let holes = (0..GRID_WIDTH)
+        .map(|x| {
+            (GRID_HEIGHT - heights[x]..GRID_HEIGHT)
+                .filter(|&y| grid[y][x] == 0)
+                .count()
+        })
+        .sum::<usize>();
+    let bumpiness = heights.windows(2).map(|pair| pair[0].abs_diff(pair[1])).sum::<usize>();
+
+    WEIGHT_LINES * lines as f64
+        + WEIGHT_HEIGHT * heights.iter().sum::<usize>() as f64
+        + WEIGHT_HOLES * holes as f64
+        + WEIGHT_BUMPINESS * bumpiness as f64
+}
+
+// The best resting spot for the piece over every rotation and column, if it fits anywhere
+fn best_placement(grid: &Grid, block: &Block) -> Option<Block> {
+    let mut best: Option<(f64, Block)> = None;
+    let mut shape = block.shape.clone();
+
+    for _ in 0..4 {
+        for x in 0..=(GRID_WIDTH - shape[0].len()) as i32 {
+            if !fits(grid, &shape, (x, 0)) {
+                continue;
+            }
+            let mut y = 0;
+            while fits(grid, &shape, (x, y + 1)) {
+                y += 1;
+            }
+
+            let mut after = *grid;
+            place(&mut after, &shape, (x, y), block.block_type.cell());
+            let lines = clear_full_rows(&mut after);
+            let score = evaluate(&after, lines);
+            if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+                best = Some((
+                    score,
+                    Block {
+                        block_type: block.block_type,
+                        position: (x, y),
+                        shape: shape.clone(),
+                    },
+                ));
+            }
+        }
+        shape = rotate_shape(&shape);
+    }
+
+    best.map(|(_, block)| block)
+}
+
+// Who is playing the other board
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Controller {
+    Cpu,
+    // A second player sharing the keyboard
+    Keyboard,
+    // A player on another machine; their board arrives over the network
+    Remote,
+}
+
+// Who the player's garbage goes to when there are several rivals
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Targeting {
+    Random,
+    HighestStack,
+    LowestStack,
+}
+
+impl Targeting {
+    pub const ALL: [Targeting; 3] = [Targeting::Random, Targeting::HighestStack, Targeting::LowestStack];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Targeting::Random => "Random",
+            Targeting::HighestStack => "Finish off (highest stack)",
+            Targeting::LowestStack => "Leader (lowest stack)",
+        }
+    }
+}
+
+pub struct RivalBoard {
+    controller: Controller,
+    grid: Grid,
+    piece_generator: PieceGenerator,
+    // Seeded from the run's, for the board's pieces and garbage holes
+    rng: StdRng,
+    // The falling piece, for a human rival; the computer places pieces instantly
+    active_block: Option<Block>,
+    pub incoming_garbage: usize,
+    // Play time of the rival's next step: a computer placement or a gravity drop
+    next_step_at: Duration,
+    // How often the computer places a piece
+    piece_interval: Duration,
+    pub lines: u32,
+    pub topped_out: bool,
+    // Where the board finished when it was knocked out of a battle royale
+    placement: Option<usize>,
+}
+
+impl RivalBoard {
+    pub fn new(controller: Controller, randomizer: Randomizer, rng: &mut StdRng) -> Self {
+        let mut board = Self {
+            controller,
+            grid: [[0; GRID_WIDTH]; GRID_HEIGHT],
+            piece_generator: PieceGenerator::new(randomizer),
+            rng: StdRng::seed_from_u64(rng.random()),
+            active_block: None,
+            incoming_garbage: 0,
+            next_step_at: Duration::ZERO,
+            piece_interval: CPU_PIECE_INTERVAL,
+            lines: 0,
+            topped_out: false,
+            placement: None,
+        };
+        if controller == Controller::Keyboard {
+            board.spawn();
+        }
+        board
+    }
+
+    // A battle royale bot, each playing at its own pace
+    fn bot(randomizer: Randomizer, rng: &mut StdRng) -> Self {
+        let mut board = Self::new(Controller::Cpu, randomizer, rng);
+        board.piece_interval = Duration::from_millis(board.rng.random_range(BOT_PIECE_INTERVAL_MS));
+        board
+    }
+
+    fn stack_height(&self) -> usize {
+        GRID_HEIGHT - self.grid.iter().position(|row| row.iter().any(|&cell| cell != 0)).unwrap_or(GRID_HEIGHT)
+    }
+
+    fn spawn(&mut self) {
+        let block = Block::new(self.piece_generator.next(&mut self.rng));
+        if fits(&self.grid, &block.shape, block.position) {
+            self.active_block = Some(block);
+        } else {
+            self.topped_out = true;
+        }
+    }
+
+    // Locks the piece into the board and returns how much garbage it sends back
+    fn lock(&mut self, block: &Block) -> usize {
+        place(&mut self.grid, &block.shape, block.position, block.block_type.cell());
+        let lines = clear_full_rows(&mut self.grid);
+        self.lines += lines as u32;
+
+        let sent = garbage_for_lines(lines);
+        let cancelled = sent.min(self.incoming_garbage);
+        self.incoming_garbage -= cancelled;
+        if lines == 0 {
+            push_garbage_rows(&mut self.grid, self.incoming_garbage, &mut self.rng);
+            self.incoming_garbage = 0;
+        }
+        sent - cancelled
+    }
+
+    pub fn show_remote(&mut self, grid: Grid, lines: u32) {
+        self.grid = grid;
+        self.lines = lines;
+    }
+
+    pub fn set_grid(&mut self, grid: Grid) {
+        self.grid = grid;
+    }
+
+    // Cleared all its garbage; a remote board's news comes as a message instead
+    pub fn dug_out(&self) -> bool {
+        self.controller != Controller::Remote && !self.grid.iter().flatten().any(|&cell| cell == GARBAGE_CELL)
+    }
+
+    fn play_cpu_piece(&mut self) -> usize {
+        let block = Block::new(self.piece_generator.next(&mut self.rng));
+        match best_placement(&self.grid, &block) {
+            Some(placed) => self.lock(&placed),
+            None => {
+                self.topped_out = true;
+                0
+            }
+        }
+    }
+
+    // Moves the human rival's piece if it fits there; returns whether it moved
+    fn try_move(&mut self, shape: Vec<Vec<u8>>, position: (i32, i32)) -> bool {
+        let Some(block) = self.active_block.as_mut() else {
+            return false;
+        };
+        if !fits(&self.grid, &shape, position) {
+            return false;
+        }
+        block.shape = shape;
+        block.position = position;
+        true
+    }
+
+    // One row down, locking the piece (and sending garbage) when it can't fall any further
+    fn drop_human_piece(&mut self) -> usize {
+        let Some(block) = self.active_block.clone() else {
+            return 0;
+        };
+        if self.try_move(block.shape.clone(), (block.position.0, block.position.1 + 1)) {
+            return 0;
+        }
+
+        let sent = self.lock(&block);
+        self.spawn();
+        sent
+    }
+
+    fn handle_keys(&mut self, input: &egui::InputState) -> usize {
+        let Some(block) = self.active_block.clone() else {
+            return 0;
+        };
+        let (x, y) = block.position;
+
+        if input.key_pressed(PLAYER_TWO_LEFT) {
+            self.try_move(block.shape.clone(), (x - 1, y));
+        }
+        if input.key_pressed(PLAYER_TWO_RIGHT) {
+            self.try_move(block.shape.clone(), (x + 1, y));
+        }
+        if input.key_pressed(PLAYER_TWO_ROTATE) {
+            self.try_move(rotate_shape(&block.shape), (x, y));
+        }
+        if input.key_pressed(PLAYER_TWO_DROP) {
+            return self.drop_human_piece();
+        }
+        0
+    }
+
+    fn render(&self, ui: &mut egui::Ui, cell_size: f32, theme: &Theme) {
+        let size = egui::vec2(GRID_WIDTH as f32, GRID_HEIGHT as f32) * cell_size;
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, theme.board);
+
+        let mut grid = self.grid;
+        if let Some(block) = &self.active_block {
+            place(&mut grid, &block.shape, block.position, block.block_type.cell());
+        }
+
+        for (y, row) in grid.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                let Some(color) = theme.cell_color(cell) else {
+                    continue;
+                };
+                let min = rect.min + egui::vec2(x as f32, y as f32) * cell_size;
+                painter.rect_filled(egui::Rect::from_min_size(min, egui::Vec2::splat(cell_size)), 0.0, color);
+            }
+        }
+
+        if let Some(placement) = self.placement {
+            painter.rect_filled(rect, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                format!("#{}", placement),
+                egui::FontId::proportional(rect.height() * 0.6),
+                egui::Color32::WHITE,
+            );
+        }
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn start_rivals(&mut self) {
+        let rng = &mut self.game_rng;
+        self.rivals = match self.mode {
+            GameMode::Battle => vec![RivalBoard::new(Controller::Cpu, self.randomizer, rng)],
+            GameMode::Versus => vec![RivalBoard::new(Controller::Keyboard, self.randomizer, rng)],
+            GameMode::Online => vec![RivalBoard::new(Controller::Remote, self.randomizer, rng)],
+            GameMode::Royale => (0..ROYALE_BOTS).map(|_| RivalBoard::bot(self.randomizer, rng)).collect(),
+            _ => vec![],
+        };
+        self.incoming_garbage = 0;
+    }
+
+    fn alive_rivals(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.rivals.len()).filter(|&i| !self.rivals[i].topped_out)
+    }
+
+    fn pick_target(&mut self) -> Option<usize> {
+        let alive: Vec<usize> = self.alive_rivals().collect();
+        match self.targeting {
+            Targeting::Random => alive.choose(&mut self.game_rng).copied(),
+            Targeting::HighestStack => alive.into_iter().max_by_key(|&i| self.rivals[i].stack_height()),
+            Targeting::LowestStack => alive.into_iter().min_by_key(|&i| self.rivals[i].stack_height()),
+        }
+    }
+
+    // Sends garbage from rival `from` to the player, or in a battle royale to any board still standing
+    fn route_rival_garbage(&mut self, from: usize, rows: usize) {
+        if self.mode != GameMode::Royale {
+            self.incoming_garbage += rows;
+            return;
+        }
+
+        let targets: Vec<usize> = self.alive_rivals().filter(|&i| i != from).collect();
+        // One extra slot for the player
+        let pick = self.game_rng.random_range(0..=targets.len());
+        match targets.get(pick) {
+            Some(&target) => self.rivals[target].incoming_garbage += rows,
+            None => self.incoming_garbage += rows,
+        }
+    }
+
+    // Trades garbage after the player locks a piece that cleared `lines_cleared` lines.
+    pub(crate) fn exchange_garbage(&mut self, lines_cleared: usize) {
+        if self.rivals.is_empty() {
+            return;
+        }
+
+        // A cheese race is a race, not a fight
+        let sent = if self.in_sudden_death() { 0 } else { garbage_for_lines(lines_cleared) };
+        let cancelled = sent.min(self.incoming_garbage);
+        self.incoming_garbage -= cancelled;
+        let remaining = sent - cancelled;
+
+        if lines_cleared == 0 && self.incoming_garbage > 0 {
+            self.add_garbage_rows(self.incoming_garbage);
+            self.incoming_garbage = 0;
+        }
+
+        if self.mode == GameMode::Online {
+            if remaining > 0 {
+                self.send_online(Message::Garbage(remaining));
+            }
+            self.send_online(Message::Board(Box::new(self.grid), self.lines));
+        } else if let Some(target) = self.pick_target() {
+            self.rivals[target].incoming_garbage += remaining;
+        }
+    }
+
+    // Runs the rival boards for this frame, catching up on steps missed since the last one.
+    pub(crate) fn update_rivals(&mut self, ctx: &egui::Context) {
+        let now = self.play_time();
+
+        for i in 0..self.rivals.len() {
+            let rival = &mut self.rivals[i];
+            if rival.topped_out {
+                continue;
+            }
+
+            let mut sent = 0;
+            let interval = match rival.controller {
+                Controller::Cpu => rival.piece_interval,
+                Controller::Keyboard => {
+                    sent += ctx.input(|input| rival.handle_keys(input));
+                    self.drop_speed
+                }
+                // The remote board only changes when a network message arrives
+                Controller::Remote => continue,
+            };
+
+            while now >= rival.next_step_at + interval && !rival.topped_out {
+                rival.next_step_at += interval;
+                sent += match rival.controller {
+                    Controller::Cpu => rival.play_cpu_piece(),
+                    Controller::Keyboard | Controller::Remote => rival.drop_human_piece(),
+                };
+            }
+            if sent > 0 && !self.in_sudden_death() {
+                self.route_rival_garbage(i, sent);
+            }
+
+            if self.rivals[i].topped_out {
+                // Finished behind every board still standing, the player included
+                self.rivals[i].placement = Some(self.alive_rivals().count() + 2);
+            }
+        }
+
+        if self.rivals_defeated() || self.rival_won_cheese_race() {
+            self.end_run();
+        }
+    }
+
+    pub(crate) fn render_rivals(&mut self, ui: &mut egui::Ui) {
+        if self.mode == GameMode::Royale {
+            self.render_royale(ui);
+            return;
+        }
+        let Some(rival) = self.rivals.first() else {
+            return;
+        };
+
+        let lines_label = match rival.controller {
+            Controller::Cpu => "CPU lines:",
+            Controller::Keyboard => "Player 2 lines:",
+            Controller::Remote => "Opponent lines:",
+        };
+        let player = match rival.controller {
+            Controller::Cpu => None,
+            Controller::Keyboard => self.player_two_profile(),
+            Controller::Remote => self.opponent(),
+        };
+        ui.horizontal(|ui| {
+            rival.render(ui, RIVAL_CELL_SIZE, self.theme());
+            ui.vertical(|ui| {
+                if let Some(player) = player {
+                    profile::draw_player(ui, player);
+                }
+                ui.label(format!("{} {}", self.tr(lines_label), rival.lines));
+                ui.label(format!("{} {}", self.tr("Incoming garbage:"), self.incoming_garbage));
+                if rival.controller == Controller::Keyboard {
+                    ui.label(self.tr("Player 2: W rotate, A/D move, S drop"));
+                }
+            });
+        });
+    }
+
+    fn render_royale(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!("{} {}", self.tr("Players left:"), self.placement()));
+            ui.label(format!("{} {}", self.tr("Incoming garbage:"), self.incoming_garbage));
+        });
+
+        let language = self.language;
+        egui::ComboBox::from_label(language.tr("Target"))
+            .selected_text(language.tr(self.targeting.label()))
+            .show_ui(ui, |ui| {
+                for targeting in Targeting::ALL {
+                    ui.selectable_value(&mut self.targeting, targeting, language.tr(targeting.label()));
+                }
+            });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing = egui::vec2(2.0, 2.0);
+            for rival in &self.rivals {
+                rival.render(ui, BOT_CELL_SIZE, self.theme());
+            }
+        });
+    }
+
+    pub(crate) fn garbage_meter_shown(&self) -> bool {
+        !self.rivals.is_empty()
+    }
+
+    // One segment per queued row, from the bottom. Garbage rises when the current piece locks
+    // without clearing, so the meter goes from yellow to red as the piece's lock delay runs out.
+    pub(crate) fn draw_garbage_meter(&self, painter: &egui::Painter, meter: egui::Rect, cell_size: f32) {
+        painter.rect_filled(meter, 1.0, egui::Color32::from_black_alpha(120));
+        let urgency = self.landed_at.map_or(0.0, |landed_at| {
+            (landed_at.elapsed().as_secs_f32() / self.lock_delay().as_secs_f32()).min(1.0)
+        });
+        let color = GARBAGE_QUEUED_COLOR.lerp_to_gamma(GARBAGE_IMMINENT_COLOR, urgency);
+        for row in 0..self.incoming_garbage.min(GRID_HEIGHT) {
+            let bottom = meter.max.y - row as f32 * cell_size;
+            let segment = egui::Rect::from_min_max(egui::pos2(meter.min.x, bottom - cell_size), egui::pos2(meter.max.x, bottom));
+            painter.rect_filled(segment.shrink(1.0), 1.0, color);
+        }
+    }
+
+    // Whether every rival has topped out, which wins the battle for the player
+    pub(crate) fn rivals_defeated(&self) -> bool {
+        !self.rivals.is_empty() && self.rivals.iter().all(|rival| rival.topped_out)
+    }
+
+    // The player's standing: one ahead of everyone already knocked out
+    pub(crate) fn placement(&self) -> usize {
+        self.alive_rivals().count() + 1
+    }
+}
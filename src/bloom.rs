@@ -0,0 +1,168 @@
+// Fancy graphics: a soft glow around the falling piece and clearing rows, drawn
+// with a custom OpenGL paint callback that adds blurred light on top of the
+// board. Only available when eframe runs on its glow (OpenGL) backend.
+
+use eframe::egui::{self, Color32};
+use eframe::egui_glow;
+use eframe::glow::{self, HasContext};
+use std::sync::Arc;
+
+use crate::CrowsTetris;
+
+// How far the light spreads past a cell, in cells
+pub const GLOW_RADIUS: f32 = 1.4;
+
+const VERTEX_SHADER: &str = r#"
+    layout(location = 0) in vec2 a_position;
+    layout(location = 1) in vec2 a_offset;
+    layout(location = 2) in vec4 a_color;
+    out vec2 v_offset;
+    out vec4 v_color;
+    void main() {
+        v_offset = a_offset;
+        v_color = a_color;
+        gl_Position = vec4(a_position, 0.0, 1.0);
+    }
+"#;
+
+// Gaussian falloff from the middle of each sprite
+const FRAGMENT_SHADER: &str = r#"
+    precision mediump float;
+    in vec2 v_offset;
+    in vec4 v_color;
+    out vec4 out_color;
+    void main() {
+        float light = exp(-dot(v_offset, v_offset) * 4.0);
+        out_color = v_color * light;
+    }
+"#;
+
+// Floats per vertex: position, offset from the sprite's middle, color
+const VERTEX_FLOATS: usize = 8;
+
+// A glowing spot over the board, in points
+#[derive(Clone, Copy)]
+pub struct GlowSprite {
+    pub center: egui::Pos2,
+    pub radius: f32,
+    pub color: Color32,
+}
+
+// The GL objects are plain handles, so the renderer can be copied into every frame's callback
+#[derive(Clone, Copy)]
+pub struct GlowRenderer {
+    program: glow::Program,
+    vertex_array: glow::VertexArray,
+    buffer: glow::Buffer,
+}
+
+impl GlowRenderer {
+    pub fn new(gl: &glow::Context) -> Option<Self> {
+        let version = if cfg!(target_arch = "wasm32") { "#version 300 es" } else { "#version 330" };
+        unsafe {
+            let program = gl.create_program().ok()?;
+            let mut shaders = Vec::new();
+            for (kind, source) in [(glow::VERTEX_SHADER, VERTEX_SHADER), (glow::FRAGMENT_SHADER, FRAGMENT_SHADER)] {
+                let shader = gl.create_shader(kind).ok()?;
+                gl.shader_source(shader, &format!("{}\n{}", version, source));
+                gl.compile_shader(shader);
+                if !gl.get_shader_compile_status(shader) {
+                    return None;
+                }
+                gl.attach_shader(program, shader);
+                shaders.push(shader);
+            }
+            gl.link_program(program);
+            for shader in shaders {
+                gl.detach_shader(program, shader);
+                gl.delete_shader(shader);
+            }
+            if !gl.get_program_link_status(program) {
+                return None;
+            }
+
+            let vertex_array = gl.create_vertex_array().ok()?;
+            let buffer = gl.create_buffer().ok()?;
+            gl.bind_vertex_array(Some(vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(buffer));
+            let stride = (VERTEX_FLOATS * size_of::<f32>()) as i32;
+            for (location, size, offset) in [(0, 2, 0), (1, 2, 2), (2, 4, 4)] {
+                gl.enable_vertex_attrib_array(location);
+                gl.vertex_attrib_pointer_f32(location, size, glow::FLOAT, false, stride, offset * size_of::<f32>() as i32);
+            }
+            gl.bind_vertex_array(None);
+
+            Some(Self {
+                program,
+                vertex_array,
+                buffer,
+            })
+        }
+    }
+
+    pub fn destroy(&self, gl: &glow::Context) {
+        unsafe {
+            gl.delete_program(self.program);
+            gl.delete_vertex_array(self.vertex_array);
+            gl.delete_buffer(self.buffer);
+        }
+    }
+
+    // `vertices` are already in the callback viewport's clip space
+    fn paint(&self, gl: &glow::Context, vertices: &[f32]) {
+        unsafe {
+            gl.use_program(Some(self.program));
+            gl.bind_vertex_array(Some(self.vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.buffer));
+            let bytes = std::slice::from_raw_parts(vertices.as_ptr().cast::<u8>(), size_of_val(vertices));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, bytes, glow::STREAM_DRAW);
+            // Light adds up where sprites overlap
+            gl.enable(glow::BLEND);
+            gl.blend_func(glow::ONE, glow::ONE);
+            gl.draw_arrays(glow::TRIANGLES, 0, (vertices.len() / VERTEX_FLOATS) as i32);
+            gl.bind_vertex_array(None);
+        }
+    }
+}
+
+// Two triangles per sprite, mapped from points in `board` to clip space
+fn sprite_vertices(board: egui::Rect, sprites: &[GlowSprite]) -> Vec<f32> {
+    let to_clip = |point: egui::Pos2| {
+        [
+            (point.x - board.min.x) / board.width() * 2.0 - 1.0,
+            1.0 - (point.y - board.min.y) / board.height() * 2.0,
+        ]
+    };
+    let mut vertices = Vec::with_capacity(sprites.len() * 6 * VERTEX_FLOATS);
+    for sprite in sprites {
+        let color = sprite.color.to_array().map(|channel| channel as f32 / 255.0);
+        for (dx, dy) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            let [x, y] = to_clip(sprite.center + egui::vec2(dx, dy) * sprite.radius);
+            vertices.extend_from_slice(&[x, y, dx, dy]);
+            vertices.extend_from_slice(&color);
+        }
+    }
+    vertices
+}
+
+impl CrowsTetris {
+    pub(crate) fn fancy_graphics_available(&self) -> bool {
+        self.glow_renderer.is_some()
+    }
+
+    pub(crate) fn paint_glow(&self, painter: &egui::Painter, board: egui::Rect, sprites: &[GlowSprite]) {
+        let Some(renderer) = self.glow_renderer.filter(|_| self.fancy_graphics) else {
+            return;
+        };
+        if sprites.is_empty() {
+            return;
+        }
+        let vertices = sprite_vertices(board, sprites);
+        painter.add(egui::PaintCallback {
+            rect: board,
+            callback: Arc::new(egui_glow::CallbackFn::new(move |_info, painter| {
+                renderer.paint(painter.gl(), &vertices);
+            })),
+        });
+    }
+}
@@ -0,0 +1,61 @@
+// Plain-text board format for bug reports and sharing: one line per row, one
+// letter per cell (I, O, T, S, Z, J, L, or G for garbage) and '.' for empty
+// cells. Pasted boards may omit empty rows at the top and trailing empty cells
+// on each row.
+
+use crate::garbage::GARBAGE_CELL;
+use crate::{BlockType, Grid, GRID_HEIGHT, GRID_WIDTH};
+
+pub fn board_to_ascii(grid: &Grid) -> String {
+    let first_used_row = grid
+        .iter()
+        .position(|row| row.iter().any(|&cell| cell != 0))
+        .unwrap_or(GRID_HEIGHT - 1);
+
+    grid[first_used_row..]
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&cell| match cell {
+                    0 => '.',
+                    GARBAGE_CELL => 'G',
+                    _ => BlockType::from_cell(cell).map_or('.', BlockType::letter),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn board_from_ascii(text: &str) -> Result<Grid, String> {
+    let rows: Vec<&str> = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    if rows.len() > GRID_HEIGHT {
+        return Err(format!("Board has {} rows, the most allowed is {}", rows.len(), GRID_HEIGHT));
+    }
+
+    let mut grid = [[0; GRID_WIDTH]; GRID_HEIGHT];
+    let top = GRID_HEIGHT - rows.len();
+
+    for (i, row) in rows.iter().enumerate() {
+        if row.chars().count() > GRID_WIDTH {
+            return Err(format!("Row {} is wider than {} cells", i + 1, GRID_WIDTH));
+        }
+
+        for (x, letter) in row.chars().enumerate() {
+            grid[top + i][x] = match letter {
+                '.' | ' ' | '0' => 0,
+                'G' | 'g' | '#' => GARBAGE_CELL,
+                _ => BlockType::from_letter(letter)
+                    .ok_or_else(|| format!("Unknown cell '{}' on row {}", letter, i + 1))?
+                    .cell(),
+            };
+        }
+    }
+
+    Ok(grid)
+}
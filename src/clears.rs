@@ -0,0 +1,113 @@
+// Classifies each locked piece's clear: how many lines, whether it was a
+// T-spin, and how long the current combo of back-to-back clearing pieces is.
+// Modes that care about the kind of clear listen to these events.
+
+use eframe::egui;
+
+use crate::{Block, BlockType, CrowsTetris, GRID_HEIGHT, GRID_WIDTH};
+
+// The combo meter is full at this many clearing pieces in a row
+const COMBO_METER_MAX: f32 = 10.0;
+// The meter's color ramps through these as the combo grows
+const COMBO_COLORS: [egui::Color32; 3] = [
+    egui::Color32::from_rgb(250, 230, 90),
+    egui::Color32::from_rgb(250, 140, 30),
+    egui::Color32::from_rgb(240, 40, 90),
+];
+// How fast the meter catches up with the combo, per second: quick to fill, slow to drain
+const COMBO_FILL_RATE: f32 = 12.0;
+const COMBO_DRAIN_RATE: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ClearEvent {
+    pub lines: usize,
+    pub t_spin: bool,
+    // Number of pieces in a row that have cleared lines, including this one
+    pub combo: u32,
+}
+
+impl CrowsTetris {
+    // A T piece whose last move was a rotation and that has at least three of the
+    // four cells diagonal to its center blocked.
+    pub(crate) fn is_t_spin(&self, block: &Block) -> bool {
+        if block.block_type != BlockType::T || !self.last_move_rotated {
+            return false;
+        }
+
+        let filled = |x: i32, y: i32| {
+            y >= 0
+                && (y as usize) < block.shape.len()
+                && x >= 0
+                && (x as usize) < block.shape[y as usize].len()
+                && block.shape[y as usize][x as usize] != 0
+        };
+        // The center is the only cell of a T with three neighbours in the piece
+        let center = (0..block.shape.len() as i32)
+            .flat_map(|y| (0..block.shape[0].len() as i32).map(move |x| (x, y)))
+            .find(|&(x, y)| {
+                filled(x, y)
+                    && [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                        .iter()
+                        .filter(|&&(nx, ny)| filled(nx, ny))
+                        .count()
+                        == 3
+            });
+        let Some((cx, cy)) = center else {
+            return false;
+        };
+
+        let (cx, cy) = (block.position.0 + cx, block.position.1 + cy);
+        let blocked = |x: i32, y: i32| {
+            x < 0
+                || x >= GRID_WIDTH as i32
+                || y >= GRID_HEIGHT as i32
+                || (y >= 0 && self.grid[y as usize][x as usize] != 0)
+        };
+        [(cx - 1, cy - 1), (cx + 1, cy - 1), (cx - 1, cy + 1), (cx + 1, cy + 1)]
+            .iter()
+            .filter(|&&(x, y)| blocked(x, y))
+            .count()
+            >= 3
+    }
+
+    // Eases the meter toward the current combo; returns whether it's still moving
+    pub(crate) fn update_combo_meter(&mut self, dt: f32) -> bool {
+        let target = self.combo as f32;
+        let rate = if target > self.combo_meter { COMBO_FILL_RATE } else { COMBO_DRAIN_RATE };
+        self.combo_meter += (target - self.combo_meter) * (rate * dt).min(1.0);
+        if (target - self.combo_meter).abs() < 0.01 {
+            self.combo_meter = target;
+        }
+        self.combo_meter != target
+    }
+
+    pub(crate) fn render_combo_meter(&self, ui: &mut egui::Ui) {
+        if self.combo_meter < 0.05 {
+            return;
+        }
+        let fill = (self.combo_meter / COMBO_METER_MAX).min(1.0);
+        let ramp = fill * (COMBO_COLORS.len() - 1) as f32;
+        let step = (ramp as usize).min(COMBO_COLORS.len() - 2);
+        let color = COMBO_COLORS[step].lerp_to_gamma(COMBO_COLORS[step + 1], ramp - step as f32);
+
+        // Only a live combo gets its count; a broken one just drains away
+        if self.combo > 0 {
+            ui.label(egui::RichText::new(format!("{} x{}", self.tr("Combo"), self.combo)).strong().color(color));
+        }
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 8.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, egui::Color32::from_black_alpha(120));
+        painter.rect_filled(rect.with_max_x(rect.min.x + rect.width() * fill), 2.0, color);
+    }
+
+    pub(crate) fn record_clear(&mut self, lines: usize, t_spin: bool) -> ClearEvent {
+        self.combo = if lines > 0 { self.combo + 1 } else { 0 };
+        self.tetrises += (lines >= 4) as u32;
+        self.t_spins += t_spin as u32;
+        ClearEvent {
+            lines,
+            t_spin,
+            combo: self.combo,
+        }
+    }
+}
@@ -0,0 +1,292 @@
+// What config.toml holds: every setting, in a table per part of the settings
+// screen, and the key bindings of each input device. Entries that are missing
+// take their defaults, and numbers out of range are pulled back into range.
+// A file that isn't TOML, or has a value of the wrong kind, can't be used at
+// all; the settings stay as they are and the error is shown. The
+// settings.txt, keybindings.txt and control_profiles.txt of older versions
+// are read if config.toml doesn't have their part yet.
+
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use toml_edit::{DocumentMut, Item, Value};
+
+use crate::controls::{self, SavedBindings};
+use crate::gamepad::{MAX_DEADZONE, MIN_DEADZONE};
+use crate::handling::{MAX_ARR_MS, MAX_DAS_MS, MAX_SOFT_DROP_FACTOR, MIN_SOFT_DROP_FACTOR};
+use crate::i18n::Language;
+use crate::music::MusicTrack;
+use crate::one_switch::{MAX_SCAN_INTERVAL_MS, MIN_SCAN_INTERVAL_MS};
+use crate::settings::{MAX_DROP_SPEED_MS, MAX_UI_SCALE, MIN_DROP_SPEED_MS, MIN_UI_SCALE};
+use crate::theme::CellStyle;
+use crate::{data_dir, BackgroundBehavior, GameMode, DESIGN_WINDOW_SIZE, MIN_WINDOW_SIZE};
+
+pub const CONFIG_FILE: &str = "config.toml";
+pub const OLD_SETTINGS_FILE: &str = "settings.txt";
+
+// Goes through the f32's shortest text so 0.3 is saved as 0.3, not 0.30000001192092896
+fn short_float<S: Serializer>(number: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(number.to_string().parse().unwrap_or_default())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub language: Language,
+    pub gameplay: GameplayConfig,
+    pub controls: ControlsConfig,
+    pub audio: AudioConfig,
+    pub display: DisplayConfig,
+    pub window: WindowConfig,
+    // Each action's binding ("key:<name>" or "button:<name>") by device ("keyboard" or "gamepad:<id>")
+    pub keybindings: SavedBindings,
+    // In the order they were saved; none until the first is saved or brought over
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_profiles: Option<Vec<ControlProfile>>,
+}
+
+// Key bindings saved under a name to switch back to later
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlProfile {
+    pub name: String,
+    pub keybindings: SavedBindings,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplayConfig {
+    pub mode: GameMode,
+    pub drop_speed_ms: u64,
+    pub show_trajectory: bool,
+    pub show_live_stats: bool,
+    pub focus_mode: bool,
+    pub background_behavior: BackgroundBehavior,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_profile: Option<String>,
+    pub das_ms: u64,
+    pub arr_ms: u64,
+    pub soft_drop_factor: u32,
+    #[serde(serialize_with = "short_float")]
+    pub gamepad_deadzone: f32,
+    pub rumble: bool,
+    pub touch_controls: bool,
+    pub mouse_controls: bool,
+    pub one_switch: bool,
+    pub scan_interval_ms: u64,
+    pub show_input_display: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    #[serde(serialize_with = "short_float")]
+    pub master_volume: f32,
+    #[serde(serialize_with = "short_float")]
+    pub effects_volume: f32,
+    #[serde(serialize_with = "short_float")]
+    pub music_volume: f32,
+    pub announce_pieces: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sound_pack: Option<String>,
+    pub menu_music: MusicTrack,
+    pub gameplay_music: MusicTrack,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    // The first theme when there's none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skin: Option<String>,
+    pub cell_style: CellStyle,
+    pub show_grid_lines: bool,
+    pub screen_shake: bool,
+    pub fancy_graphics: bool,
+    #[serde(serialize_with = "short_float")]
+    pub ui_scale: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub width: u32,
+    pub height: u32,
+    // Left to the system when there's none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+    pub fullscreen: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            language: Language::detect(),
+            gameplay: GameplayConfig::default(),
+            controls: ControlsConfig::default(),
+            audio: AudioConfig::default(),
+            display: DisplayConfig::default(),
+            window: WindowConfig::default(),
+            keybindings: BTreeMap::new(),
+            control_profiles: None,
+        }
+    }
+}
+
+impl Default for GameplayConfig {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::Marathon,
+            drop_speed_ms: 125,
+            show_trajectory: false,
+            show_live_stats: true,
+            focus_mode: false,
+            background_behavior: BackgroundBehavior::Pause,
+        }
+    }
+}
+
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        Self {
+            control_profile: None,
+            das_ms: 170,
+            arr_ms: 50,
+            soft_drop_factor: 20,
+            gamepad_deadzone: 0.5,
+            rumble: true,
+            touch_controls: false,
+            mouse_controls: false,
+            one_switch: false,
+            scan_interval_ms: 800,
+            show_input_display: false,
+        }
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            effects_volume: 1.0,
+            music_volume: 1.0,
+            announce_pieces: false,
+            sound_pack: None,
+            menu_music: MusicTrack::Roost,
+            gameplay_music: MusicTrack::Korobeiniki,
+        }
+    }
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            skin: None,
+            cell_style: CellStyle::Rounded,
+            show_grid_lines: true,
+            screen_shake: true,
+            fancy_graphics: false,
+            ui_scale: 1.0,
+        }
+    }
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: DESIGN_WINDOW_SIZE.x as u32,
+            height: DESIGN_WINDOW_SIZE.y as u32,
+            x: None,
+            y: None,
+            fullscreen: false,
+        }
+    }
+}
+
+impl Config {
+    // config.toml, or the old settings.txt if there's no config.toml yet
+    pub fn load() -> Result<Config, String> {
+        let mut config = match fs::read_to_string(data_dir::path(CONFIG_FILE)) {
+            Ok(text) => toml_edit::de::from_str(&text).map_err(|error| format!("{}: {}", CONFIG_FILE, error))?,
+            Err(_) => fs::read_to_string(data_dir::path(OLD_SETTINGS_FILE))
+                .map(|text| from_old_settings(&text))
+                .unwrap_or_default(),
+        };
+        config.validate();
+        Ok(config)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let text = toml_edit::ser::to_string_pretty(self).map_err(io::Error::other)?;
+        data_dir::write_atomic(CONFIG_FILE, &text)
+    }
+
+    // Pulls numbers the game can't use back into range
+    pub fn validate(&mut self) {
+        let gameplay = &mut self.gameplay;
+        gameplay.drop_speed_ms = gameplay.drop_speed_ms.clamp(MIN_DROP_SPEED_MS, MAX_DROP_SPEED_MS);
+
+        let controls = &mut self.controls;
+        controls.das_ms = controls.das_ms.min(MAX_DAS_MS);
+        controls.arr_ms = controls.arr_ms.min(MAX_ARR_MS);
+        controls.soft_drop_factor = controls.soft_drop_factor.clamp(MIN_SOFT_DROP_FACTOR, MAX_SOFT_DROP_FACTOR);
+        controls.gamepad_deadzone = controls.gamepad_deadzone.clamp(MIN_DEADZONE, MAX_DEADZONE);
+        controls.scan_interval_ms = controls.scan_interval_ms.clamp(MIN_SCAN_INTERVAL_MS, MAX_SCAN_INTERVAL_MS);
+
+        let audio = &mut self.audio;
+        for volume in [&mut audio.master_volume, &mut audio.effects_volume, &mut audio.music_volume] {
+            *volume = volume.clamp(0.0, 1.0);
+        }
+
+        self.display.ui_scale = self.display.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+
+        let window = &mut self.window;
+        window.width = window.width.max(MIN_WINDOW_SIZE.x as u32);
+        window.height = window.height.max(MIN_WINDOW_SIZE.y as u32);
+
+        controls::validate_bindings(&mut self.keybindings);
+        // A profile needs a name of its own, one that isn't a preset's
+        if let Some(profiles) = &mut self.control_profiles {
+            let mut names = Vec::new();
+            profiles.retain_mut(|profile| {
+                profile.name = profile.name.trim().to_string();
+                controls::validate_bindings(&mut profile.keybindings);
+                let keep = !profile.name.is_empty()
+                    && !controls::PRESETS.contains(&profile.name.as_str())
+                    && !names.contains(&profile.name);
+                names.push(profile.name.clone());
+                keep
+            });
+        }
+    }
+}
+
+// settings.txt had a key=value line per setting, with no tables. Each key goes in the table
+// config.toml has it in; a value that isn't a TOML number or true/false is a name.
+fn from_old_settings(text: &str) -> Config {
+    let Ok(template) = toml_edit::ser::to_document(&Config::default()) else {
+        return Config::default();
+    };
+    let mut document = DocumentMut::new();
+    for (key, text) in text.lines().filter_map(|line| line.split_once('=')) {
+        let value = text.parse::<Value>().unwrap_or_else(|_| Value::from(text));
+        let table = template.iter().find(|(_, item)| item.as_table_like().is_some_and(|table| table.contains_key(key)));
+        match table {
+            Some((table, _)) => {
+                document.entry(table).or_insert(toml_edit::table())[key] = Item::Value(value);
+            }
+            None => document[key] = Item::Value(value),
+        }
+    }
+    toml_edit::de::from_document(document).unwrap_or_default()
+}
@@ -0,0 +1,80 @@
+// Asks before throwing away a run in progress: giving up, or quitting the
+// game in the middle of one. The run is paused while the question is up.
+
+use eframe::egui;
+
+use crate::{CrowsTetris, GameState};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Confirm {
+    Abandon,
+    Quit,
+}
+
+impl Confirm {
+    fn question(self) -> &'static str {
+        match self {
+            Confirm::Abandon => "Abandon current game?",
+            Confirm::Quit => "Quit the game? The current run will be lost.",
+        }
+    }
+}
+
+// Closing from inside the frame would deadlock the viewport, so the command is sent from another thread
+fn close_window(ctx: &egui::Context) {
+    let ctx = ctx.clone();
+    std::thread::spawn(move || {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    });
+}
+
+impl CrowsTetris {
+    // Does it straight away when there's no run to lose
+    pub(crate) fn ask(&mut self, ctx: &egui::Context, confirm: Confirm) {
+        if self.state != GameState::Playing {
+            self.confirmed(ctx, confirm);
+            return;
+        }
+        self.confirming = Some((confirm, self.is_paused));
+        self.set_paused(true);
+    }
+
+    fn confirmed(&mut self, ctx: &egui::Context, confirm: Confirm) {
+        match confirm {
+            Confirm::Abandon => self.end_run(),
+            Confirm::Quit => close_window(ctx),
+        }
+    }
+
+    // Enter agrees and Escape backs out; both are used up so the screen underneath doesn't see them
+    pub(crate) fn render_confirmation(&mut self, ctx: &egui::Context) {
+        let Some((confirm, was_paused)) = self.confirming else {
+            return;
+        };
+        let (mut yes, mut no) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        egui::Modal::new(egui::Id::new("confirm")).show(ctx, |ui| {
+            ui.set_width(260.0);
+            ui.vertical_centered(|ui| {
+                ui.heading(self.tr(confirm.question()));
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    yes |= ui.button(self.tr("Yes")).clicked();
+                    no |= ui.button(self.tr("No")).clicked();
+                });
+            });
+        });
+
+        if yes {
+            self.confirming = None;
+            self.confirmed(ctx, confirm);
+        } else if no {
+            self.confirming = None;
+            self.set_paused(was_paused);
+        }
+    }
+}
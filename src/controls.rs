@@ -0,0 +1,547 @@
+// Game actions and the per-device bindings that trigger them. Each keyboard
+// or gamepad keeps its own binding set, and whichever device was used last
+// is the active one. The whole lot can be saved under a name and switched
+// back to later, so people sharing a machine keep their own keys. The
+// bindings and the named profiles are saved in config.toml; older versions
+// kept them in keybindings.txt and control_profiles.txt, which are read if
+// config.toml doesn't have them yet.
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{self, BufRead};
+
+use crate::config::{Config, ControlProfile};
+use crate::data_dir;
+use crate::file_errors::Retry;
+
+pub const OLD_KEYBINDINGS_FILE: &str = "keybindings.txt";
+pub const OLD_CONTROL_PROFILES_FILE: &str = "control_profiles.txt";
+// Built-in keyboard layouts, offered next to the saved profiles
+pub const PRESETS: [&str; 6] = [
+    "Guideline",
+    "Arrows only",
+    "Left-handed",
+    "One hand (left side)",
+    "One hand (right side)",
+    "Vim",
+];
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    // Clockwise; the name is kept so older binding files still load
+    Rotate,
+    RotateCounterclockwise,
+    SoftDrop,
+    HardDrop,
+    Hold,
+    Pause,
+    UseItem,
+    Abandon,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 11] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Rotate,
+        Action::RotateCounterclockwise,
+        Action::SoftDrop,
+        Action::HardDrop,
+        Action::Hold,
+        Action::Pause,
+        Action::UseItem,
+        Action::Abandon,
+        Action::Quit,
+    ];
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|action| format!("{:?}", action) == name)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Rotate => "Rotate clockwise",
+            Action::RotateCounterclockwise => "Rotate counterclockwise",
+            Action::SoftDrop => "Soft drop",
+            Action::HardDrop => "Hard drop",
+            Action::Hold => "Hold",
+            Action::Pause => "Pause",
+            Action::UseItem => "Use item",
+            Action::Abandon => "Give up",
+            Action::Quit => "Quit game",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum InputDevice {
+    Keyboard,
+    Gamepad(String),
+}
+
+impl InputDevice {
+    fn id(&self) -> String {
+        match self {
+            InputDevice::Keyboard => "keyboard".to_string(),
+            InputDevice::Gamepad(id) => format!("gamepad:{}", id),
+        }
+    }
+
+    fn from_id(id: &str) -> Option<InputDevice> {
+        match id {
+            "keyboard" => Some(InputDevice::Keyboard),
+            _ => id
+                .strip_prefix("gamepad:")
+                .map(|id| InputDevice::Gamepad(id.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Binding {
+    Key(egui::Key),
+    Button(String),
+}
+
+impl Binding {
+    fn encode(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("key:{}", key.name()),
+            Binding::Button(button) => format!("button:{}", button),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Key(key) => key.name().to_string(),
+            Binding::Button(button) => button.clone(),
+        }
+    }
+
+    fn decode(text: &str) -> Option<Binding> {
+        if let Some(key) = text.strip_prefix("key:") {
+            egui::Key::from_name(key).map(Binding::Key)
+        } else {
+            text.strip_prefix("button:")
+                .map(|button| Binding::Button(button.to_string()))
+        }
+    }
+}
+
+pub type Bindings = HashMap<Action, Binding>;
+
+fn default_bindings(device: &InputDevice) -> Bindings {
+    match device {
+        InputDevice::Keyboard => HashMap::from([
+            (Action::MoveLeft, Binding::Key(egui::Key::ArrowLeft)),
+            (Action::MoveRight, Binding::Key(egui::Key::ArrowRight)),
+            (Action::Rotate, Binding::Key(egui::Key::ArrowUp)),
+            (Action::RotateCounterclockwise, Binding::Key(egui::Key::Z)),
+            (Action::SoftDrop, Binding::Key(egui::Key::ArrowDown)),
+            (Action::HardDrop, Binding::Key(egui::Key::Enter)),
+            (Action::Hold, Binding::Key(egui::Key::C)),
+            (Action::Pause, Binding::Key(egui::Key::Space)),
+            (Action::UseItem, Binding::Key(egui::Key::E)),
+            (Action::Abandon, Binding::Key(egui::Key::Escape)),
+            (Action::Quit, Binding::Key(egui::Key::Q)),
+        ]),
+        InputDevice::Gamepad(_) => HashMap::from([
+            (Action::MoveLeft, Binding::Button("DPadLeft".to_string())),
+            (Action::MoveRight, Binding::Button("DPadRight".to_string())),
+            (Action::Rotate, Binding::Button("South".to_string())),
+            (Action::RotateCounterclockwise, Binding::Button("East".to_string())),
+            (Action::SoftDrop, Binding::Button("DPadDown".to_string())),
+            (Action::HardDrop, Binding::Button("DPadUp".to_string())),
+            (Action::Hold, Binding::Button("West".to_string())),
+            (Action::Pause, Binding::Button("Start".to_string())),
+            (Action::UseItem, Binding::Button("North".to_string())),
+            (Action::Abandon, Binding::Button("Select".to_string())),
+            (Action::Quit, Binding::Button("Mode".to_string())),
+        ]),
+    }
+}
+
+fn preset_bindings(name: &str) -> Option<Bindings> {
+    let keys = match name {
+        "Arrows only" => return Some(default_bindings(&InputDevice::Keyboard)),
+        "Guideline" => [
+            egui::Key::ArrowLeft,
+            egui::Key::ArrowRight,
+            egui::Key::X,
+            egui::Key::Z,
+            egui::Key::ArrowDown,
+            egui::Key::Space,
+            egui::Key::C,
+            egui::Key::P,
+            egui::Key::E,
+            egui::Key::Escape,
+            egui::Key::Q,
+        ],
+        "Left-handed" => [
+            egui::Key::A,
+            egui::Key::D,
+            egui::Key::W,
+            egui::Key::Q,
+            egui::Key::S,
+            egui::Key::Space,
+            egui::Key::F,
+            egui::Key::P,
+            egui::Key::R,
+            egui::Key::Escape,
+            egui::Key::F10,
+        ],
+        // Everything in reach of one hand resting on the home row, the thumb on space
+        "One hand (left side)" => [
+            egui::Key::S,
+            egui::Key::F,
+            egui::Key::E,
+            egui::Key::W,
+            egui::Key::D,
+            egui::Key::Space,
+            egui::Key::A,
+            egui::Key::Tab,
+            egui::Key::R,
+            egui::Key::Escape,
+            egui::Key::Q,
+        ],
+        "One hand (right side)" => [
+            egui::Key::J,
+            egui::Key::L,
+            egui::Key::I,
+            egui::Key::U,
+            egui::Key::K,
+            egui::Key::Space,
+            egui::Key::Semicolon,
+            egui::Key::P,
+            egui::Key::O,
+            egui::Key::Backspace,
+            egui::Key::End,
+        ],
+        // h and l move, j goes down, k goes "up" (rotates); u undoes a turn, y yanks the piece into hold
+        "Vim" => [
+            egui::Key::H,
+            egui::Key::L,
+            egui::Key::K,
+            egui::Key::U,
+            egui::Key::J,
+            egui::Key::Space,
+            egui::Key::Y,
+            egui::Key::P,
+            egui::Key::E,
+            egui::Key::Escape,
+            egui::Key::Q,
+        ],
+        _ => return None,
+    };
+    Some(Action::ALL.into_iter().zip(keys.map(Binding::Key)).collect())
+}
+
+type DeviceProfiles = HashMap<InputDevice, Bindings>;
+
+// control_profiles.txt had a name,device,action,binding line per binding
+fn load_old_control_profiles() -> Vec<ControlProfile> {
+    let mut saved: Vec<ControlProfile> = Vec::new();
+    let Ok(file) = fs::File::open(data_dir::path(OLD_CONTROL_PROFILES_FILE)) else {
+        return saved;
+    };
+    for line in io::BufReader::new(file).lines().map_while(Result::ok) {
+        let parts: Vec<&str> = line.splitn(4, ',').collect();
+        let [name, device, action, binding] = parts[..] else {
+            continue;
+        };
+        let Some(action) = Action::from_name(action) else {
+            continue;
+        };
+        let index = match saved.iter().position(|profile| profile.name == name) {
+            Some(index) => index,
+            None => {
+                saved.push(ControlProfile { name: name.to_string(), keybindings: SavedBindings::new() });
+                saved.len() - 1
+            }
+        };
+        saved[index].keybindings.entry(device.to_string()).or_default().insert(action, binding.to_string());
+    }
+    saved
+}
+
+// The saved profiles from config.toml, or from the old file if config.toml has none yet
+fn load_control_profiles(config: Option<&Config>) -> Vec<(String, DeviceProfiles)> {
+    let saved = config.and_then(|config| config.control_profiles.clone()).unwrap_or_else(load_old_control_profiles);
+    decode_control_profiles(&saved)
+}
+
+fn decode_control_profiles(saved: &[ControlProfile]) -> Vec<(String, DeviceProfiles)> {
+    saved.iter().map(|profile| (profile.name.clone(), decode_device_bindings(&profile.keybindings))).collect()
+}
+
+fn encode_bindings(profiles: &DeviceProfiles) -> SavedBindings {
+    profiles
+        .iter()
+        .map(|(device, bindings)| {
+            let bindings = bindings.iter().map(|(&action, binding)| (action, binding.encode())).collect();
+            (device.id(), bindings)
+        })
+        .collect()
+}
+
+// Drops the devices and bindings the game can't read
+pub fn validate_bindings(saved: &mut SavedBindings) {
+    saved.retain(|device, bindings| {
+        bindings.retain(|_, binding| Binding::decode(binding).is_some());
+        InputDevice::from_id(device).is_some()
+    });
+}
+
+pub struct Controls {
+    profiles: DeviceProfiles,
+    // Named copies of `profiles`, in the order they were saved
+    saved: Vec<(String, DeviceProfiles)>,
+    // The profile or preset last switched to, if the bindings haven't changed since
+    pub profile_name: Option<String>,
+    active_device: InputDevice,
+    // Gamepad buttons pressed this frame and held down, by name
+    buttons_pressed: HashSet<String>,
+    buttons_down: HashSet<String>,
+    // The last save that failed, for the game to report
+    pub save_error: Option<(Retry, io::Error)>,
+}
+
+// As saved in config.toml: each action's binding by device
+pub type SavedBindings = BTreeMap<String, BTreeMap<Action, String>>;
+
+// keybindings.txt had a device,action,binding line per binding
+fn load_old_bindings() -> SavedBindings {
+    let mut saved = SavedBindings::new();
+    if let Ok(file) = fs::File::open(data_dir::path(OLD_KEYBINDINGS_FILE)) {
+        for line in io::BufReader::new(file).lines().map_while(Result::ok) {
+            let parts: Vec<&str> = line.splitn(3, ',').collect();
+            if let [device, action, binding] = parts[..] {
+                if let Some(action) = Action::from_name(action) {
+                    saved.entry(device.to_string()).or_default().insert(action, binding.to_string());
+                }
+            }
+        }
+    }
+    saved
+}
+
+fn load_bindings(config: Option<&Config>) -> DeviceProfiles {
+    let saved = config.map(|config| config.keybindings.clone()).unwrap_or_default();
+    let saved = if saved.is_empty() { load_old_bindings() } else { saved };
+    decode_bindings(&saved)
+}
+
+// Only what's there; a profile leaves the rest as it was when it's switched to
+fn decode_device_bindings(saved: &SavedBindings) -> DeviceProfiles {
+    let mut profiles: DeviceProfiles = HashMap::new();
+    for (device, bindings) in saved {
+        let Some(device) = InputDevice::from_id(device) else {
+            continue;
+        };
+        for (&action, binding) in bindings {
+            if let Some(binding) = Binding::decode(binding) {
+                profiles.entry(device.clone()).or_default().insert(action, binding);
+            }
+        }
+    }
+    profiles
+}
+
+fn decode_bindings(saved: &SavedBindings) -> DeviceProfiles {
+    let mut profiles = decode_device_bindings(saved);
+
+    // Fill in anything missing from older or hand-edited files
+    profiles.entry(InputDevice::Keyboard).or_default();
+    for (device, bindings) in profiles.iter_mut() {
+        for (action, binding) in default_bindings(device) {
+            bindings.entry(action).or_insert(binding);
+        }
+    }
+    profiles
+}
+
+impl Controls {
+    pub fn load() -> Self {
+        let config = Config::load().ok();
+        Self {
+            profiles: load_bindings(config.as_ref()),
+            saved: load_control_profiles(config.as_ref()),
+            profile_name: None,
+            active_device: InputDevice::Keyboard,
+            buttons_pressed: HashSet::new(),
+            buttons_down: HashSet::new(),
+            save_error: None,
+        }
+    }
+
+    // Picks up the bindings again after config.toml was changed outside the game
+    pub fn reload_bindings(&mut self, saved: &SavedBindings) {
+        if saved.is_empty() {
+            return;
+        }
+        let profiles = decode_bindings(saved);
+        if profiles != self.profiles {
+            self.profiles = profiles;
+            self.profile_name = None;
+        }
+    }
+
+    // And the saved profiles, after config.toml was changed outside the game
+    pub fn reload_profiles(&mut self, saved: Option<&[ControlProfile]>) {
+        if let Some(saved) = saved {
+            self.saved = decode_control_profiles(saved);
+        }
+    }
+
+    pub fn keybindings(&self) -> SavedBindings {
+        encode_bindings(&self.profiles)
+    }
+
+    pub fn control_profiles(&self) -> Vec<ControlProfile> {
+        self.saved
+            .iter()
+            .map(|(name, profiles)| ControlProfile { name: name.clone(), keybindings: encode_bindings(profiles) })
+            .collect()
+    }
+
+    // Into the config file as it is on disk, so the rest of it stays as it was; one that can't
+    // be read isn't written over
+    pub fn save(&self) -> io::Result<()> {
+        let mut config = Config::load().map_err(io::Error::other)?;
+        config.keybindings = self.keybindings();
+        config.save()
+    }
+
+    pub fn save_profiles(&self) -> io::Result<()> {
+        let mut config = Config::load().map_err(io::Error::other)?;
+        config.control_profiles = Some(self.control_profiles());
+        config.save()
+    }
+
+    fn save_or_keep_error(&mut self, saved: io::Result<()>, retry: Retry) {
+        if let Err(error) = saved {
+            self.save_error = Some((retry, error));
+        }
+    }
+
+    // Switches to the given device, creating a default profile the first time it's seen.
+    pub fn set_active_device(&mut self, device: InputDevice) {
+        if self.active_device == device {
+            return;
+        }
+
+        if !self.profiles.contains_key(&device) {
+            self.profiles.insert(device.clone(), default_bindings(&device));
+            self.save_or_keep_error(self.save(), Retry::SaveBindings);
+        }
+        self.active_device = device;
+    }
+
+    pub fn active_device(&self) -> &InputDevice {
+        &self.active_device
+    }
+
+    pub fn set_buttons(&mut self, pressed: HashSet<String>, down: HashSet<String>) {
+        self.buttons_pressed = pressed;
+        self.buttons_down = down;
+    }
+
+    pub fn bindings(&self) -> &Bindings {
+        &self.profiles[&self.active_device]
+    }
+
+    // Binds the action on the active device and saves the change. An action that
+    // already had that binding takes over the old one, so no two actions share a
+    // key; it's returned so the player can be told.
+    pub fn rebind(&mut self, action: Action, binding: Binding) -> Option<Action> {
+        let bindings = self.profiles.get_mut(&self.active_device)?;
+        let conflict = bindings
+            .iter()
+            .find(|&(&other, other_binding)| other != action && *other_binding == binding)
+            .map(|(&other, _)| other);
+        let previous = bindings.insert(action, binding);
+        if let (Some(other), Some(previous)) = (conflict, previous) {
+            bindings.insert(other, previous);
+        }
+        self.profile_name = None;
+        self.save_or_keep_error(self.save(), Retry::SaveBindings);
+        conflict
+    }
+
+    pub fn saved_profiles(&self) -> impl Iterator<Item = &str> {
+        self.saved.iter().map(|(name, _)| name.as_str())
+    }
+
+    // Keeps the current bindings under the name, replacing a profile saved with it before
+    pub fn save_profile(&mut self, name: &str) {
+        let name = name.trim().to_string();
+        if name.is_empty() || PRESETS.contains(&name.as_str()) {
+            return;
+        }
+        match self.saved.iter_mut().find(|(saved, _)| *saved == name) {
+            Some((_, profiles)) => *profiles = self.profiles.clone(),
+            None => self.saved.push((name.clone(), self.profiles.clone())),
+        }
+        self.save_or_keep_error(self.save_profiles(), Retry::SaveControlProfiles);
+        self.profile_name = Some(name);
+    }
+
+    pub fn delete_profile(&mut self, name: &str) {
+        self.saved.retain(|(saved, _)| saved != name);
+        self.save_or_keep_error(self.save_profiles(), Retry::SaveControlProfiles);
+        if self.profile_name.as_deref() == Some(name) {
+            self.profile_name = None;
+        }
+    }
+
+    // Switches to a saved profile, or to a preset's keyboard layout
+    pub fn apply_profile(&mut self, name: &str) {
+        if let Some((_, profiles)) = self.saved.iter().find(|(saved, _)| saved == name) {
+            for (device, bindings) in profiles {
+                let current = self.profiles.entry(device.clone()).or_insert_with(|| default_bindings(device));
+                current.extend(bindings.iter().map(|(&action, binding)| (action, binding.clone())));
+            }
+        } else if let Some(bindings) = preset_bindings(name) {
+            self.profiles.insert(InputDevice::Keyboard, bindings);
+        } else {
+            return;
+        }
+        self.profile_name = Some(name.to_string());
+        self.save_or_keep_error(self.save(), Retry::SaveBindings);
+    }
+
+    pub fn pressed(&self, input: &egui::InputState, action: Action) -> bool {
+        match self.bindings().get(&action) {
+            Some(Binding::Key(key)) => input.key_pressed(*key),
+            Some(Binding::Button(button)) => self.buttons_pressed.contains(button),
+            None => false,
+        }
+    }
+
+    // Like `pressed`, but not for the system's key repeat, which auto shift replaces
+    pub fn pressed_once(&self, input: &egui::InputState, action: Action) -> bool {
+        match self.bindings().get(&action) {
+            Some(Binding::Key(key)) => input.events.iter().any(|event| {
+                matches!(event, egui::Event::Key { key: pressed, pressed: true, repeat: false, .. } if pressed == key)
+            }),
+            Some(Binding::Button(button)) => self.buttons_pressed.contains(button),
+            None => false,
+        }
+    }
+
+    // Whether the action's key is down right now, not just pressed this frame
+    pub fn held(&self, input: &egui::InputState, action: Action) -> bool {
+        match self.bindings().get(&action) {
+            Some(Binding::Key(key)) => input.key_down(*key),
+            Some(Binding::Button(button)) => self.buttons_down.contains(button),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+// Daily challenge: one Marathon run a day per profile, with every player
+// getting the same piece sequence seeded from the (UTC) date, and a
+// leaderboard of that day's results.
+
+use eframe::egui;
+use std::fmt::Write;
+use std::fs;
+use std::io::{self, BufRead};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::file_errors::Retry;
+use crate::{data_dir, CrowsTetris};
+
+pub const DAILY_RESULTS_FILE: &str = "daily_results.txt";
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+// Mixed into the day number so the daily sequence isn't just seed 0, 1, 2...
+const SEED_SALT: u64 = 0x6372_6f77_7321;
+
+#[derive(Debug, Clone)]
+pub struct DailyResult {
+    pub day: u64,
+    pub name: String,
+    pub score: i32,
+    pub lines: u32,
+}
+
+// Days since 1970-01-01 in UTC, so everyone rolls over to the next challenge at the same moment
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() / SECONDS_PER_DAY)
+}
+
+pub fn seed(day: u64) -> u64 {
+    day ^ SEED_SALT
+}
+
+// YYYY-MM-DD for a day number (Howard Hinnant's civil_from_days)
+pub fn date_label(day: u64) -> String {
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
+
+pub fn load_daily_results() -> Vec<DailyResult> {
+    if let Ok(file) = fs::File::open(data_dir::path(DAILY_RESULTS_FILE)) {
+        io::BufReader::new(file)
+            .lines()
+            .filter_map(|line| {
+                let line = line.ok()?;
+                let parts: Vec<&str> = line.split(',').collect();
+                if parts.len() != 4 {
+                    return None;
+                }
+                Some(DailyResult {
+                    day: parts[0].parse().ok()?,
+                    name: parts[1].to_string(),
+                    score: parts[2].parse().ok()?,
+                    lines: parts[3].parse().ok()?,
+                })
+            })
+            .collect()
+    } else {
+        vec![]
+    }
+}
+
+pub fn save_daily_results(results: &[DailyResult]) -> io::Result<()> {
+    let mut text = String::new();
+    for result in results {
+        writeln!(text, "{},{},{},{}", result.day, result.name, result.score, result.lines).ok();
+    }
+    data_dir::write_atomic(DAILY_RESULTS_FILE, &text)
+}
+
+impl CrowsTetris {
+    pub(crate) fn daily_attempted(&self) -> bool {
+        let today = today();
+        let name = &self.active_profile().name;
+        self.daily_results
+            .iter()
+            .any(|result| result.day == today && &result.name == name)
+    }
+
+    // The attempt is used up as soon as the run starts, so quitting midway doesn't earn a retry
+    pub(crate) fn start_daily_attempt(&mut self) {
+        self.daily_results.push(DailyResult {
+            day: today(),
+            name: self.active_profile().name.clone(),
+            score: 0,
+            lines: 0,
+        });
+        self.check_saved(save_daily_results(&self.daily_results), Retry::SaveDailyResults);
+    }
+
+    pub(crate) fn record_daily_result(&mut self) {
+        let today = today();
+        let name = self.active_profile().name.clone();
+        if let Some(result) = self
+            .daily_results
+            .iter_mut()
+            .find(|result| result.day == today && result.name == name)
+        {
+            result.score = self.score;
+            result.lines = self.lines;
+        }
+        self.check_saved(save_daily_results(&self.daily_results), Retry::SaveDailyResults);
+    }
+
+    pub(crate) fn render_daily_leaderboard(&self, ui: &mut egui::Ui) {
+        let today = today();
+        ui.heading(format!("{} {}", self.tr("Daily challenge"), date_label(today)));
+
+        let mut results: Vec<&DailyResult> = self.daily_results.iter().filter(|result| result.day == today).collect();
+        if results.is_empty() {
+            ui.label(self.tr("Nobody has played today's challenge yet."));
+        }
+        results.sort_by_key(|result| std::cmp::Reverse(result.score));
+        for (i, result) in results.iter().take(10).enumerate() {
+            ui.label(format!("{}. {} - {} ({} {})", i + 1, result.name, result.score, result.lines, self.tr("lines")));
+        }
+    }
+}
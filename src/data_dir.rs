@@ -0,0 +1,133 @@
+// Where the game keeps its saved files: the platform's per-user data
+// directory (~/.local/share/crows-tetris, ~/Library/Application Support/
+// crows-tetris or %APPDATA%\crows-tetris), so scores and settings don't
+// depend on where the game was started from. Files an older version left in
+// the folder the game was started from, or next to the game itself, are
+// copied over on the first launch; the originals are left where they were.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::OnceLock;
+
+use crate::{autosave, config, controls, daily, high_scores, history, lifetime, profile, replay, splits, suspend};
+
+const APP_DIR: &str = "crows-tetris";
+// Left in the data directory once the old files have been looked for, so it's only done once
+const MIGRATED_MARKER: &str = ".migrated";
+
+// Every file the game saves
+const SAVED_FILES: [&str; 13] = [
+    config::CONFIG_FILE,
+    config::OLD_SETTINGS_FILE,
+    high_scores::HIGH_SCORE_FILE,
+    high_scores::OLD_HIGH_SCORE_FILE,
+    controls::OLD_KEYBINDINGS_FILE,
+    controls::OLD_CONTROL_PROFILES_FILE,
+    profile::PROFILES_FILE,
+    splits::SPLITS_FILE,
+    daily::DAILY_RESULTS_FILE,
+    suspend::SUSPEND_FILE,
+    lifetime::LIFETIME_STATS_FILE,
+    history::GAME_HISTORY_FILE,
+    autosave::AUTOSAVE_FILE,
+];
+// Every directory of files the game saves
+const SAVED_DIRS: [&str; 1] = [replay::REPLAYS_DIR];
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+fn platform_dir() -> Option<PathBuf> {
+    let home = || env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|home| home.join("Library").join("Application Support"))
+    } else {
+        // The XDG spec says to ignore a relative XDG_DATA_HOME
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home().map(|home| home.join(".local").join("share")))
+    };
+    Some(base?.join(APP_DIR))
+}
+
+fn copy_if_missing(old: &Path, new: &Path) {
+    if old.is_file() && !new.exists() {
+        fs::copy(old, new).ok();
+    }
+}
+
+// Copies the saved files from one directory to another, leaving alone any the new one already has
+fn migrate(from: &Path, to: &Path) {
+    for file in SAVED_FILES {
+        copy_if_missing(&from.join(file), &to.join(file));
+    }
+    for dir in SAVED_DIRS {
+        let Ok(entries) = fs::read_dir(from.join(dir)) else {
+            continue;
+        };
+        if fs::create_dir_all(to.join(dir)).is_err() {
+            continue;
+        }
+        for entry in entries.filter_map(Result::ok) {
+            copy_if_missing(&entry.path(), &to.join(dir).join(entry.file_name()));
+        }
+    }
+}
+
+// Brings an old install's files over the first time there's a data directory, and never again.
+// A file in more than one of the old folders comes from the first.
+pub fn migrate_once(from: &[PathBuf], to: &Path) {
+    if to.join(MIGRATED_MARKER).exists() {
+        return;
+    }
+    for dir in from.iter().filter(|dir| dir.as_path() != to) {
+        migrate(dir, to);
+    }
+    fs::write(to.join(MIGRATED_MARKER), "").ok();
+}
+
+// Older versions kept their files in the working directory. That was meant to be the game's own
+// folder, but with `cargo run` or a shortcut elsewhere it was wherever the game was started from.
+pub fn old_dirs() -> Vec<PathBuf> {
+    let working = env::current_dir().ok();
+    let install = env::current_exe().ok().and_then(|exe| Some(exe.parent()?.to_path_buf()));
+    let mut dirs: Vec<PathBuf> = working.into_iter().chain(install).collect();
+    dirs.dedup();
+    dirs
+}
+
+// Picks the data directory at startup. Without one (no home directory, or it can't be
+// created) files stay in the working directory as before.
+pub fn init() {
+    let Some(dir) = platform_dir() else {
+        return;
+    };
+    if fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+    migrate_once(&old_dirs(), &dir);
+    DATA_DIR.set(dir).ok();
+}
+
+// Where a saved file lives
+pub fn path(file: &str) -> PathBuf {
+    DATA_DIR.get().map_or_else(|| PathBuf::from(file), |dir| dir.join(file))
+}
+
+// Replaces a saved file in one step: the new contents go to a temporary file next to it, which
+// is then renamed over it. A crash part way leaves the old file whole, and each running copy of
+// the game writes its own temporary file so two saving at once can't mix their contents.
+pub fn write_atomic(file: &str, contents: &str) -> io::Result<()> {
+    let target = path(file);
+    let temp = path(&format!("{}.{}.tmp", file, process::id()));
+    let written = fs::write(&temp, contents).and_then(|()| fs::rename(&temp, &target));
+    if written.is_err() {
+        fs::remove_file(&temp).ok();
+    }
+    written
+}
@@ -0,0 +1,84 @@
+// Developer overlay, toggled with F3: frame rate and timing, the gravity timer,
+// where the active piece is and what's left in the randomizer bag. Meant for
+// chasing down timing problems, so it stays in English.
+
+use eframe::egui;
+use std::collections::VecDeque;
+
+use crate::{CrowsTetris, GameState};
+
+// Frames averaged for the FPS reading
+const FRAME_SAMPLES: usize = 60;
+
+impl CrowsTetris {
+    pub(crate) fn update_debug_overlay(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.key_pressed(egui::Key::F3)) {
+            self.show_debug = !self.show_debug;
+            self.frame_times.clear();
+        }
+        if !self.show_debug {
+            return;
+        }
+
+        record_frame_time(&mut self.frame_times, ctx.input(|i| i.unstable_dt));
+        let average = self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32;
+        let slowest = self.frame_times.iter().copied().fold(0.0, f32::max);
+
+        egui::Area::new(egui::Id::new("debug_overlay"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(egui::pos2(4.0, 4.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    let line = |ui: &mut egui::Ui, text: String| {
+                        ui.label(egui::RichText::new(text).monospace().size(10.0));
+                    };
+                    line(ui, format!("FPS {:.0}", 1.0 / average.max(f32::EPSILON)));
+                    line(ui, format!("frame {:.1} ms (max {:.1})", average * 1000.0, slowest * 1000.0));
+                    if self.state != GameState::Playing {
+                        return;
+                    }
+
+                    line(
+                        ui,
+                        format!(
+                            "gravity {} / {} ms",
+                            self.last_update.elapsed().as_millis(),
+                            self.current_drop_speed().as_millis()
+                        ),
+                    );
+                    if let Some(landed_at) = self.landed_at {
+                        line(
+                            ui,
+                            format!(
+                                "lock {} / {} ms",
+                                landed_at.elapsed().as_millis(),
+                                self.lock_delay().as_millis()
+                            ),
+                        );
+                    }
+                    line(
+                        ui,
+                        format!(
+                            "paused {} countdown {} clearing {}",
+                            self.is_paused,
+                            self.counting_down(),
+                            self.clearing()
+                        ),
+                    );
+                    match &self.active_block {
+                        Some(block) => line(ui, format!("piece {:?} at {:?}", block.block_type, block.position)),
+                        None => line(ui, "piece none".to_string()),
+                    }
+                    let bag: String = self.piece_generator.bag().iter().map(|block_type| block_type.letter()).collect();
+                    line(ui, format!("bag [{}]", bag));
+                });
+            });
+    }
+}
+
+fn record_frame_time(frame_times: &mut VecDeque<f32>, dt: f32) {
+    if frame_times.len() == FRAME_SAMPLES {
+        frame_times.pop_front();
+    }
+    frame_times.push_back(dt);
+}
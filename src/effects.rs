@@ -0,0 +1,422 @@
+// Short-lived visual effects layered over the board. They only change what is
+// drawn; the game state underneath has already moved on.
+
+use eframe::egui;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+use crate::clears::ClearEvent;
+use crate::master::SECTION_LENGTH;
+use crate::{AgeGrid, Block, CrowsTetris, GameMode, Grid, GRID_HEIGHT, GRID_WIDTH};
+
+pub const LINE_CLEAR_DURATION: Duration = Duration::from_millis(200);
+const DROP_TRAIL_DURATION: Duration = Duration::from_millis(150);
+// Particle motion is in board cells and seconds
+const SPARKS_PER_ROW: usize = 8;
+const SPARK_LIFE: f32 = 0.5;
+const SPARK_GRAVITY: f32 = 40.0;
+const FEATHERS_PER_TETRIS: usize = 12;
+const FEATHER_LIFE: f32 = 1.6;
+const FEATHER_FALL_SPEED: f32 = 2.5;
+const FEATHER_COLOR: egui::Color32 = egui::Color32::from_rgb(30, 30, 40);
+// A long gap between frames shouldn't fling particles across the board
+const MAX_FRAME_TIME: f32 = 0.1;
+const SHAKE_DURATION: Duration = Duration::from_millis(250);
+// Largest shake offset, in points, for a Tetris
+const SHAKE_AMPLITUDE: f32 = 5.0;
+// Combos this long shake the board too, a little less than a Tetris
+const SHAKE_COMBO: u32 = 4;
+// Fraction of the animation spent flashing before the rows collapse
+const FLASH_PART: f32 = 0.5;
+// The board warns once the stack reaches this many rows from the top
+pub const DANGER_ROWS: usize = 5;
+const DANGER_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 30, 30);
+// Width of the red glow along the board edges, in cells
+const DANGER_EDGE: f32 = 1.5;
+// How long the board takes to fill with gray after topping out
+const TOP_OUT_DURATION: Duration = Duration::from_millis(1200);
+const LEVEL_UP_DURATION: Duration = Duration::from_millis(1500);
+const LEVEL_UP_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 220, 80);
+
+// The board as it was just before full rows were removed
+pub struct LineClearAnimation {
+    pub grid: Grid,
+    pub locked_at: AgeGrid,
+    rows: Vec<usize>,
+    started: Instant,
+}
+
+impl LineClearAnimation {
+    pub fn new(grid: Grid, locked_at: AgeGrid, rows: Vec<usize>) -> Self {
+        Self {
+            grid,
+            locked_at,
+            rows,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started.elapsed() < LINE_CLEAR_DURATION
+    }
+
+    // For every row: how far it has fallen so far, in rows, and for a cleared
+    // row how much of it is still showing (1 while flashing, shrinking to 0).
+    pub fn row_layout(&self) -> ([f32; GRID_HEIGHT], [Option<f32>; GRID_HEIGHT]) {
+        let progress = self.started.elapsed().as_secs_f32() / LINE_CLEAR_DURATION.as_secs_f32();
+        let collapse = ((progress - FLASH_PART) / (1.0 - FLASH_PART)).clamp(0.0, 1.0);
+
+        let mut offsets = [0.0; GRID_HEIGHT];
+        let mut remaining = [None; GRID_HEIGHT];
+        for (y, offset) in offsets.iter_mut().enumerate() {
+            if self.rows.contains(&y) {
+                remaining[y] = Some(1.0 - collapse);
+            }
+            let cleared_below = self.rows.iter().filter(|&&row| row > y).count();
+            *offset = cleared_below as f32 * collapse;
+        }
+        (offsets, remaining)
+    }
+}
+
+// The playfield jolts on heavy clears and settles back
+pub struct Shake {
+    started: Instant,
+    strength: f32,
+}
+
+impl Shake {
+    fn is_running(&self) -> bool {
+        self.started.elapsed() < SHAKE_DURATION
+    }
+
+    fn offset(&self) -> egui::Vec2 {
+        let t = self.started.elapsed().as_secs_f32() / SHAKE_DURATION.as_secs_f32();
+        let amplitude = SHAKE_AMPLITUDE * self.strength * (1.0 - t).max(0.0).powi(2);
+        egui::vec2((t * 40.0).sin(), (t * 53.0).cos()) * amplitude
+    }
+}
+
+// The banner announcing a faster level
+pub struct LevelUp {
+    level: u32,
+    started: Instant,
+}
+
+impl LevelUp {
+    fn is_running(&self) -> bool {
+        self.started.elapsed() < LEVEL_UP_DURATION
+    }
+}
+
+pub enum Effect {
+    // Streaks down the columns a hard-dropped piece fell through: (column, top row, bottom row)
+    DropTrail {
+        columns: Vec<(usize, f32, f32)>,
+        color: egui::Color32,
+        started: Instant,
+    },
+    Particle(Particle),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticleKind {
+    // Bursts out of cleared rows and falls quickly
+    Spark,
+    // Crow feathers for a Tetris: drift down slowly, swaying and turning
+    Feather,
+}
+
+pub struct Particle {
+    kind: ParticleKind,
+    position: egui::Pos2,
+    velocity: egui::Vec2,
+    color: egui::Color32,
+    angle: f32,
+    // Seconds left
+    life: f32,
+}
+
+impl Particle {
+    fn update(&mut self, dt: f32) {
+        match self.kind {
+            ParticleKind::Spark => self.velocity.y += SPARK_GRAVITY * dt,
+            ParticleKind::Feather => {
+                self.velocity = egui::vec2((self.life * 5.0 + self.angle).sin() * 2.0, FEATHER_FALL_SPEED);
+                self.angle += self.velocity.x * dt;
+            }
+        }
+        self.position += self.velocity * dt;
+        self.life -= dt;
+    }
+
+    fn draw(&self, painter: &egui::Painter, board: egui::Rect, cell_size: f32) {
+        let center = board.min + self.position.to_vec2() * cell_size;
+        match self.kind {
+            ParticleKind::Spark => {
+                let fade = (self.life / SPARK_LIFE).clamp(0.0, 1.0);
+                painter.circle_filled(center, cell_size * 0.12, self.color.gamma_multiply(fade));
+            }
+            ParticleKind::Feather => {
+                let fade = (self.life / FEATHER_LIFE * 2.0).clamp(0.0, 1.0);
+                let half = egui::vec2(self.angle.cos(), self.angle.sin()) * cell_size * 0.35;
+                painter.line_segment(
+                    [center - half, center + half],
+                    egui::Stroke::new(cell_size * 0.18, self.color.gamma_multiply(fade)),
+                );
+            }
+        }
+    }
+}
+
+impl Effect {
+    fn update(&mut self, dt: f32) {
+        if let Effect::Particle(particle) = self {
+            particle.update(dt);
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        match self {
+            Effect::DropTrail { started, .. } => started.elapsed() >= DROP_TRAIL_DURATION,
+            Effect::Particle(particle) => particle.life <= 0.0,
+        }
+    }
+
+    fn draw(&self, painter: &egui::Painter, board: egui::Rect, cell_size: f32) {
+        match self {
+            Effect::DropTrail { columns, color, started } => {
+                let fade = 1.0 - started.elapsed().as_secs_f32() / DROP_TRAIL_DURATION.as_secs_f32();
+                let bottom_color = color.gamma_multiply(0.6 * fade.max(0.0));
+                for &(x, top, bottom) in columns {
+                    let left = board.min.x + (x as f32 + 0.2) * cell_size;
+                    let right = left + 0.6 * cell_size;
+                    let top = board.min.y + top * cell_size;
+                    let bottom = board.min.y + bottom * cell_size;
+
+                    // Clear at the top, brightest where the piece landed
+                    let mut mesh = egui::Mesh::default();
+                    mesh.colored_vertex(egui::pos2(left, top), egui::Color32::TRANSPARENT);
+                    mesh.colored_vertex(egui::pos2(right, top), egui::Color32::TRANSPARENT);
+                    mesh.colored_vertex(egui::pos2(right, bottom), bottom_color);
+                    mesh.colored_vertex(egui::pos2(left, bottom), bottom_color);
+                    mesh.add_triangle(0, 1, 2);
+                    mesh.add_triangle(0, 2, 3);
+                    painter.add(mesh);
+                }
+            }
+            Effect::Particle(particle) => particle.draw(painter, board, cell_size),
+        }
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn clearing(&self) -> bool {
+        self.line_clear.as_ref().is_some_and(LineClearAnimation::is_running)
+    }
+
+    // Whether anything is still animating and needs frames
+    pub(crate) fn effects_running(&self) -> bool {
+        self.clearing()
+            || !self.effects.is_empty()
+            || self.shake.as_ref().is_some_and(Shake::is_running)
+            || self.level_up.as_ref().is_some_and(LevelUp::is_running)
+            || self.combo_meter_moving
+            || self.danger() > 0.0
+    }
+
+    // Master levels go up with every piece, so only a new section gets a banner there
+    pub(crate) fn announce_level_up(&mut self, previous: u32) {
+        let step = if self.mode == GameMode::Master { SECTION_LENGTH } else { 1 };
+        if self.level / step > previous / step {
+            self.level_up = Some(LevelUp {
+                level: self.level,
+                started: Instant::now(),
+            });
+        }
+    }
+
+    // Whether the level indicator should be lit up right now: it blinks while the banner shows
+    pub(crate) fn level_flash(&self) -> bool {
+        self.level_up
+            .as_ref()
+            .filter(|level_up| level_up.is_running())
+            .is_some_and(|level_up| level_up.started.elapsed().as_millis() / 150 % 2 == 0)
+    }
+
+    // Slides in over the middle of the board, holds, then fades out
+    pub(crate) fn draw_level_up(&self, painter: &egui::Painter, board: egui::Rect) {
+        let Some(level_up) = self.level_up.as_ref().filter(|level_up| level_up.is_running()) else {
+            return;
+        };
+        let t = level_up.started.elapsed().as_secs_f32() / LEVEL_UP_DURATION.as_secs_f32();
+        let slide = (1.0 - t / 0.15).max(0.0);
+        let fade = ((1.0 - t) / 0.25).min(1.0);
+
+        let height = (board.height() * 0.14).max(24.0);
+        let center = board.center() - egui::vec2(slide * board.width(), 0.0);
+        let banner = egui::Rect::from_center_size(center, egui::vec2(board.width(), height));
+        painter.rect_filled(banner, 0.0, egui::Color32::from_black_alpha(170).gamma_multiply(fade));
+        painter.text(
+            center,
+            egui::Align2::CENTER_CENTER,
+            format!("{} {} — {}", self.tr("Level"), level_up.level, self.tr("speed up!")),
+            egui::FontId::proportional(height * 0.5),
+            LEVEL_UP_COLOR.gamma_multiply(fade),
+        );
+    }
+
+    pub(crate) fn topping_out(&self) -> bool {
+        self.top_out_at.is_some_and(|at| at.elapsed() < TOP_OUT_DURATION)
+    }
+
+    // Rows turn gray one after another from the bottom, the newest one fading in
+    pub(crate) fn draw_top_out(&self, painter: &egui::Painter, board: egui::Rect, cell_size: f32) {
+        let Some(at) = self.top_out_at else {
+            return;
+        };
+        let progress = (at.elapsed().as_secs_f32() / TOP_OUT_DURATION.as_secs_f32()).min(1.0);
+        let filled = progress * GRID_HEIGHT as f32;
+        for row in 0..GRID_HEIGHT {
+            let amount = (filled - row as f32).clamp(0.0, 1.0);
+            if amount <= 0.0 {
+                break;
+            }
+            let top = board.max.y - (row + 1) as f32 * cell_size;
+            let row_rect = egui::Rect::from_min_size(egui::pos2(board.min.x, top), egui::vec2(board.width(), cell_size));
+            painter.rect_filled(row_rect, 0.0, self.theme().garbage.gamma_multiply(0.85 * amount));
+        }
+    }
+
+    // How close the stack is to the top: 0 while it's safely low, rising to 1
+    // when the top row has blocks in it
+    pub(crate) fn danger(&self) -> f32 {
+        match self.grid.iter().position(|row| row.iter().any(|&cell| cell != 0)) {
+            Some(top) if top < DANGER_ROWS => (DANGER_ROWS - top) as f32 / DANGER_ROWS as f32,
+            _ => 0.0,
+        }
+    }
+
+    // A red pulse over the board and a glow along its edges, stronger the closer the stack is to topping out
+    pub(crate) fn draw_danger(&self, painter: &egui::Painter, board: egui::Rect, cell_size: f32, time: f64) {
+        let danger = self.danger();
+        if danger <= 0.0 {
+            return;
+        }
+        let pulse = 0.5 + 0.5 * (time as f32 * (4.0 + 4.0 * danger)).sin();
+        painter.rect_filled(board, 2.0, DANGER_COLOR.gamma_multiply(0.15 * danger * pulse));
+
+        let edge = DANGER_COLOR.gamma_multiply(0.4 + 0.4 * danger);
+        let width = (DANGER_EDGE * cell_size).min(board.width() / 2.0);
+        for (outer, inner) in [
+            (board.left(), board.left() + width),
+            (board.right(), board.right() - width),
+        ] {
+            let mut mesh = egui::Mesh::default();
+            mesh.colored_vertex(egui::pos2(outer, board.top()), edge);
+            mesh.colored_vertex(egui::pos2(inner, board.top()), egui::Color32::TRANSPARENT);
+            mesh.colored_vertex(egui::pos2(inner, board.bottom()), egui::Color32::TRANSPARENT);
+            mesh.colored_vertex(egui::pos2(outer, board.bottom()), edge);
+            mesh.add_triangle(0, 1, 2);
+            mesh.add_triangle(0, 2, 3);
+            painter.add(mesh);
+        }
+    }
+
+    pub(crate) fn shake_for_clear(&mut self, clear: &ClearEvent) {
+        if !self.screen_shake {
+            return;
+        }
+        let strength = if clear.lines >= 4 {
+            1.0
+        } else if clear.lines > 0 && clear.combo >= SHAKE_COMBO {
+            0.6
+        } else {
+            return;
+        };
+        self.shake = Some(Shake {
+            started: Instant::now(),
+            strength,
+        });
+    }
+
+    pub(crate) fn shake_offset(&self) -> egui::Vec2 {
+        self.shake
+            .as_ref()
+            .filter(|shake| shake.is_running())
+            .map_or(egui::Vec2::ZERO, Shake::offset)
+    }
+
+    // `from` is where the piece started falling, `block` where it ended up
+    pub(crate) fn add_drop_trail(&mut self, block: &Block, from: i32) {
+        let mut columns = vec![];
+        for dx in 0..block.shape[0].len() {
+            // The trail ends at the top cell of the piece in each column
+            let Some(dy) = block.shape.iter().position(|row| row[dx] != 0) else {
+                continue;
+            };
+            let top = (from + dy as i32).max(0) as f32;
+            let bottom = (block.position.1 + dy as i32).max(0) as f32;
+            if bottom > top {
+                columns.push(((block.position.0 + dx as i32) as usize, top, bottom));
+            }
+        }
+
+        if !columns.is_empty() {
+            self.effects.push(Effect::DropTrail {
+                columns,
+                color: self.theme().piece_color(block.block_type),
+                started: Instant::now(),
+            });
+        }
+    }
+
+    // Sparks from every cleared row, in the colors of the cells that were in it, and feathers for a Tetris
+    pub(crate) fn emit_clear_particles(&mut self, rows: &[usize]) {
+        let mut rng = rand::rng();
+        for &y in rows {
+            for _ in 0..SPARKS_PER_ROW {
+                let x = rng.random_range(0..GRID_WIDTH);
+                let color = self.theme().cell_color(self.grid[y][x]).unwrap_or(egui::Color32::WHITE);
+                self.effects.push(Effect::Particle(Particle {
+                    kind: ParticleKind::Spark,
+                    position: egui::pos2(x as f32 + 0.5, y as f32 + 0.5),
+                    velocity: egui::vec2(rng.random_range(-4.0..4.0), rng.random_range(-12.0..-4.0)),
+                    color,
+                    angle: 0.0,
+                    life: SPARK_LIFE,
+                }));
+            }
+        }
+
+        if rows.len() >= 4 {
+            let top = rows.iter().min().copied().unwrap_or(0) as f32;
+            for _ in 0..FEATHERS_PER_TETRIS {
+                self.effects.push(Effect::Particle(Particle {
+                    kind: ParticleKind::Feather,
+                    position: egui::pos2(rng.random_range(0.0..GRID_WIDTH as f32), top + rng.random_range(0.0..4.0)),
+                    velocity: egui::Vec2::ZERO,
+                    color: FEATHER_COLOR,
+                    angle: rng.random_range(0.0..std::f32::consts::TAU),
+                    life: FEATHER_LIFE * rng.random_range(0.7..1.0),
+                }));
+            }
+        }
+    }
+
+    pub(crate) fn update_effects(&mut self) {
+        let dt = self.effects_updated.elapsed().as_secs_f32().min(MAX_FRAME_TIME);
+        self.effects_updated = Instant::now();
+        for effect in &mut self.effects {
+            effect.update(dt);
+        }
+        self.effects.retain(|effect| !effect.is_finished());
+        self.combo_meter_moving = self.update_combo_meter(dt);
+    }
+
+    pub(crate) fn draw_effects(&self, painter: &egui::Painter, board: egui::Rect, cell_size: f32) {
+        for effect in &self.effects {
+            effect.draw(painter, board, cell_size);
+        }
+    }
+}
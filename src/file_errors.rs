@@ -0,0 +1,171 @@
+// Problems with the game's files. Nothing that goes wrong reading or writing
+// a file stops the game; each problem is shown in the corner of the screen
+// instead, for a while or, if the save can be tried again, until it's retried
+// or dismissed.
+
+use eframe::egui;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::daily::save_daily_results;
+use crate::profile::save_profiles;
+use crate::splits::save_best_splits;
+use crate::CrowsTetris;
+
+const SHOWN_FOR: Duration = Duration::from_secs(8);
+
+// What the Retry button does
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Retry {
+    SaveSettings,
+    LoadSettings,
+    SaveHighScores,
+    SaveLifetimeStats,
+    SaveBindings,
+    SaveControlProfiles,
+    SaveDailyResults,
+    SaveProfiles,
+    SaveSplits,
+    // The file in the replays folder the last run's replay was going to
+    SaveReplay(String),
+    SaveGameHistory,
+    Autosave,
+    // The sound pack's name
+    LoadSoundPack(String),
+}
+
+impl Retry {
+    // Goes in front of the error itself
+    fn failure(&self) -> &'static str {
+        match self {
+            Retry::SaveSettings => "Couldn't save the settings:",
+            Retry::LoadSettings => "Couldn't read the settings:",
+            Retry::SaveHighScores => "Couldn't save the high scores:",
+            Retry::SaveLifetimeStats => "Couldn't save the lifetime stats:",
+            Retry::SaveBindings => "Couldn't save the key bindings:",
+            Retry::SaveControlProfiles => "Couldn't save the control profiles:",
+            Retry::SaveDailyResults => "Couldn't save the daily results:",
+            Retry::SaveProfiles => "Couldn't save the profiles:",
+            Retry::SaveSplits => "Couldn't save the best splits:",
+            Retry::SaveReplay(_) => "Couldn't save the replay:",
+            Retry::SaveGameHistory => "Couldn't save the game history:",
+            Retry::Autosave => "Couldn't autosave the run:",
+            Retry::LoadSoundPack(_) => "Couldn't load the sound pack:",
+        }
+    }
+}
+
+pub struct FileError {
+    message: String,
+    pub retry: Option<Retry>,
+    shown: Instant,
+}
+
+impl FileError {
+    pub fn new(message: String, retry: Option<Retry>) -> Self {
+        Self {
+            message,
+            retry,
+            shown: Instant::now(),
+        }
+    }
+}
+
+impl CrowsTetris {
+    // The same problem again replaces the one already shown rather than piling up
+    pub(crate) fn report_file_error(&mut self, message: String, retry: Option<Retry>) {
+        self.file_errors.retain(|error| error.message != message);
+        self.file_errors.push(FileError::new(message, retry));
+    }
+
+    // Reports what went wrong, with a Retry button that does it again
+    pub(crate) fn report_failure(&mut self, retry: Retry, error: impl std::fmt::Display) {
+        let message = format!("{} {}", self.tr(retry.failure()), error);
+        self.report_file_error(message, Some(retry));
+    }
+
+    pub(crate) fn check_saved(&mut self, saved: io::Result<()>, retry: Retry) {
+        if let Err(error) = saved {
+            self.report_failure(retry, error);
+        }
+    }
+
+    // Called once whatever the retry was for has worked some other way
+    pub(crate) fn clear_file_error(&mut self, retry: &Retry) {
+        self.file_errors.retain(|error| error.retry.as_ref() != Some(retry));
+    }
+
+    // Tried again from the start; failing again reports it again
+    pub(crate) fn retry(&mut self, ctx: &egui::Context, retry: Retry) {
+        self.clear_file_error(&retry);
+        match &retry {
+            Retry::SaveSettings => self.save_settings(),
+            Retry::LoadSettings => self.load_settings(ctx),
+            Retry::SaveHighScores => self.save_high_score_table(),
+            Retry::SaveLifetimeStats => self.save_lifetime(),
+            Retry::SaveBindings => {
+                let saved = self.controls.save();
+                self.check_saved(saved, retry);
+            }
+            Retry::SaveControlProfiles => {
+                let saved = self.controls.save_profiles();
+                self.check_saved(saved, retry);
+            }
+            Retry::SaveDailyResults => self.check_saved(save_daily_results(&self.daily_results), retry),
+            Retry::SaveProfiles => self.check_saved(save_profiles(&self.profiles), retry),
+            Retry::SaveSplits => self.check_saved(save_best_splits(&self.best_splits), retry),
+            Retry::SaveReplay(file) => self.save_last_replay(file.clone()),
+            Retry::SaveGameHistory => self.save_game_history(),
+            // Done again straight away if the run is still going
+            Retry::Autosave => self.autosaved_at = None,
+            Retry::LoadSoundPack(name) => self.load_sound_pack_setting(name.clone()),
+        }
+    }
+
+    // Controls save themselves as they change; what failed is passed on here
+    pub(crate) fn report_control_save_errors(&mut self) {
+        if let Some((retry, error)) = self.controls.save_error.take() {
+            self.report_failure(retry, error);
+        }
+    }
+
+    pub(crate) fn render_file_errors(&mut self, ctx: &egui::Context) {
+        self.file_errors.retain(|error| error.retry.is_some() || error.shown.elapsed() < SHOWN_FOR);
+        if self.file_errors.is_empty() {
+            return;
+        }
+
+        let mut retry = None;
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("file_errors"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (i, error) in self.file_errors.iter().enumerate() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_max_width(260.0);
+                        ui.horizontal(|ui| {
+                            ui.colored_label(egui::Color32::RED, &error.message);
+                            if let Some(action) = &error.retry {
+                                if ui.small_button(self.tr("Retry")).clicked() {
+                                    retry = Some(action.clone());
+                                }
+                            }
+                            if ui.small_button("×").on_hover_text(self.tr("Dismiss")).clicked() {
+                                dismissed = Some(i);
+                            }
+                        });
+                    });
+                }
+            });
+
+        if let Some(i) = dismissed {
+            self.file_errors.remove(i);
+        }
+        if let Some(action) = retry {
+            self.retry(ctx, action);
+        }
+        // Keeps the screen coming back to take them down after their time
+        ctx.request_repaint_after(Duration::from_secs(1));
+    }
+}
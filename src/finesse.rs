@@ -0,0 +1,108 @@
+// Finesse: whether a piece got where it was dropped in as few presses as it
+// could have. Every hard-dropped piece is checked against the fewest presses
+// that reach the same spot from where it spawned, counting a move held into
+// the wall as one press. Soft-dropped pieces aren't judged, since they may
+// have been tucked somewhere the presses at the top can't reach, and neither
+// are pieces placed with the mouse, touch or one switch.
+
+use eframe::egui;
+use std::collections::{HashSet, VecDeque};
+
+use crate::classic::Ruleset;
+use crate::controls::Action;
+use crate::{turned, Block, BlockType, CrowsTetris, GRID_WIDTH};
+
+const FINESSE_ACTIONS: [Action; 4] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Rotate,
+    Action::RotateCounterclockwise,
+];
+
+// The columns the piece covers and its rows counted from its top, which is what a drop decides
+fn footprint(block: &Block) -> Vec<(i32, i32)> {
+    let mut cells = Vec::new();
+    for (dy, row) in block.shape.iter().enumerate() {
+        for (dx, &cell) in row.iter().enumerate() {
+            if cell != 0 {
+                cells.push((block.position.0 + dx as i32, block.position.1 + dy as i32));
+            }
+        }
+    }
+    let top = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    cells.iter_mut().for_each(|(_, y)| *y -= top);
+    cells.sort();
+    cells
+}
+
+fn fits(block: &Block) -> bool {
+    footprint(block).iter().all(|&(x, _)| (0..GRID_WIDTH as i32).contains(&x))
+}
+
+fn shifted(block: &Block, dx: i32) -> Block {
+    Block {
+        position: (block.position.0 + dx, block.position.1),
+        ..block.clone()
+    }
+}
+
+// Every place one press takes the piece: a step, a turn, or a held move to the wall
+fn one_press(ruleset: Ruleset, block: &Block) -> Vec<Block> {
+    let mut moves = vec![shifted(block, -1), shifted(block, 1), turned(ruleset, block, true), turned(ruleset, block, false)];
+    for dx in [-1, 1] {
+        let mut wall = block.clone();
+        while fits(&shifted(&wall, dx)) {
+            wall = shifted(&wall, dx);
+        }
+        moves.push(wall);
+    }
+    moves
+}
+
+// The fewest presses from the spawn spot to where the piece ended up, on an open board
+pub fn fewest_presses(ruleset: Ruleset, block_type: BlockType, placed: &Block) -> Option<u32> {
+    let goal = footprint(placed);
+    let start = Block::new(block_type);
+    let mut seen = HashSet::from([(start.shape.clone(), start.position)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((block, presses)) = queue.pop_front() {
+        if footprint(&block) == goal {
+            return Some(presses);
+        }
+        for next in one_press(ruleset, &block) {
+            if fits(&next) && seen.insert((next.shape.clone(), next.position)) {
+                queue.push_back((next, presses + 1));
+            }
+        }
+    }
+    None
+}
+
+impl CrowsTetris {
+    pub(crate) fn count_finesse_presses(&mut self, ctx: &egui::Context) {
+        let pressed = FINESSE_ACTIONS
+            .iter()
+            .filter(|&&action| ctx.input(|i| self.controls.pressed_once(i, action)))
+            .count();
+        self.piece_presses += pressed as u32;
+    }
+
+    // Called as a piece is hard dropped, before it locks
+    pub(crate) fn judge_finesse(&mut self) {
+        if self.piece_soft_dropped || self.soft_dropping || self.mouse_controls || self.touch_controls || self.one_switch {
+            return;
+        }
+        let Some(block) = &self.active_block else {
+            return;
+        };
+        if fewest_presses(self.ruleset, block.block_type, block).is_some_and(|fewest| self.piece_presses > fewest) {
+            self.finesse_faults += 1;
+        }
+    }
+
+    // Each piece starts with a clean slate
+    pub(crate) fn reset_finesse(&mut self) {
+        self.piece_presses = 0;
+        self.piece_soft_dropped = false;
+    }
+}
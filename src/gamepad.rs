@@ -0,0 +1,239 @@
+// Controller input through gilrs. Buttons are named after gilrs' buttons
+// ("South", "DPadLeft", ...), which is what gamepad bindings store. The left
+// stick counts as the d-pad once it's pushed past the deadzone. Outside of
+// play the d-pad and face buttons drive the menus as arrow keys, Enter and
+// Escape. Controllers that can rumble give a short pulse on hard drops and
+// line clears.
+
+use eframe::egui;
+use gilrs::ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Repeat, Replay, Ticks};
+use gilrs::{Axis, EventType, GamepadId, Gilrs};
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::controls::{Binding, InputDevice};
+use crate::{CrowsTetris, GameState};
+
+pub const MIN_DEADZONE: f32 = 0.1;
+pub const MAX_DEADZONE: f32 = 0.9;
+// Gamepad events don't wake egui up, so it's polled at this rate while one is connected
+const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+const MENU_KEYS: [(&str, egui::Key); 6] = [
+    ("DPadUp", egui::Key::ArrowUp),
+    ("DPadDown", egui::Key::ArrowDown),
+    ("DPadLeft", egui::Key::ArrowLeft),
+    ("DPadRight", egui::Key::ArrowRight),
+    ("South", egui::Key::Enter),
+    ("East", egui::Key::Escape),
+];
+
+// The d-pad directions the stick is pushed towards
+pub fn stick_directions(x: f32, y: f32, deadzone: f32) -> Vec<&'static str> {
+    let mut directions = Vec::new();
+    if x <= -deadzone {
+        directions.push("DPadLeft");
+    }
+    if x >= deadzone {
+        directions.push("DPadRight");
+    }
+    // Up is positive on gilrs' axes
+    if y >= deadzone {
+        directions.push("DPadUp");
+    }
+    if y <= -deadzone {
+        directions.push("DPadDown");
+    }
+    directions
+}
+
+pub const HARD_DROP_RUMBLE: (f32, Duration) = (0.2, Duration::from_millis(60));
+
+// Strength from 0 to 1 and length of the pulse for a clear; a Tetris gets the full shake
+pub fn clear_rumble(lines: usize) -> (f32, Duration) {
+    match lines {
+        0 => (0.0, Duration::ZERO),
+        1..=3 => (0.25 + 0.15 * lines as f32, Duration::from_millis(100 + 40 * lines as u64)),
+        _ => (1.0, Duration::from_millis(350)),
+    }
+}
+
+// Controllers are told apart by their UUID, so each keeps its bindings across sessions
+fn device_of(gilrs: &Gilrs, id: GamepadId) -> (InputDevice, String) {
+    let gamepad = gilrs.gamepad(id);
+    let uuid: String = gamepad.uuid().iter().map(|byte| format!("{:02x}", byte)).collect();
+    (InputDevice::Gamepad(uuid), gamepad.name().to_string())
+}
+
+pub struct Gamepads {
+    gilrs: Option<Gilrs>,
+    pub deadzone: f32,
+    stick: (f32, f32),
+    buttons_down: HashSet<String>,
+    // The name of the controller used last, for the settings screen
+    pub active_name: Option<String>,
+    pub rumble: bool,
+    // Kept so the pulse isn't cut off when it's dropped; a new one replaces it
+    rumble_effect: Option<Effect>,
+}
+
+impl Gamepads {
+    // Without a controller backend (or in the self-test) nothing is ever pressed
+    pub fn new(gilrs: Option<Gilrs>) -> Self {
+        Self {
+            gilrs,
+            deadzone: 0.5,
+            stick: (0.0, 0.0),
+            buttons_down: HashSet::new(),
+            active_name: None,
+            rumble: true,
+            rumble_effect: None,
+        }
+    }
+
+    pub fn connect() -> Self {
+        Self::new(Gilrs::new().ok())
+    }
+
+    // Both motors at once, on every connected controller that has them
+    pub fn rumble(&mut self, (strength, length): (f32, Duration)) {
+        let Some(gilrs) = self.gilrs.as_mut().filter(|_| self.rumble && strength > 0.0) else {
+            return;
+        };
+        let ids: Vec<GamepadId> = gilrs
+            .gamepads()
+            .filter(|(_, gamepad)| gamepad.is_ff_supported())
+            .map(|(id, _)| id)
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        let play_for = Ticks::from_ms(length.as_millis() as u32);
+        let magnitude = (strength.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+        let scheduling = Replay { play_for, ..Default::default() };
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect { kind: BaseEffectType::Strong { magnitude }, scheduling, ..Default::default() })
+            .add_effect(BaseEffect { kind: BaseEffectType::Weak { magnitude }, scheduling, ..Default::default() })
+            .gamepads(&ids)
+            .repeat(Repeat::For(play_for))
+            .finish(gilrs);
+        if let Ok(effect) = effect {
+            effect.play().ok();
+            self.rumble_effect = Some(effect);
+        }
+    }
+
+    fn connected(&self) -> bool {
+        self.gilrs.as_ref().is_some_and(|gilrs| gilrs.gamepads().next().is_some())
+    }
+
+    fn down(&self) -> HashSet<String> {
+        let stick = stick_directions(self.stick.0, self.stick.1, self.deadzone);
+        self.buttons_down.iter().cloned().chain(stick.into_iter().map(str::to_string)).collect()
+    }
+
+    // Drains the pending events. Returns the device that sent them, if any, and
+    // the buttons pressed since the last poll.
+    fn poll(&mut self) -> (Option<InputDevice>, HashSet<String>) {
+        let was_down = self.down();
+        let Some(gilrs) = self.gilrs.as_mut() else {
+            return (None, HashSet::new());
+        };
+        let mut pressed = HashSet::new();
+        let mut device = None;
+
+        while let Some(event) = gilrs.next_event() {
+            match event.event {
+                EventType::ButtonPressed(button, _) => {
+                    let name = format!("{:?}", button);
+                    pressed.insert(name.clone());
+                    self.buttons_down.insert(name);
+                    device = Some(device_of(gilrs, event.id));
+                }
+                EventType::ButtonReleased(button, _) => {
+                    self.buttons_down.remove(&format!("{:?}", button));
+                }
+                EventType::AxisChanged(Axis::LeftStickX, value, _) => {
+                    self.stick.0 = value;
+                    if value.abs() >= self.deadzone {
+                        device = Some(device_of(gilrs, event.id));
+                    }
+                }
+                EventType::AxisChanged(Axis::LeftStickY, value, _) => {
+                    self.stick.1 = value;
+                    if value.abs() >= self.deadzone {
+                        device = Some(device_of(gilrs, event.id));
+                    }
+                }
+                EventType::Disconnected => {
+                    self.buttons_down.clear();
+                    self.stick = (0.0, 0.0);
+                }
+                _ => {}
+            }
+        }
+
+        // Pushing the stick over the deadzone presses that direction
+        pressed.extend(self.down().difference(&was_down).cloned());
+        let device = device.map(|(device, name)| {
+            self.active_name = Some(name);
+            device
+        });
+        (device, pressed)
+    }
+}
+
+impl CrowsTetris {
+    // Called with each frame's input before egui sees it, so the menu keys a
+    // controller stands in for move focus just like real ones
+    pub(crate) fn poll_gamepads(&mut self, ctx: &egui::Context, raw_input: &mut egui::RawInput) {
+        if raw_input.events.iter().any(|event| matches!(event, egui::Event::Key { .. })) {
+            self.controls.set_active_device(InputDevice::Keyboard);
+        }
+
+        let (device, pressed) = self.gamepads.poll();
+        if let Some(device) = device {
+            self.controls.set_active_device(device);
+        }
+        self.controls.set_buttons(pressed.clone(), self.gamepads.down());
+        if self.gamepads.connected() {
+            ctx.request_repaint_after(POLL_INTERVAL);
+        }
+
+        // A button pressed while rebinding is the new binding, not a menu key
+        if let Some(action) = self.rebinding {
+            if let Some(button) = pressed.into_iter().next() {
+                self.rebind_swap = self.controls.rebind(action, Binding::Button(button)).map(|other| (action, other));
+                self.rebinding = None;
+            }
+            return;
+        }
+
+        let in_menu = self.state != GameState::Playing || self.is_paused;
+        if !in_menu {
+            return;
+        }
+        for (button, key) in MENU_KEYS {
+            if pressed.contains(button) {
+                for key_pressed in [true, false] {
+                    raw_input.events.push(egui::Event::Key {
+                        key,
+                        physical_key: None,
+                        pressed: key_pressed,
+                        repeat: false,
+                        modifiers: egui::Modifiers::NONE,
+                    });
+                }
+            }
+        }
+    }
+
+    pub(crate) fn device_label(&self) -> String {
+        match (self.controls.active_device(), &self.gamepads.active_name) {
+            (InputDevice::Keyboard, _) => self.tr("Keyboard").to_string(),
+            (InputDevice::Gamepad(_), Some(name)) => name.clone(),
+            (InputDevice::Gamepad(_), None) => self.tr("Gamepad").to_string(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+// Garbage rows: solid lines of gray cells with a single hole, pushed up from
+// the bottom of the board.
+
+use rand::Rng;
+use std::time::Instant;
+
+use crate::{CrowsTetris, Grid, GRID_HEIGHT, GRID_WIDTH};
+
+pub const GARBAGE_CELL: u8 = 8;
+
+// Shifts the grid up and fills the bottom `count` rows with garbage.
+pub fn push_garbage_rows(grid: &mut Grid, count: usize, rng: &mut impl Rng) {
+    let count = count.min(GRID_HEIGHT);
+    grid.copy_within(count.., 0);
+
+    for row in &mut grid[GRID_HEIGHT - count..] {
+        *row = [GARBAGE_CELL; GRID_WIDTH];
+        row[rng.random_range(0..GRID_WIDTH)] = 0;
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn add_garbage_rows(&mut self, count: usize) {
+        let count = count.min(GRID_HEIGHT);
+        push_garbage_rows(&mut self.grid, count, &mut self.game_rng);
+        self.locked_at.copy_within(count.., 0);
+        for row in &mut self.locked_at[GRID_HEIGHT - count..] {
+            *row = [Some(Instant::now()); GRID_WIDTH];
+        }
+    }
+
+    pub(crate) fn garbage_rows_left(&self) -> usize {
+        self.grid.iter().filter(|row| row.contains(&GARBAGE_CELL)).count()
+    }
+}
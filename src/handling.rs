@@ -0,0 +1,186 @@
+// Handling: how the piece moves while a direction is held. After the
+// delayed auto shift (DAS) it repeats a column every auto repeat rate (ARR),
+// and holding soft drop makes gravity that many times faster. The settings
+// screen has a strip to try the tuning on.
+
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use crate::controls::{Action, Controls};
+use crate::{CrowsTetris, GRID_WIDTH};
+
+pub const MAX_DAS_MS: u64 = 400;
+pub const MAX_ARR_MS: u64 = 200;
+pub const MIN_SOFT_DROP_FACTOR: u32 = 2;
+pub const MAX_SOFT_DROP_FACTOR: u32 = 40;
+const FRAME_MS: f64 = 1000.0 / 60.0;
+const TEST_COLUMNS: i32 = 10;
+const TEST_CELL_SIZE: f32 = 16.0;
+
+pub struct Handling {
+    pub das: Duration,
+    // Zero slides the piece all the way at once
+    pub arr: Duration,
+    pub soft_drop_factor: u32,
+}
+
+impl Default for Handling {
+    fn default() -> Self {
+        Self {
+            das: Duration::from_millis(170),
+            arr: Duration::from_millis(50),
+            soft_drop_factor: 20,
+        }
+    }
+}
+
+// Milliseconds with the frames at 60 fps they come to, as players compare them
+fn format_ms(ms: f64) -> String {
+    format!("{:.0} ms ({:.1} F)", ms, ms / FRAME_MS)
+}
+
+struct HeldShift {
+    direction: i32,
+    since: Instant,
+    repeats: u32,
+}
+
+#[derive(Default)]
+pub struct AutoShift {
+    held: Option<HeldShift>,
+}
+
+// For each direction: pressed this frame (not counting the system's key repeat) and still down
+pub type ShiftInput = ((bool, bool), (bool, bool));
+
+impl AutoShift {
+    // The columns to move this frame, negative for left. The newest press wins
+    // when both directions are down, and letting go of it hands back to the other.
+    pub fn update(&mut self, (left, right): ShiftInput, handling: &Handling, now: Instant) -> i32 {
+        let mut steps = 0;
+        let pressed = if right.0 { Some(1) } else if left.0 { Some(-1) } else { None };
+        if let Some(direction) = pressed {
+            self.held = Some(HeldShift { direction, since: now, repeats: 0 });
+            steps += direction;
+        }
+
+        let down = |direction: i32| if direction > 0 { right.1 } else { left.1 };
+        match self.held.as_mut() {
+            Some(held) if down(held.direction) => {
+                let charged = now.saturating_duration_since(held.since);
+                if charged >= handling.das {
+                    if handling.arr.is_zero() {
+                        steps += held.direction * GRID_WIDTH as i32;
+                    } else {
+                        let due = ((charged - handling.das).as_millis() / handling.arr.as_millis()) as u32 + 1;
+                        steps += held.direction * (due - held.repeats) as i32;
+                        held.repeats = due;
+                    }
+                }
+            }
+            Some(held) => {
+                let other = -held.direction;
+                self.held = down(other).then_some(HeldShift { direction: other, since: now, repeats: 0 });
+            }
+            None => {}
+        }
+        steps
+    }
+
+    pub fn active(&self) -> bool {
+        self.held.is_some()
+    }
+}
+
+fn shift_input(controls: &Controls, input: &egui::InputState) -> ShiftInput {
+    let state = |action| (controls.pressed_once(input, action), controls.held(input, action));
+    (state(Action::MoveLeft), state(Action::MoveRight))
+}
+
+impl CrowsTetris {
+    // Columns to move the falling piece this frame, from the keys and from touches or the mouse
+    pub(crate) fn shift_steps(&mut self, ctx: &egui::Context) -> i32 {
+        let input = ctx.input(|i| shift_input(&self.controls, i));
+        let pointer: i32 = self
+            .pointer_actions
+            .iter()
+            .map(|action| match action {
+                Action::MoveLeft => -1,
+                Action::MoveRight => 1,
+                _ => 0,
+            })
+            .sum();
+        self.auto_shift.update(input, &self.handling, Instant::now()) + pointer
+    }
+
+    // Soft drop speeds gravity up for as long as it's held. The piece keeps how far it
+    // had sunk toward the next row, so pressing late in a slow step doesn't make up the
+    // whole step at the fast rate in one go, and letting go doesn't jump it back.
+    pub(crate) fn set_soft_dropping(&mut self, held: bool) {
+        if held == self.soft_dropping {
+            return;
+        }
+        let progress = (self.last_update.elapsed().as_secs_f32() / self.gravity_interval().as_secs_f32()).min(1.0);
+        self.soft_dropping = held;
+        self.piece_soft_dropped |= held;
+        self.last_update = Instant::now() - self.gravity_interval().mul_f32(progress);
+    }
+
+    // Gravity while soft drop is held is this many times faster
+    pub(crate) fn soft_drop_factor(&self) -> u32 {
+        if self.soft_dropping {
+            self.handling.soft_drop_factor
+        } else {
+            1
+        }
+    }
+
+    pub(crate) fn render_handling_settings(&mut self, ui: &mut egui::Ui) {
+        let mut das_ms = self.handling.das.as_millis() as u64;
+        let label = self.tr("Delayed auto shift");
+        let slider = egui::Slider::new(&mut das_ms, 0..=MAX_DAS_MS).custom_formatter(|ms, _| format_ms(ms));
+        if ui.add(slider.text(label)).changed() {
+            self.handling.das = Duration::from_millis(das_ms);
+        }
+
+        let mut arr_ms = self.handling.arr.as_millis() as u64;
+        let label = self.tr("Auto repeat rate");
+        let slider = egui::Slider::new(&mut arr_ms, 0..=MAX_ARR_MS).custom_formatter(|ms, _| format_ms(ms));
+        if ui.add(slider.text(label)).changed() {
+            self.handling.arr = Duration::from_millis(arr_ms);
+        }
+
+        let label = self.tr("Soft drop speed");
+        ui.add(
+            egui::Slider::new(&mut self.handling.soft_drop_factor, MIN_SOFT_DROP_FACTOR..=MAX_SOFT_DROP_FACTOR)
+                .custom_formatter(|factor, _| format!("×{}", factor))
+                .text(label),
+        );
+
+        ui.label(self.tr("Hold left or right to try it:"));
+        self.render_handling_test(ui);
+    }
+
+    // A block on a short strip that moves with the move keys and the tuning above
+    fn render_handling_test(&mut self, ui: &mut egui::Ui) {
+        // Rebinding takes the next key, so the strip leaves it alone
+        if self.rebinding.is_none() {
+            let input = ui.input(|i| shift_input(&self.controls, i));
+            let steps = self.handling_test.update(input, &self.handling, Instant::now());
+            self.handling_test_column = (self.handling_test_column + steps).clamp(0, TEST_COLUMNS - 1);
+        }
+        if self.handling_test.active() {
+            ui.ctx().request_repaint();
+        }
+
+        let size = egui::vec2(TEST_COLUMNS as f32, 1.0) * TEST_CELL_SIZE;
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let theme = self.theme();
+        painter.rect_filled(rect, 2.0, theme.board);
+        let min = rect.min + egui::vec2(self.handling_test_column as f32 * TEST_CELL_SIZE, 0.0);
+        let cell = egui::Rect::from_min_size(min, egui::Vec2::splat(TEST_CELL_SIZE));
+        let color = theme.cell_color(1).unwrap_or(egui::Color32::WHITE);
+        self.cell_style.paint(&painter, cell, color);
+    }
+}
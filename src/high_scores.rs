@@ -0,0 +1,313 @@
+// The high score tables, one per mode, saved together to high_scores.toml
+// with a [[score]] table per entry: who, how much, and how the run went
+// (mode, lines, level, time, and when it was played). Most modes rank by
+// score; the ones that race to a goal rank finished runs by time. The
+// `name,score` lines of older versions' high_scores.txt are imported the first
+// time there's no high_scores.toml, marked as legacy scores; the old file is
+// left as it was.
+//
+// Each entry is saved with a signature worked out from everything in it, the
+// run's seed and a digest of its replay. An entry edited by hand no longer
+// matches its signature and is shown as unverified. The key is in the game
+// itself, so this only catches casual edits, not someone set on forging one.
+
+use eframe::egui;
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use toml_edit::DocumentMut;
+
+use crate::daily::{self, SECONDS_PER_DAY};
+use crate::file_errors::Retry;
+use crate::replay::{encode_events, Replay};
+use crate::{data_dir, fnv1a, format_time, profile, CrowsTetris, GameMode};
+
+pub const HIGH_SCORE_FILE: &str = "high_scores.toml";
+pub const OLD_HIGH_SCORE_FILE: &str = "high_scores.txt";
+pub const HIGH_SCORE_COUNT: usize = 10;
+const SIGNING_KEY: &str = "crows-tetris high scores";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighScore {
+    pub name: String,
+    pub score: i32,
+    pub lines: u32,
+    pub level: u32,
+    pub mode: GameMode,
+    pub duration: Duration,
+    // Seconds since 1970-01-01 UTC; 0 for scores from before it was recorded
+    pub timestamp: u64,
+    pub seed: u64,
+    // 0 for runs without a replay
+    pub replay_digest: u64,
+    // Whether the saved signature matched; not saved itself
+    pub verified: bool,
+    // Imported from the old name,score file
+    pub legacy: bool,
+}
+
+impl HighScore {
+    fn signature(&self) -> u64 {
+        let fields = format!(
+            "{}|{}|{}|{}|{}|{:?}|{}|{}|{}|{}|{}",
+            SIGNING_KEY,
+            self.name,
+            self.score,
+            self.lines,
+            self.level,
+            self.mode,
+            self.duration.as_millis(),
+            self.timestamp,
+            self.seed,
+            self.replay_digest,
+            self.legacy,
+        );
+        fnv1a(fields.as_bytes())
+    }
+}
+
+pub fn replay_digest(replay: &Replay) -> u64 {
+    fnv1a(encode_events(&replay.events).as_bytes())
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Ranking {
+    Score,
+    Time,
+}
+
+pub fn ranking(mode: GameMode) -> Ranking {
+    match mode {
+        GameMode::Sprint | GameMode::Dig => Ranking::Time,
+        _ => Ranking::Score,
+    }
+}
+
+// Best first
+fn compare(a: &HighScore, b: &HighScore) -> Ordering {
+    match ranking(a.mode) {
+        Ranking::Score => b.score.cmp(&a.score),
+        Ranking::Time => a.duration.cmp(&b.duration),
+    }
+}
+
+// The best entries for one mode, best first
+pub fn leaderboard(high_scores: &[HighScore], mode: GameMode) -> Vec<&HighScore> {
+    let mut entries: Vec<&HighScore> = high_scores.iter().filter(|entry| entry.mode == mode).collect();
+    entries.sort_by(|a, b| compare(a, b));
+    entries.truncate(HIGH_SCORE_COUNT);
+    entries
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
+
+fn marathon() -> GameMode {
+    GameMode::Marathon
+}
+
+fn is_false(flag: &bool) -> bool {
+    !flag
+}
+
+// A [[score]] table as it's saved. Only the name and score have to be there.
+#[derive(Serialize, Deserialize)]
+struct ScoreRecord {
+    name: String,
+    score: i32,
+    #[serde(default)]
+    lines: u32,
+    #[serde(default)]
+    level: u32,
+    #[serde(default = "marathon")]
+    mode: GameMode,
+    #[serde(default)]
+    duration_ms: u64,
+    #[serde(default)]
+    timestamp: u64,
+    // Seeds use all 64 bits, more than a TOML integer holds
+    #[serde(default)]
+    seed: String,
+    // Hex, like the signature
+    #[serde(default)]
+    replay_digest: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    legacy: bool,
+    // Unverified entries stay that way rather than being signed as they are now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+}
+
+impl From<&HighScore> for ScoreRecord {
+    fn from(entry: &HighScore) -> Self {
+        Self {
+            name: entry.name.clone(),
+            score: entry.score,
+            lines: entry.lines,
+            level: entry.level,
+            mode: entry.mode,
+            duration_ms: entry.duration.as_millis() as u64,
+            timestamp: entry.timestamp,
+            seed: entry.seed.to_string(),
+            replay_digest: format!("{:016x}", entry.replay_digest),
+            legacy: entry.legacy,
+            signature: entry.verified.then(|| format!("{:016x}", entry.signature())),
+        }
+    }
+}
+
+impl From<ScoreRecord> for HighScore {
+    fn from(record: ScoreRecord) -> Self {
+        let hex = |text: &str| u64::from_str_radix(text, 16).ok();
+        let mut entry = HighScore {
+            name: record.name,
+            score: record.score,
+            lines: record.lines,
+            level: record.level,
+            mode: record.mode,
+            duration: Duration::from_millis(record.duration_ms),
+            timestamp: record.timestamp,
+            seed: record.seed.parse().unwrap_or(0),
+            replay_digest: hex(&record.replay_digest).unwrap_or(0),
+            verified: false,
+            legacy: record.legacy,
+        };
+        entry.verified = record.signature.as_deref().and_then(hex) == Some(entry.signature());
+        entry
+    }
+}
+
+#[derive(Serialize)]
+struct ScoreFile {
+    score: Vec<ScoreRecord>,
+}
+
+// Old entries only had a name and a score, from whatever mode was being played. They're
+// signed as they're brought in, so they can be told apart from ones added to the file later.
+fn import_old_scores() -> Vec<HighScore> {
+    let Ok(text) = fs::read_to_string(data_dir::path(OLD_HIGH_SCORE_FILE)) else {
+        return Vec::new();
+    };
+    text.lines()
+        .filter_map(|line| {
+            let (name, score) = line.split_once(',')?;
+            Some(HighScore {
+                name: name.to_string(),
+                score: score.parse().ok()?,
+                lines: 0,
+                level: 0,
+                mode: GameMode::Marathon,
+                duration: Duration::ZERO,
+                timestamp: 0,
+                seed: 0,
+                replay_digest: 0,
+                verified: true,
+                legacy: true,
+            })
+        })
+        .collect()
+}
+
+pub fn load_high_scores() -> Vec<HighScore> {
+    let Ok(text) = fs::read_to_string(data_dir::path(HIGH_SCORE_FILE)) else {
+        let imported = import_old_scores();
+        if !imported.is_empty() {
+            save_high_scores(&imported).ok();
+        }
+        return imported;
+    };
+    let Ok(document) = text.parse::<DocumentMut>() else {
+        return Vec::new();
+    };
+    // Read one by one, so an entry that can't be read doesn't take the others with it
+    let Some(scores) = document.get("score").and_then(|scores| scores.as_array_of_tables()) else {
+        return Vec::new();
+    };
+    scores
+        .iter()
+        .filter_map(|table| toml_edit::de::from_document::<ScoreRecord>(DocumentMut::from(table.clone())).ok())
+        .map(HighScore::from)
+        .collect()
+}
+
+pub fn save_high_scores(high_scores: &[HighScore]) -> io::Result<()> {
+    let file = ScoreFile {
+        score: high_scores.iter().map(ScoreRecord::from).collect(),
+    };
+    let text = toml_edit::ser::to_string_pretty(&file).map_err(io::Error::other)?;
+    data_dir::write_atomic(HIGH_SCORE_FILE, &text)
+}
+
+impl CrowsTetris {
+    // Keeps every mode's table sorted and trimmed to the best few. Starts from the file, so
+    // scores another copy of the game saved since this one loaded them aren't written over.
+    pub(crate) fn add_high_score(&mut self, entry: HighScore) {
+        for saved in load_high_scores() {
+            if !self.high_scores.contains(&saved) {
+                self.high_scores.push(saved);
+            }
+        }
+        self.high_scores.push(entry);
+        self.high_scores = GameMode::ALL
+            .into_iter()
+            .flat_map(|mode| leaderboard(&self.high_scores, mode))
+            .cloned()
+            .collect();
+        self.save_high_score_table();
+    }
+
+    pub(crate) fn save_high_score_table(&mut self) {
+        let saved = save_high_scores(&self.high_scores);
+        self.check_saved(saved, Retry::SaveHighScores);
+    }
+
+    // Whether the last run can go on its mode's table: a run ranked by time has to reach the goal
+    pub(crate) fn last_run_qualifies(&self) -> bool {
+        self.last_result
+            .as_ref()
+            .is_some_and(|result| ranking(result.mode) == Ranking::Score || result.completed)
+    }
+
+    pub(crate) fn render_high_scores(&self, ui: &mut egui::Ui, mode: GameMode) {
+        ui.heading(format!("{} {}", self.tr("High Scores:"), self.tr(mode.label())));
+        let entries = leaderboard(&self.high_scores, mode);
+        if entries.is_empty() {
+            ui.label(self.tr("No scores yet"));
+        }
+        for (i, entry) in entries.into_iter().enumerate() {
+            let result = match ranking(mode) {
+                Ranking::Score => entry.score.to_string(),
+                Ranking::Time => format_time(entry.duration),
+            };
+            let avatar = self.profiles.iter().find(|profile| profile.name == entry.name).map(|profile| profile.avatar);
+            ui.horizontal(|ui| {
+                if let Some(avatar) = avatar {
+                    profile::draw_crow(ui, avatar, 18.0);
+                }
+                ui.label(format!("{}. {} - {}", i + 1, entry.name, result));
+                if entry.legacy && entry.verified {
+                    ui.weak(self.tr("legacy")).on_hover_text(self.tr("Carried over from an older version's score file"));
+                } else if !entry.verified {
+                    ui.colored_label(egui::Color32::YELLOW, self.tr("unverified"))
+                        .on_hover_text(self.tr("This entry doesn't match its signature; the file may have been edited"));
+                }
+            });
+            // Scores carried over from the old file don't know how the run went
+            if entry.timestamp > 0 {
+                ui.small(format!(
+                    "{} · {} {} · {} {} · {} · {}",
+                    self.tr(entry.mode.label()),
+                    entry.lines,
+                    self.tr("lines"),
+                    self.tr("Level"),
+                    entry.level,
+                    format_time(entry.duration),
+                    daily::date_label(entry.timestamp / SECONDS_PER_DAY),
+                ));
+            }
+        }
+    }
+}
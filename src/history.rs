@@ -0,0 +1,150 @@
+// A record of every game, one [[game]] table per game added to the end of
+// game_history.toml as it finishes, and the "Export stats" action that writes
+// them all out as a CSV file for spreadsheets and other tools.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table};
+
+use crate::daily::{self, SECONDS_PER_DAY};
+use crate::file_errors::Retry;
+use crate::high_scores;
+use crate::settings::find_by_name;
+use crate::{data_dir, CrowsTetris, GameMode};
+
+pub const GAME_HISTORY_FILE: &str = "game_history.toml";
+const STATS_EXPORT_FILE: &str = "stats_export.csv";
+const CSV_HEADER: &str = "date,mode,score,lines,duration_s,pps,finesse_faults";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    // Seconds since 1970-01-01 UTC
+    pub timestamp: u64,
+    pub mode: GameMode,
+    pub score: i32,
+    pub lines: u32,
+    pub pieces: u32,
+    pub duration: Duration,
+    pub finesse_faults: u32,
+}
+
+impl GameRecord {
+    // Worked out the same way as on the results screen
+    fn pieces_per_second(&self) -> f64 {
+        self.pieces as f64 / self.duration.as_secs_f64().max(1.0)
+    }
+
+    fn csv_row(&self) -> String {
+        let seconds = self.timestamp % SECONDS_PER_DAY;
+        format!(
+            "{} {:02}:{:02}:{:02},{:?},{},{},{:.3},{:.2},{}",
+            daily::date_label(self.timestamp / SECONDS_PER_DAY),
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            self.mode,
+            self.score,
+            self.lines,
+            self.duration.as_secs_f64(),
+            self.pieces_per_second(),
+            self.finesse_faults,
+        )
+    }
+}
+
+fn read_record(table: &Table) -> Option<GameRecord> {
+    let number = |key| table.get(key)?.as_integer();
+    Some(GameRecord {
+        timestamp: number("timestamp")? as u64,
+        mode: find_by_name(&GameMode::ALL, table.get("mode")?.as_str()?)?,
+        score: number("score")? as i32,
+        lines: number("lines").unwrap_or(0) as u32,
+        pieces: number("pieces").unwrap_or(0) as u32,
+        duration: Duration::from_millis(number("duration_ms").unwrap_or(0) as u64),
+        finesse_faults: number("finesse_faults").unwrap_or(0) as u32,
+    })
+}
+
+pub fn load_game_history() -> Vec<GameRecord> {
+    let Some(document) = fs::read_to_string(data_dir::path(GAME_HISTORY_FILE))
+        .ok()
+        .and_then(|text| text.parse::<DocumentMut>().ok())
+    else {
+        return Vec::new();
+    };
+    document
+        .get("game")
+        .and_then(|games| games.as_array_of_tables())
+        .map(|games| games.iter().filter_map(read_record).collect())
+        .unwrap_or_default()
+}
+
+// Appended rather than rewritten, so the file never has to be read back in to grow
+pub fn append_game(record: &GameRecord) -> io::Result<()> {
+    let mut table = Table::new();
+    table["timestamp"] = value(record.timestamp as i64);
+    table["mode"] = value(format!("{:?}", record.mode));
+    table["score"] = value(record.score as i64);
+    table["lines"] = value(record.lines as i64);
+    table["pieces"] = value(record.pieces as i64);
+    table["duration_ms"] = value(record.duration.as_millis() as i64);
+    table["finesse_faults"] = value(record.finesse_faults as i64);
+    let mut games = ArrayOfTables::new();
+    games.push(table);
+    let mut document = DocumentMut::new();
+    document.insert("game", Item::ArrayOfTables(games));
+
+    let mut file = OpenOptions::new().create(true).append(true).open(data_dir::path(GAME_HISTORY_FILE))?;
+    writeln!(file, "{}", document)
+}
+
+pub fn history_csv(records: &[GameRecord]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    for record in records {
+        csv.push('\n');
+        csv.push_str(&record.csv_row());
+    }
+    csv.push('\n');
+    csv
+}
+
+// Writes every game so far to the export file and says where it went
+pub fn export_stats() -> Result<PathBuf, String> {
+    data_dir::write_atomic(STATS_EXPORT_FILE, &history_csv(&load_game_history())).map_err(|error| error.to_string())?;
+    Ok(data_dir::path(STATS_EXPORT_FILE))
+}
+
+impl CrowsTetris {
+    pub(crate) fn record_game_history(&mut self) {
+        let Some(result) = &self.last_result else {
+            return;
+        };
+        self.unsaved_games.push(GameRecord {
+            timestamp: high_scores::now(),
+            mode: result.mode,
+            score: result.score,
+            lines: result.lines,
+            pieces: result.pieces,
+            duration: result.duration,
+            finesse_faults: result.finesse_faults,
+        });
+        self.save_game_history();
+    }
+
+    // Games that couldn't be added to the file are kept and tried again with the next one
+    pub(crate) fn save_game_history(&mut self) {
+        let mut failed = None;
+        self.unsaved_games.retain(|record| match append_game(record) {
+            Ok(()) => false,
+            Err(error) => {
+                failed = Some(error);
+                true
+            }
+        });
+        if let Some(error) = failed {
+            self.report_failure(Retry::SaveGameHistory, error);
+        }
+    }
+}
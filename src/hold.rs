@@ -0,0 +1,122 @@
+// Hold: the player can stash the falling piece and swap it back in later,
+// once per piece. The side panels show the held piece and the next queue.
+
+use eframe::egui;
+use std::time::Instant;
+
+use crate::classic::Ruleset;
+use crate::controls::Action;
+use crate::replay::ReplayEvent;
+use crate::skin::Skin;
+use crate::theme::{CellStyle, Theme};
+use crate::{Block, BlockType, CrowsTetris};
+
+// How many upcoming pieces the next queue shows
+pub const NEXT_QUEUE_LEN: usize = 5;
+const PREVIEW_CELL_SIZE: f32 = 12.0;
+
+// Draws a piece in its spawn orientation, in a box that fits any piece.
+fn draw_piece_preview(
+    ui: &mut egui::Ui,
+    block_type: Option<BlockType>,
+    theme: &Theme,
+    skin: Option<&Skin>,
+    cell_style: CellStyle,
+) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(4.0, 2.0) * PREVIEW_CELL_SIZE, egui::Sense::hover());
+    let Some(block_type) = block_type else {
+        return;
+    };
+    let shape = Block::new(block_type).shape;
+    let offset = egui::vec2(4.0 - shape[0].len() as f32, 2.0 - shape.len() as f32) * PREVIEW_CELL_SIZE / 2.0;
+    let painter = ui.painter_at(rect);
+    for (y, row) in shape.iter().enumerate() {
+        for (x, &cell) in row.iter().enumerate() {
+            if cell != 0 {
+                let min = rect.min + offset + egui::vec2(x as f32, y as f32) * PREVIEW_CELL_SIZE;
+                let cell_rect = egui::Rect::from_min_size(min, egui::Vec2::splat(PREVIEW_CELL_SIZE));
+                match skin {
+                    Some(skin) => skin.paint_cell(&painter, cell_rect, block_type.cell(), 1.0),
+                    None => cell_style.paint(&painter, cell_rect, theme.piece_color(block_type)),
+                }
+            }
+        }
+    }
+}
+
+impl CrowsTetris {
+    // NES Tetris had no hold and showed a single next piece
+    pub(crate) fn hold_allowed(&self) -> bool {
+        self.ruleset == Ruleset::Modern
+    }
+
+    // Nor hard drop; pieces came down only as fast as gravity or a soft drop took them
+    pub(crate) fn hard_drop_allowed(&self) -> bool {
+        self.ruleset == Ruleset::Modern
+    }
+
+    fn next_queue_len(&self) -> usize {
+        match self.ruleset {
+            Ruleset::Modern => NEXT_QUEUE_LEN,
+            Ruleset::Classic => 1,
+        }
+    }
+
+    pub(crate) fn hold_piece(&mut self) {
+        self.record_replay(ReplayEvent::Input(Action::Hold));
+        if !self.hold_allowed() || self.hold_used {
+            return;
+        }
+        let Some(block) = self.active_block.take() else {
+            return;
+        };
+
+        let swapped_in = match self.held_piece.replace(block.block_type) {
+            Some(held) => Block::new(held),
+            None => self.generate_random_block(),
+        };
+        self.active_block = Some(swapped_in);
+        self.hold_used = true;
+        self.reset_finesse();
+        self.landed_at = None;
+        self.last_move_rotated = false;
+        self.last_update = Instant::now();
+    }
+
+    pub(crate) fn render_hold_panel(&self, ui: &mut egui::Ui) {
+        if !self.hold_allowed() {
+            return;
+        }
+        if !self.focus_mode {
+            ui.label(self.tr("Hold"));
+        }
+        ui.add_enabled_ui(!self.hold_used, |ui| draw_piece_preview(ui, self.held_piece, self.theme(), self.skin.as_ref(), self.cell_style));
+    }
+
+    pub(crate) fn render_next_panel(&self, ui: &mut egui::Ui) {
+        if !self.focus_mode {
+            ui.label(self.tr("Next"));
+        }
+        // A piece picked in practice mode comes before the queue
+        let upcoming = self.next_piece.into_iter().chain(self.next_queue.iter().copied());
+        for block_type in upcoming.take(self.next_queue_len()) {
+            draw_piece_preview(ui, Some(block_type), self.theme(), self.skin.as_ref(), self.cell_style);
+            ui.add_space(4.0);
+        }
+        if self.focus_mode {
+            return;
+        }
+
+        ui.add_space(20.0);
+        ui.label(egui::RichText::new(self.tr("Score:")).strong());
+        ui.label(egui::RichText::new(self.score.to_string()).size(21.0).strong());
+        let level = egui::RichText::new(format!("{} {}", self.tr("Level:"), self.level));
+        ui.label(if self.level_flash() { level.strong().color(egui::Color32::YELLOW) } else { level });
+        ui.label(format!("{} {}", self.tr("Lines:"), self.lines));
+        ui.add_space(10.0);
+        self.render_combo_meter(ui);
+        self.render_live_stats(ui);
+        ui.add_space(6.0);
+        self.render_stack_sparkline(ui);
+    }
+}
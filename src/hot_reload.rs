@@ -0,0 +1,93 @@
+// Hot reloading. Once a second the game looks at when the config file (key
+// bindings included), the theme files and the current skin were last changed, and
+// reads whichever changed again, so they can be edited in another window
+// and seen in the game straight away.
+
+use eframe::egui;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::config::CONFIG_FILE;
+use crate::theme::THEMES_DIR;
+use crate::{data_dir, skin, CrowsTetris};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+fn modified(path: PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+// Every file in the themes directory, so adding or removing one counts as a change too
+fn themes_modified() -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut files: Vec<_> = fs::read_dir(THEMES_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| {
+                    let path = entry.ok()?.path();
+                    Some((path.clone(), modified(path)))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+// When each watched file was last changed, as of the last look
+pub struct WatchedFiles {
+    checked: Instant,
+    config: Option<SystemTime>,
+    themes: Vec<(PathBuf, Option<SystemTime>)>,
+    // Which skin, since picking another isn't a change to watch for
+    skin: Option<(String, Option<SystemTime>)>,
+}
+
+impl WatchedFiles {
+    pub fn new() -> Self {
+        Self {
+            checked: Instant::now(),
+            config: modified(data_dir::path(CONFIG_FILE)),
+            themes: themes_modified(),
+            skin: None,
+        }
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn hot_reload(&mut self, ctx: &egui::Context) {
+        if self.watched.checked.elapsed() >= CHECK_INTERVAL {
+            self.reload_changed_files(ctx);
+        }
+    }
+
+    pub(crate) fn reload_changed_files(&mut self, ctx: &egui::Context) {
+        self.watched.checked = Instant::now();
+
+        let config = modified(data_dir::path(CONFIG_FILE));
+        if config != self.watched.config {
+            self.watched.config = config;
+            self.reload_settings(ctx);
+        }
+
+        let themes = themes_modified();
+        if themes != self.watched.themes {
+            self.watched.themes = themes;
+            self.reload_themes();
+        }
+
+        let skin = self.skin.as_ref().map(|skin| {
+            let modified = modified(PathBuf::from(skin::skin_path(&skin.name)));
+            (skin.name.clone(), modified)
+        });
+        if let (Some((name, modified)), Some((watched_name, watched_modified))) = (&skin, &self.watched.skin) {
+            // A half-written image keeps the skin as it was
+            if name == watched_name && modified != watched_modified {
+                if let Ok(reloaded) = skin::load_skin(ctx, name) {
+                    self.skin = Some(reloaded);
+                }
+            }
+        }
+        self.watched.skin = skin;
+    }
+}
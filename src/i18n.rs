@@ -0,0 +1,1346 @@
+// UI translations. The English text doubles as the lookup key, so any string
+// without a translation simply falls back to English.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    Spanish,
+    French,
+    German,
+    Portuguese,
+}
+
+impl Language {
+    pub const ALL: [Language; 5] = [
+        Language::English,
+        Language::Spanish,
+        Language::French,
+        Language::German,
+        Language::Portuguese,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Portuguese => "pt",
+        }
+    }
+
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+            Language::French => "Français",
+            Language::German => "Deutsch",
+            Language::Portuguese => "Português",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Language> {
+        Language::ALL.iter().copied().find(|lang| lang.code() == code)
+    }
+
+    // Accepts BCP 47 tags ("pt-BR") as well as POSIX locales ("de_DE.UTF-8").
+    pub fn from_locale(locale: &str) -> Option<Language> {
+        let primary = locale
+            .split(['-', '_', '.', '@'])
+            .next()?
+            .to_ascii_lowercase();
+        Language::from_code(&primary)
+    }
+
+    // Picks the translation matching the OS locale, or English if we don't have one.
+    pub fn detect() -> Language {
+        sys_locale::get_locales()
+            .find_map(|locale| Language::from_locale(&locale))
+            .unwrap_or(Language::English)
+    }
+
+    pub fn tr(self, text: &'static str) -> &'static str {
+        let translated = match self {
+            Language::English => None,
+            Language::Spanish => spanish(text),
+            Language::French => french(text),
+            Language::German => german(text),
+            Language::Portuguese => portuguese(text),
+        };
+        translated.unwrap_or(text)
+    }
+}
+
+fn spanish(text: &str) -> Option<&'static str> {
+    Some(match text {
+        "Start Game" => "Iniciar partida",
+        "High Scores:" => "Mejores puntuaciones:",
+        "Score:" => "Puntuación:",
+        "Level:" => "Nivel:",
+        "Game Paused" => "Juego en pausa",
+        "Game Over!" => "¡Fin del juego!",
+        "Enter Name:" => "Introduce tu nombre:",
+        "Submit Score" => "Enviar puntuación",
+        "Back to Start" => "Volver al inicio",
+        "Language" => "Idioma",
+        "When unfocused" => "Sin foco",
+        "Pause" => "Pausar",
+        "Keep running (reduced redraws)" => "Seguir (menos redibujado)",
+        "Keep running" => "Seguir jugando",
+        "Mode" => "Modo",
+        "Marathon" => "Maratón",
+        "Party (items)" => "Fiesta (objetos)",
+        "Item:" => "Objeto:",
+        "Clear bottom rows" => "Borrar filas inferiores",
+        "Slow your fall" => "Caída más lenta",
+        "Shuffle your bottom rows" => "Mezclar tus filas inferiores",
+        "Randomizer" => "Aleatorizador",
+        "Classic (memoryless)" => "Clásico (sin memoria)",
+        "7-bag" => "Bolsa de 7",
+        "Sticky gravity" => "Gravedad pegajosa",
+        "Rematch" => "Revancha",
+        "Change rules" => "Cambiar reglas",
+        "Ultra (2 minutes)" => "Ultra (2 minutos)",
+        "Lines:" => "Líneas:",
+        "Time left:" => "Tiempo restante:",
+        "Time:" => "Tiempo:",
+        "Copy board" => "Copiar tablero",
+        "Load board" => "Cargar tablero",
+        "Start with this board" => "Empezar con este tablero",
+        "Zen (endless)" => "Zen (sin fin)",
+        "Dig race" => "Carrera de excavación",
+        "Garbage rows" => "Filas de basura",
+        "Garbage left:" => "Basura restante:",
+        "All garbage cleared!" => "¡Basura eliminada!",
+        "Invisible stack" => "Pila invisible",
+        "Beginner column hints" => "Guías de columna para principiantes",
+        "Master (20G)" => "Maestro (20G)",
+        "Grade:" => "Grado:",
+        "Section:" => "Sección:",
+        "Section" => "Sección",
+        "Profile" => "Perfil",
+        "Crow color" => "Color del cuervo",
+        "Accessory" => "Accesorio",
+        "Add profile" => "Añadir perfil",
+        "Black" => "Negro",
+        "Blue" => "Azul",
+        "White" => "Blanco",
+        "Red" => "Rojo",
+        "Green" => "Verde",
+        "None" => "Ninguno",
+        "Top hat" => "Sombrero de copa",
+        "Crown" => "Corona",
+        "Scarf" => "Bufanda",
+        "Monocle" => "Monóculo",
+        "Missions" => "Misiones",
+        "Mission:" => "Misión:",
+        "Completed:" => "Completadas:",
+        "Missions completed:" => "Misiones completadas:",
+        "Lines" => "Líneas",
+        "4-line clears" => "Limpiezas de 4 líneas",
+        "T-spin clears" => "Líneas con T-spin",
+        "T-spin doubles" => "T-spin dobles",
+        "Combo" => "Combo",
+        "Daily challenge" => "Desafío diario",
+        "Nobody has played today's challenge yet." => "Nadie ha jugado el desafío de hoy todavía.",
+        "lines" => "líneas",
+        "You've already played today's challenge. Come back tomorrow!" => "Ya jugaste el desafío de hoy. ¡Vuelve mañana!",
+        "Practice (sandbox)" => "Práctica (libre)",
+        "Practice tools" => "Herramientas de práctica",
+        "Random" => "Aleatoria",
+        "Next piece" => "Siguiente pieza",
+        "Gravity" => "Gravedad",
+        "Save snapshot" => "Guardar instantánea",
+        "Restore" => "Restaurar",
+        "Battle vs CPU" => "Batalla contra la CPU",
+        "CPU lines:" => "Líneas de la CPU:",
+        "Incoming garbage:" => "Basura entrante:",
+        "You win!" => "¡Ganaste!",
+        "The CPU wins" => "Gana la CPU",
+        "2 players (same keyboard)" => "2 jugadores (mismo teclado)",
+        "Player 2 lines:" => "Líneas del jugador 2:",
+        "Player 2: W rotate, A/D move, S drop" => "Jugador 2: W girar, A/D mover, S bajar",
+        "Player 1 wins!" => "¡Gana el jugador 1!",
+        "Player 2 wins!" => "¡Gana el jugador 2!",
+        "Online 1v1" => "1 contra 1 en línea",
+        "Connection lost" => "Conexión perdida",
+        "Host game" => "Crear partida",
+        "Address or room code:" => "Dirección o código de sala:",
+        "Join game" => "Unirse",
+        "Room code:" => "Código de sala:",
+        "Waiting for an opponent to join..." => "Esperando a un rival...",
+        "Not connected" => "Sin conexión",
+        "Opponent connected" => "Rival conectado",
+        "Start match" => "Empezar partida",
+        "Connected. Waiting for the host to start..." => "Conectado. Esperando a que el anfitrión empiece...",
+        "Leave" => "Salir",
+        "Opponent lines:" => "Líneas del rival:",
+        "Your opponent wins" => "Gana tu rival",
+        "Battle royale (bots)" => "Battle royale (bots)",
+        "Finish off (highest stack)" => "Rematar (pila más alta)",
+        "Leader (lowest stack)" => "Líder (pila más baja)",
+        "Players left:" => "Jugadores restantes:",
+        "Target" => "Objetivo",
+        "Placement:" => "Puesto:",
+        "Time attack (checkpoints)" => "Contrarreloj (puntos de control)",
+        "Checkpoint" => "Punto de control",
+        "All checkpoints reached!" => "¡Todos los puntos de control alcanzados!",
+        "Out of time" => "Se acabó el tiempo",
+        "Rules" => "Reglas",
+        "Modern" => "Moderno",
+        "Classic (NES)" => "Clásico (NES)",
+        "Sprint (40 lines)" => "Sprint (40 líneas)",
+        "Lines left:" => "Líneas restantes:",
+        "40 lines cleared!" => "¡40 líneas completadas!",
+        "Endless classic play. Clear lines and survive as long as you can." => "Juego clásico sin fin. Completa líneas y aguanta todo lo que puedas.",
+        "Clear 40 lines as fast as you can." => "Completa 40 líneas lo más rápido posible.",
+        "Clearing lines earns items you can use with E." => "Completar líneas te da objetos que puedes usar con E.",
+        "After a clear, loose groups of blocks fall and can set off chains." => "Tras completar líneas, los grupos sueltos caen y pueden encadenar.",
+        "Score as much as you can in two minutes." => "Consigue todos los puntos que puedas en dos minutos.",
+        "No game over: the stack trims itself when it gets too high." => "Sin fin de partida: la pila se recorta sola cuando sube demasiado.",
+        "Dig through rows of garbage as fast as you can." => "Excava entre filas de basura lo más rápido posible.",
+        "Locked blocks fade away, so remember your stack." => "Los bloques fijados se desvanecen: recuerda tu pila.",
+        "Levels 0 to 999 with gravity up to 20G. Earn a grade." => "Niveles 0 a 999 con gravedad hasta 20G. Consigue un rango.",
+        "Complete rotating objectives for bonus points." => "Completa objetivos cambiantes para ganar puntos extra.",
+        "The same pieces for everyone today, one attempt per day." => "Las mismas piezas para todos hoy, un intento por día.",
+        "Pick pieces, turn gravity off and save snapshots." => "Elige piezas, quita la gravedad y guarda instantáneas.",
+        "Send garbage to a computer opponent until it tops out." => "Envía basura a la CPU hasta que se desborde.",
+        "Two players on one keyboard: arrow keys against WASD." => "Dos jugadores en un teclado: flechas contra WASD.",
+        "Play someone over the network by address or room code." => "Juega contra alguien por red con una dirección o código de sala.",
+        "Outlast a crowd of bots while garbage flies everywhere." => "Sobrevive a una multitud de bots mientras vuela basura por todas partes.",
+        "Reach each line checkpoint before its time runs out." => "Alcanza cada punto de control antes de que se acabe su tiempo.",
+        "Starting level" => "Nivel inicial",
+        "Seed (optional):" => "Semilla (opcional):",
+        "Seed:" => "Semilla:",
+        "Copy" => "Copiar",
+        "Hold" => "Reserva",
+        "Next" => "Siguiente",
+        "Theme" => "Tema",
+        "Reload" => "Recargar",
+        "Skin" => "Aspecto",
+        "Flat colors" => "Colores planos",
+        "Fullscreen (F11)" => "Pantalla completa (F11)",
+        "Grid lines and border" => "Cuadrícula y borde",
+        "Screen shake" => "Temblor de pantalla",
+        "Resume" => "Reanudar",
+        "Restart" => "Reiniciar",
+        "Settings" => "Ajustes",
+        "Quit to menu" => "Salir al menú",
+        "Gameplay" => "Juego",
+        "Controls" => "Controles",
+        "Audio" => "Audio",
+        "Display" => "Pantalla",
+        "Effects volume" => "Volumen de efectos",
+        "Music volume" => "Volumen de música",
+        "Back" => "Volver",
+        "Drop interval (ms)" => "Intervalo de caída (ms)",
+        "Move left" => "Mover a la izquierda",
+        "Move right" => "Mover a la derecha",
+        "Soft drop" => "Bajar",
+        "Hard drop" => "Caída rápida",
+        "Use item" => "Usar objeto",
+        "Give up" => "Rendirse",
+        "Live stats (PPS, APM, KPP)" => "Estadísticas en vivo (PPS, APM, KPP)",
+        "Press any key" => "Pulsa cualquier tecla",
+        "Level" => "Nivel",
+        "speed up!" => "¡más rápido!",
+        "UI scale" => "Escala de la interfaz",
+        "Focus mode (hide score and labels)" => "Modo concentración (ocultar puntuación y textos)",
+        "Fancy graphics (glow)" => "Gráficos avanzados (brillo)",
+        "Needs the OpenGL renderer" => "Requiere el renderizador OpenGL",
+        "Cell style" => "Estilo de celdas",
+        "Rounded" => "Redondeado",
+        "Flat" => "Plano",
+        "Beveled" => "Biselado",
+        "Outlined" => "Contorno",
+        "Show pressed keys (for streaming)" => "Mostrar teclas pulsadas (para streaming)",
+        "Splits" => "Parciales",
+        "New best splits!" => "¡Nuevos mejores parciales!",
+        "Stack height" => "Altura de la pila",
+        "Pieces" => "Piezas",
+        "I drought:" => "Sequía de I:",
+        "No I for" => "Sin I desde",
+        "Pieces dealt since the last I piece" => "Piezas repartidas desde la última I",
+        "Rotate clockwise" => "Girar a la derecha",
+        "Rotate counterclockwise" => "Girar a la izquierda",
+        "Key was taken; swapped:" => "La tecla estaba en uso; intercambiadas:",
+        "Press a key or button..." => "Pulsa una tecla o botón...",
+        "Device:" => "Dispositivo:",
+        "Keyboard" => "Teclado",
+        "Gamepad" => "Mando",
+        "Stick deadzone" => "Zona muerta del stick",
+        "Touch controls (swipe and tap the board)" => "Controles táctiles (desliza y toca el tablero)",
+        "Mouse controls (piece follows the cursor)" => "Control con ratón (la pieza sigue al cursor)",
+        "Left click rotates, the wheel rotates the other way, right click drops" => "Clic izquierdo gira, la rueda gira al otro lado, clic derecho suelta",
+        "Handling" => "Manejo",
+        "Delayed auto shift" => "Retardo de autodesplazamiento",
+        "Auto repeat rate" => "Velocidad de repetición",
+        "Soft drop speed" => "Velocidad de caída suave",
+        "Hold left or right to try it:" => "Mantén izquierda o derecha para probarlo:",
+        "Guideline" => "Estándar",
+        "Arrows only" => "Solo flechas",
+        "Left-handed" => "Zurdo",
+        "Custom" => "Personalizado",
+        "Control profile" => "Perfil de controles",
+        "Save profile" => "Guardar perfil",
+        "Delete profile" => "Borrar perfil",
+        "One hand (left side)" => "Una mano (lado izquierdo)",
+        "One hand (right side)" => "Una mano (lado derecho)",
+        "Controller rumble" => "Vibración del mando",
+        "On-screen keyboard" => "Teclado en pantalla",
+        "Delete" => "Borrar",
+        "Done" => "Listo",
+        "Quit game" => "Salir del juego",
+        "Abandon current game?" => "¿Abandonar la partida actual?",
+        "Quit the game? The current run will be lost." => "¿Salir del juego? Se perderá la partida actual.",
+        "Yes" => "Sí",
+        "No" => "No",
+        "One-switch mode (the drop key places pieces)" => "Modo de un pulsador (la tecla de caída coloca las piezas)",
+        "Rotation, then column, cycle by themselves; press to pick each" => "La rotación y luego la columna cambian solas; pulsa para elegir cada una",
+        "Scan step (ms)" => "Paso de escaneo (ms)",
+        "Press to keep this rotation" => "Pulsa para quedarte con esta rotación",
+        "Press to drop here" => "Pulsa para soltar aquí",
+        "Menu music" => "Música del menú",
+        "Gameplay music" => "Música de juego",
+        "Korobeiniki" => "Korobeiniki",
+        "Roost" => "Percha",
+        "Off" => "Apagada",
+        "Master volume" => "Volumen general",
+        "Built-in sounds" => "Sonidos integrados",
+        "Sound pack" => "Paquete de sonidos",
+        "Announce each piece and the next with a sound" => "Anunciar cada pieza y la siguiente con un sonido",
+        "No scores yet" => "Aún no hay puntuaciones",
+        "Only finished runs go on this leaderboard" => "Solo las partidas terminadas entran en esta tabla",
+        "Save & Quit" => "Guardar y salir",
+        "Continue" => "Continuar",
+        "Replay" => "Repetición",
+        "Replays" => "Repeticiones",
+        "No replays yet" => "Aún no hay repeticiones",
+        "Watch" => "Ver",
+        "Watch replay" => "Ver repetición",
+        "Close" => "Cerrar",
+        "Stats" => "Estadísticas",
+        "Games played" => "Partidas jugadas",
+        "Lines cleared" => "Líneas completadas",
+        "Pieces placed" => "Piezas colocadas",
+        "Tetrises" => "Tetris",
+        "T-spins" => "T-spins",
+        "Play time" => "Tiempo de juego",
+        "Best PPS" => "Mejor PPS",
+        "Finesse faults:" => "Errores de finesse:",
+        "Export stats" => "Exportar estadísticas",
+        "Every game so far, as a CSV file" => "Todas las partidas hasta ahora, en un archivo CSV",
+        "Saved to" => "Guardado en",
+        "The last run was interrupted." => "La última partida se interrumpió.",
+        "Discard" => "Descartar",
+        "unverified" => "sin verificar",
+        "This entry doesn't match its signature; the file may have been edited" => "Esta entrada no coincide con su firma; puede que el archivo se haya editado",
+        "legacy" => "antigua",
+        "Carried over from an older version's score file" => "Traída del archivo de puntuaciones de una versión anterior",
+        "Retry" => "Reintentar",
+        "Dismiss" => "Cerrar",
+        "Couldn't save the settings:" => "No se pudieron guardar los ajustes:",
+        "Couldn't read the settings:" => "No se pudieron leer los ajustes:",
+        "Couldn't save the high scores:" => "No se pudieron guardar las puntuaciones:",
+        "Couldn't save the lifetime stats:" => "No se pudieron guardar las estadísticas:",
+        "Couldn't save the run:" => "No se pudo guardar la partida:",
+        "Theme not found:" => "Tema no encontrado:",
+        "Couldn't save the key bindings:" => "No se pudieron guardar los controles:",
+        "Couldn't save the control profiles:" => "No se pudieron guardar los perfiles de control:",
+        "Couldn't save the daily results:" => "No se pudieron guardar los resultados diarios:",
+        "Couldn't save the profiles:" => "No se pudieron guardar los perfiles:",
+        "Couldn't save the best splits:" => "No se pudieron guardar los mejores parciales:",
+        "Couldn't save the replay:" => "No se pudo guardar la repetición:",
+        "Couldn't save the game history:" => "No se pudo guardar el historial:",
+        "Couldn't autosave the run:" => "No se pudo autoguardar la partida:",
+        "Couldn't load the sound pack:" => "No se pudo cargar el paquete de sonidos:",
+        "Connecting..." => "Conectando...",
+        "Accept" => "Aceptar",
+        "Decline" => "Rechazar",
+        "Waiting for your opponent to answer..." => "Esperando la respuesta de tu rival...",
+        "Your opponent declined the rematch." => "Tu rival rechazó la revancha.",
+        "The host wants a rematch" => "El anfitrión quiere la revancha",
+        "Waiting for the host to offer a rematch..." => "Esperando a que el anfitrión ofrezca la revancha...",
+        "Best of" => "Al mejor de",
+        "best of" => "al mejor de",
+        "Round" => "Ronda",
+        "Winner" => "Ganador",
+        "Opponent lines" => "Líneas del rival",
+        "Attack" => "Ataque",
+        "PPS" => "PPS",
+        "Time" => "Tiempo",
+        "Sudden death" => "Muerte súbita",
+        "You" => "Tú",
+        "Opponent" => "Rival",
+        "Player 1" => "Jugador 1",
+        "Player 2" => "Jugador 2",
+        "You win the round" => "Ganas la ronda",
+        "Your opponent wins the round" => "Tu rival gana la ronda",
+        "Player 1 wins the round" => "El jugador 1 gana la ronda",
+        "Player 2 wins the round" => "El jugador 2 gana la ronda",
+        "Sudden death: cheese race" => "Muerte súbita: carrera de excavación",
+        "The rounds are split evenly. Both boards start on the same garbage, and the first to dig out wins." => "Las rondas están empatadas. Ambos tableros empiezan con la misma basura y gana el primero en despejarla.",
+        "Next round" => "Siguiente ronda",
+        "Waiting for the host to start the next round..." => "Esperando a que el anfitrión empiece la siguiente ronda...",
+        "Press to fall here" => "Pulsa para caer aquí",
+        "Nobody" => "Nadie",
+        "Player 2 profile" => "Perfil del jugador 2",
+        _ => return None,
+    })
+}
+
+fn french(text: &str) -> Option<&'static str> {
+    Some(match text {
+        "Start Game" => "Commencer",
+        "High Scores:" => "Meilleurs scores :",
+        "Score:" => "Score :",
+        "Level:" => "Niveau :",
+        "Game Paused" => "Jeu en pause",
+        "Game Over!" => "Partie terminée !",
+        "Enter Name:" => "Entrez votre nom :",
+        "Submit Score" => "Enregistrer le score",
+        "Back to Start" => "Retour à l'accueil",
+        "Language" => "Langue",
+        "When unfocused" => "Hors focus",
+        "Pause" => "Pause",
+        "Keep running (reduced redraws)" => "Continuer (affichage réduit)",
+        "Keep running" => "Continuer",
+        "Mode" => "Mode",
+        "Marathon" => "Marathon",
+        "Party (items)" => "Fête (objets)",
+        "Item:" => "Objet :",
+        "Clear bottom rows" => "Effacer les lignes du bas",
+        "Slow your fall" => "Chute ralentie",
+        "Shuffle your bottom rows" => "Mélanger tes lignes du bas",
+        "Randomizer" => "Générateur",
+        "Classic (memoryless)" => "Classique (sans mémoire)",
+        "7-bag" => "Sac de 7",
+        "Sticky gravity" => "Gravité collante",
+        "Rematch" => "Revanche",
+        "Change rules" => "Changer les règles",
+        "Ultra (2 minutes)" => "Ultra (2 minutes)",
+        "Lines:" => "Lignes :",
+        "Time left:" => "Temps restant :",
+        "Time:" => "Temps :",
+        "Copy board" => "Copier le plateau",
+        "Load board" => "Charger un plateau",
+        "Start with this board" => "Commencer avec ce plateau",
+        "Zen (endless)" => "Zen (sans fin)",
+        "Dig race" => "Course de creusage",
+        "Garbage rows" => "Lignes de déchets",
+        "Garbage left:" => "Déchets restants :",
+        "All garbage cleared!" => "Tous les déchets éliminés !",
+        "Invisible stack" => "Pile invisible",
+        "Beginner column hints" => "Aide de colonnes débutant",
+        "Master (20G)" => "Maître (20G)",
+        "Grade:" => "Grade :",
+        "Section:" => "Section :",
+        "Section" => "Section",
+        "Profile" => "Profil",
+        "Crow color" => "Couleur du corbeau",
+        "Accessory" => "Accessoire",
+        "Add profile" => "Ajouter un profil",
+        "Black" => "Noir",
+        "Blue" => "Bleu",
+        "White" => "Blanc",
+        "Red" => "Rouge",
+        "Green" => "Vert",
+        "None" => "Aucun",
+        "Top hat" => "Haut-de-forme",
+        "Crown" => "Couronne",
+        "Scarf" => "Écharpe",
+        "Monocle" => "Monocle",
+        "Missions" => "Missions",
+        "Mission:" => "Mission :",
+        "Completed:" => "Terminées :",
+        "Missions completed:" => "Missions terminées :",
+        "Lines" => "Lignes",
+        "4-line clears" => "Effacements de 4 lignes",
+        "T-spin clears" => "Lignes en T-spin",
+        "T-spin doubles" => "T-spin doubles",
+        "Combo" => "Combo",
+        "Daily challenge" => "Défi du jour",
+        "Nobody has played today's challenge yet." => "Personne n'a encore joué le défi du jour.",
+        "lines" => "lignes",
+        "You've already played today's challenge. Come back tomorrow!" => "Tu as déjà joué le défi du jour. Reviens demain !",
+        "Practice (sandbox)" => "Entraînement (bac à sable)",
+        "Practice tools" => "Outils d'entraînement",
+        "Random" => "Aléatoire",
+        "Next piece" => "Pièce suivante",
+        "Gravity" => "Gravité",
+        "Save snapshot" => "Enregistrer l'état",
+        "Restore" => "Restaurer",
+        "Battle vs CPU" => "Duel contre l'ordinateur",
+        "CPU lines:" => "Lignes de l'ordinateur :",
+        "Incoming garbage:" => "Déchets entrants :",
+        "You win!" => "Tu as gagné !",
+        "The CPU wins" => "L'ordinateur gagne",
+        "2 players (same keyboard)" => "2 joueurs (même clavier)",
+        "Player 2 lines:" => "Lignes du joueur 2 :",
+        "Player 2: W rotate, A/D move, S drop" => "Joueur 2 : W tourner, A/D déplacer, S descendre",
+        "Player 1 wins!" => "Le joueur 1 gagne !",
+        "Player 2 wins!" => "Le joueur 2 gagne !",
+        "Online 1v1" => "1 contre 1 en ligne",
+        "Connection lost" => "Connexion perdue",
+        "Host game" => "Héberger une partie",
+        "Address or room code:" => "Adresse ou code de salle :",
+        "Join game" => "Rejoindre",
+        "Room code:" => "Code de salle :",
+        "Waiting for an opponent to join..." => "En attente d'un adversaire...",
+        "Not connected" => "Non connecté",
+        "Opponent connected" => "Adversaire connecté",
+        "Start match" => "Lancer la partie",
+        "Connected. Waiting for the host to start..." => "Connecté. En attente de l'hôte...",
+        "Leave" => "Quitter",
+        "Opponent lines:" => "Lignes de l'adversaire :",
+        "Your opponent wins" => "Ton adversaire gagne",
+        "Battle royale (bots)" => "Battle royale (bots)",
+        "Finish off (highest stack)" => "Achever (pile la plus haute)",
+        "Leader (lowest stack)" => "Meneur (pile la plus basse)",
+        "Players left:" => "Joueurs restants :",
+        "Target" => "Cible",
+        "Placement:" => "Classement :",
+        "Time attack (checkpoints)" => "Contre-la-montre (points de contrôle)",
+        "Checkpoint" => "Point de contrôle",
+        "All checkpoints reached!" => "Tous les points de contrôle atteints !",
+        "Out of time" => "Temps écoulé",
+        "Rules" => "Règles",
+        "Modern" => "Moderne",
+        "Classic (NES)" => "Classique (NES)",
+        "Sprint (40 lines)" => "Sprint (40 lignes)",
+        "Lines left:" => "Lignes restantes :",
+        "40 lines cleared!" => "40 lignes effacées !",
+        "Endless classic play. Clear lines and survive as long as you can." => "Jeu classique sans fin. Efface des lignes et tiens le plus longtemps possible.",
+        "Clear 40 lines as fast as you can." => "Efface 40 lignes le plus vite possible.",
+        "Clearing lines earns items you can use with E." => "Effacer des lignes rapporte des objets à utiliser avec E.",
+        "After a clear, loose groups of blocks fall and can set off chains." => "Après un effacement, les groupes libres tombent et peuvent enchaîner.",
+        "Score as much as you can in two minutes." => "Marque un maximum de points en deux minutes.",
+        "No game over: the stack trims itself when it gets too high." => "Pas de fin de partie : la pile se réduit quand elle monte trop.",
+        "Dig through rows of garbage as fast as you can." => "Creuse à travers les lignes de déchets le plus vite possible.",
+        "Locked blocks fade away, so remember your stack." => "Les blocs posés s'effacent : souviens-toi de ta pile.",
+        "Levels 0 to 999 with gravity up to 20G. Earn a grade." => "Niveaux 0 à 999 avec une gravité jusqu'à 20G. Obtiens un grade.",
+        "Complete rotating objectives for bonus points." => "Accomplis des objectifs changeants pour des points bonus.",
+        "The same pieces for everyone today, one attempt per day." => "Les mêmes pièces pour tous aujourd'hui, un essai par jour.",
+        "Pick pieces, turn gravity off and save snapshots." => "Choisis les pièces, coupe la gravité et enregistre des états.",
+        "Send garbage to a computer opponent until it tops out." => "Envoie des déchets à l'ordinateur jusqu'à ce qu'il déborde.",
+        "Two players on one keyboard: arrow keys against WASD." => "Deux joueurs sur un clavier : flèches contre WASD.",
+        "Play someone over the network by address or room code." => "Affronte quelqu'un en réseau par adresse ou code de salle.",
+        "Outlast a crowd of bots while garbage flies everywhere." => "Survis à une foule de bots pendant que les déchets volent partout.",
+        "Reach each line checkpoint before its time runs out." => "Atteins chaque point de contrôle avant la fin de son temps.",
+        "Starting level" => "Niveau de départ",
+        "Seed (optional):" => "Graine (facultatif) :",
+        "Seed:" => "Graine :",
+        "Copy" => "Copier",
+        "Hold" => "Réserve",
+        "Next" => "Suivant",
+        "Theme" => "Thème",
+        "Reload" => "Recharger",
+        "Skin" => "Apparence",
+        "Flat colors" => "Couleurs unies",
+        "Fullscreen (F11)" => "Plein écran (F11)",
+        "Grid lines and border" => "Grille et bordure",
+        "Screen shake" => "Tremblement de l'écran",
+        "Resume" => "Reprendre",
+        "Restart" => "Recommencer",
+        "Settings" => "Paramètres",
+        "Quit to menu" => "Retour au menu",
+        "Gameplay" => "Jeu",
+        "Controls" => "Commandes",
+        "Audio" => "Audio",
+        "Display" => "Affichage",
+        "Effects volume" => "Volume des effets",
+        "Music volume" => "Volume de la musique",
+        "Back" => "Retour",
+        "Drop interval (ms)" => "Intervalle de chute (ms)",
+        "Move left" => "Gauche",
+        "Move right" => "Droite",
+        "Soft drop" => "Descente douce",
+        "Hard drop" => "Chute directe",
+        "Use item" => "Utiliser un objet",
+        "Give up" => "Abandonner",
+        "Live stats (PPS, APM, KPP)" => "Statistiques en direct (PPS, APM, KPP)",
+        "Press any key" => "Appuyez sur une touche",
+        "Level" => "Niveau",
+        "speed up!" => "ça accélère !",
+        "UI scale" => "Échelle de l'interface",
+        "Focus mode (hide score and labels)" => "Mode concentration (masquer score et textes)",
+        "Fancy graphics (glow)" => "Graphismes avancés (lueur)",
+        "Needs the OpenGL renderer" => "Nécessite le rendu OpenGL",
+        "Cell style" => "Style des cases",
+        "Rounded" => "Arrondi",
+        "Flat" => "Plat",
+        "Beveled" => "Biseauté",
+        "Outlined" => "Contour",
+        "Show pressed keys (for streaming)" => "Afficher les touches pressées (pour le streaming)",
+        "Splits" => "Temps intermédiaires",
+        "New best splits!" => "Nouveaux meilleurs temps intermédiaires !",
+        "Stack height" => "Hauteur de la pile",
+        "Pieces" => "Pièces",
+        "I drought:" => "Disette de I :",
+        "No I for" => "Sans I depuis",
+        "Pieces dealt since the last I piece" => "Pièces distribuées depuis le dernier I",
+        "Rotate clockwise" => "Tourner à droite",
+        "Rotate counterclockwise" => "Tourner à gauche",
+        "Key was taken; swapped:" => "Touche déjà utilisée ; échangées :",
+        "Press a key or button..." => "Appuyez sur une touche ou un bouton...",
+        "Device:" => "Appareil :",
+        "Keyboard" => "Clavier",
+        "Gamepad" => "Manette",
+        "Stick deadzone" => "Zone morte du stick",
+        "Touch controls (swipe and tap the board)" => "Commandes tactiles (glissez et touchez le plateau)",
+        "Mouse controls (piece follows the cursor)" => "Commandes à la souris (la pièce suit le curseur)",
+        "Left click rotates, the wheel rotates the other way, right click drops" => "Clic gauche pour tourner, la molette tourne dans l’autre sens, clic droit pour lâcher",
+        "Handling" => "Maniabilité",
+        "Delayed auto shift" => "Délai de répétition",
+        "Auto repeat rate" => "Vitesse de répétition",
+        "Soft drop speed" => "Vitesse de chute douce",
+        "Hold left or right to try it:" => "Maintenez gauche ou droite pour essayer :",
+        "Guideline" => "Standard",
+        "Arrows only" => "Flèches seulement",
+        "Left-handed" => "Gaucher",
+        "Custom" => "Personnalisé",
+        "Control profile" => "Profil de commandes",
+        "Save profile" => "Enregistrer le profil",
+        "Delete profile" => "Supprimer le profil",
+        "One hand (left side)" => "Une main (côté gauche)",
+        "One hand (right side)" => "Une main (côté droit)",
+        "Controller rumble" => "Vibration de la manette",
+        "On-screen keyboard" => "Clavier à l’écran",
+        "Delete" => "Effacer",
+        "Done" => "Terminé",
+        "Quit game" => "Quitter le jeu",
+        "Abandon current game?" => "Abandonner la partie en cours ?",
+        "Quit the game? The current run will be lost." => "Quitter le jeu ? La partie en cours sera perdue.",
+        "Yes" => "Oui",
+        "No" => "Non",
+        "One-switch mode (the drop key places pieces)" => "Mode un contacteur (la touche de chute place les pièces)",
+        "Rotation, then column, cycle by themselves; press to pick each" => "La rotation puis la colonne défilent seules ; appuyez pour choisir chacune",
+        "Scan step (ms)" => "Pas de défilement (ms)",
+        "Press to keep this rotation" => "Appuyez pour garder cette rotation",
+        "Press to drop here" => "Appuyez pour lâcher ici",
+        "Menu music" => "Musique des menus",
+        "Gameplay music" => "Musique en jeu",
+        "Korobeiniki" => "Korobeiniki",
+        "Roost" => "Perchoir",
+        "Off" => "Désactivée",
+        "Master volume" => "Volume général",
+        "Built-in sounds" => "Sons intégrés",
+        "Sound pack" => "Pack de sons",
+        "Announce each piece and the next with a sound" => "Annoncer chaque pièce et la suivante par un son",
+        "No scores yet" => "Pas encore de scores",
+        "Only finished runs go on this leaderboard" => "Seules les parties terminées entrent dans ce classement",
+        "Save & Quit" => "Sauvegarder et quitter",
+        "Continue" => "Continuer",
+        "Replay" => "Replay",
+        "Replays" => "Replays",
+        "No replays yet" => "Aucun replay pour l’instant",
+        "Watch" => "Regarder",
+        "Watch replay" => "Regarder le replay",
+        "Close" => "Fermer",
+        "Stats" => "Statistiques",
+        "Games played" => "Parties jouées",
+        "Lines cleared" => "Lignes complétées",
+        "Pieces placed" => "Pièces posées",
+        "Tetrises" => "Tetris",
+        "T-spins" => "T-spins",
+        "Play time" => "Temps de jeu",
+        "Best PPS" => "Meilleur PPS",
+        "Finesse faults:" => "Fautes de finesse :",
+        "Export stats" => "Exporter les statistiques",
+        "Every game so far, as a CSV file" => "Toutes les parties jusqu’ici, dans un fichier CSV",
+        "Saved to" => "Enregistré dans",
+        "The last run was interrupted." => "La dernière partie a été interrompue.",
+        "Discard" => "Abandonner",
+        "unverified" => "non vérifié",
+        "This entry doesn't match its signature; the file may have been edited" => "Cette entrée ne correspond pas à sa signature ; le fichier a peut-être été modifié",
+        "legacy" => "ancien",
+        "Carried over from an older version's score file" => "Reprise du fichier de scores d'une ancienne version",
+        "Retry" => "Réessayer",
+        "Dismiss" => "Fermer",
+        "Couldn't save the settings:" => "Impossible d'enregistrer les réglages :",
+        "Couldn't read the settings:" => "Impossible de lire les réglages :",
+        "Couldn't save the high scores:" => "Impossible d'enregistrer les meilleurs scores :",
+        "Couldn't save the lifetime stats:" => "Impossible d'enregistrer les statistiques :",
+        "Couldn't save the run:" => "Impossible d'enregistrer la partie :",
+        "Theme not found:" => "Thème introuvable :",
+        "Couldn't save the key bindings:" => "Impossible d'enregistrer les touches :",
+        "Couldn't save the control profiles:" => "Impossible d'enregistrer les profils de commandes :",
+        "Couldn't save the daily results:" => "Impossible d'enregistrer les résultats du jour :",
+        "Couldn't save the profiles:" => "Impossible d'enregistrer les profils :",
+        "Couldn't save the best splits:" => "Impossible d'enregistrer les meilleurs temps intermédiaires :",
+        "Couldn't save the replay:" => "Impossible d'enregistrer le replay :",
+        "Couldn't save the game history:" => "Impossible d'enregistrer l'historique :",
+        "Couldn't autosave the run:" => "Impossible de sauvegarder automatiquement la partie :",
+        "Couldn't load the sound pack:" => "Impossible de charger le pack de sons :",
+        "Connecting..." => "Connexion...",
+        "Accept" => "Accepter",
+        "Decline" => "Refuser",
+        "Waiting for your opponent to answer..." => "En attente de la réponse de l'adversaire...",
+        "Your opponent declined the rematch." => "L'adversaire a refusé la revanche.",
+        "The host wants a rematch" => "L'hôte veut une revanche",
+        "Waiting for the host to offer a rematch..." => "En attente d'une revanche proposée par l'hôte...",
+        "Best of" => "Au meilleur de",
+        "best of" => "au meilleur de",
+        "Round" => "Manche",
+        "Winner" => "Vainqueur",
+        "Opponent lines" => "Lignes de l'adversaire",
+        "Attack" => "Attaque",
+        "PPS" => "PPS",
+        "Time" => "Temps",
+        "Sudden death" => "Mort subite",
+        "You" => "Toi",
+        "Opponent" => "Adversaire",
+        "Player 1" => "Joueur 1",
+        "Player 2" => "Joueur 2",
+        "You win the round" => "Tu gagnes la manche",
+        "Your opponent wins the round" => "L'adversaire gagne la manche",
+        "Player 1 wins the round" => "Le joueur 1 gagne la manche",
+        "Player 2 wins the round" => "Le joueur 2 gagne la manche",
+        "Sudden death: cheese race" => "Mort subite : course de creusage",
+        "The rounds are split evenly. Both boards start on the same garbage, and the first to dig out wins." => "Les manches sont à égalité. Les deux plateaux commencent avec les mêmes déchets, et le premier à les dégager gagne.",
+        "Next round" => "Manche suivante",
+        "Waiting for the host to start the next round..." => "En attente de la manche suivante lancée par l'hôte...",
+        "Press to fall here" => "Appuyez pour tomber ici",
+        "Nobody" => "Personne",
+        "Player 2 profile" => "Profil du joueur 2",
+        _ => return None,
+    })
+}
+
+fn german(text: &str) -> Option<&'static str> {
+    Some(match text {
+        "Start Game" => "Spiel starten",
+        "High Scores:" => "Bestenliste:",
+        "Score:" => "Punkte:",
+        "Level:" => "Level:",
+        "Game Paused" => "Spiel pausiert",
+        "Game Over!" => "Spiel vorbei!",
+        "Enter Name:" => "Name eingeben:",
+        "Submit Score" => "Punkte eintragen",
+        "Back to Start" => "Zurück zum Start",
+        "Language" => "Sprache",
+        "When unfocused" => "Ohne Fokus",
+        "Pause" => "Pausieren",
+        "Keep running (reduced redraws)" => "Weiterlaufen (weniger Neuzeichnen)",
+        "Keep running" => "Weiterlaufen",
+        "Mode" => "Modus",
+        "Marathon" => "Marathon",
+        "Party (items)" => "Party (Items)",
+        "Item:" => "Item:",
+        "Clear bottom rows" => "Untere Reihen löschen",
+        "Slow your fall" => "Langsamer fallen",
+        "Shuffle your bottom rows" => "Eigene untere Reihen mischen",
+        "Randomizer" => "Zufallsgenerator",
+        "Classic (memoryless)" => "Klassisch (gedächtnislos)",
+        "7-bag" => "7er-Beutel",
+        "Sticky gravity" => "Klebrige Schwerkraft",
+        "Rematch" => "Revanche",
+        "Change rules" => "Regeln ändern",
+        "Ultra (2 minutes)" => "Ultra (2 Minuten)",
+        "Lines:" => "Reihen:",
+        "Time left:" => "Restzeit:",
+        "Time:" => "Zeit:",
+        "Copy board" => "Spielfeld kopieren",
+        "Load board" => "Spielfeld laden",
+        "Start with this board" => "Mit diesem Spielfeld starten",
+        "Zen (endless)" => "Zen (endlos)",
+        "Dig race" => "Grab-Rennen",
+        "Garbage rows" => "Müllreihen",
+        "Garbage left:" => "Müll übrig:",
+        "All garbage cleared!" => "Aller Müll beseitigt!",
+        "Invisible stack" => "Unsichtbarer Stapel",
+        "Beginner column hints" => "Spaltenhilfe für Anfänger",
+        "Master (20G)" => "Meister (20G)",
+        "Grade:" => "Rang:",
+        "Section:" => "Abschnitt:",
+        "Section" => "Abschnitt",
+        "Profile" => "Profil",
+        "Crow color" => "Krähenfarbe",
+        "Accessory" => "Accessoire",
+        "Add profile" => "Profil hinzufügen",
+        "Black" => "Schwarz",
+        "Blue" => "Blau",
+        "White" => "Weiß",
+        "Red" => "Rot",
+        "Green" => "Grün",
+        "None" => "Keins",
+        "Top hat" => "Zylinder",
+        "Crown" => "Krone",
+        "Scarf" => "Schal",
+        "Monocle" => "Monokel",
+        "Missions" => "Missionen",
+        "Mission:" => "Mission:",
+        "Completed:" => "Erledigt:",
+        "Missions completed:" => "Erledigte Missionen:",
+        "Lines" => "Reihen",
+        "4-line clears" => "4-Reihen-Abräumer",
+        "T-spin clears" => "T-Spin-Abräumer",
+        "T-spin doubles" => "T-Spin-Doubles",
+        "Combo" => "Kombo",
+        "Daily challenge" => "Tägliche Herausforderung",
+        "Nobody has played today's challenge yet." => "Heute hat noch niemand die Herausforderung gespielt.",
+        "lines" => "Reihen",
+        "You've already played today's challenge. Come back tomorrow!" => "Du hast die heutige Herausforderung schon gespielt. Komm morgen wieder!",
+        "Practice (sandbox)" => "Training (Sandkasten)",
+        "Practice tools" => "Trainingswerkzeuge",
+        "Random" => "Zufällig",
+        "Next piece" => "Nächstes Teil",
+        "Gravity" => "Schwerkraft",
+        "Save snapshot" => "Zustand speichern",
+        "Restore" => "Laden",
+        "Battle vs CPU" => "Kampf gegen CPU",
+        "CPU lines:" => "CPU-Reihen:",
+        "Incoming garbage:" => "Eingehender Müll:",
+        "You win!" => "Du hast gewonnen!",
+        "The CPU wins" => "Die CPU gewinnt",
+        "2 players (same keyboard)" => "2 Spieler (eine Tastatur)",
+        "Player 2 lines:" => "Reihen Spieler 2:",
+        "Player 2: W rotate, A/D move, S drop" => "Spieler 2: W drehen, A/D bewegen, S fallen",
+        "Player 1 wins!" => "Spieler 1 gewinnt!",
+        "Player 2 wins!" => "Spieler 2 gewinnt!",
+        "Online 1v1" => "Online 1 gegen 1",
+        "Connection lost" => "Verbindung verloren",
+        "Host game" => "Spiel hosten",
+        "Address or room code:" => "Adresse oder Raumcode:",
+        "Join game" => "Beitreten",
+        "Room code:" => "Raumcode:",
+        "Waiting for an opponent to join..." => "Warte auf einen Gegner...",
+        "Not connected" => "Nicht verbunden",
+        "Opponent connected" => "Gegner verbunden",
+        "Start match" => "Match starten",
+        "Connected. Waiting for the host to start..." => "Verbunden. Warte auf den Host...",
+        "Leave" => "Verlassen",
+        "Opponent lines:" => "Reihen des Gegners:",
+        "Your opponent wins" => "Dein Gegner gewinnt",
+        "Battle royale (bots)" => "Battle Royale (Bots)",
+        "Finish off (highest stack)" => "Erledigen (höchster Stapel)",
+        "Leader (lowest stack)" => "Anführer (niedrigster Stapel)",
+        "Players left:" => "Verbleibende Spieler:",
+        "Target" => "Ziel",
+        "Placement:" => "Platzierung:",
+        "Time attack (checkpoints)" => "Zeitangriff (Checkpoints)",
+        "Checkpoint" => "Checkpoint",
+        "All checkpoints reached!" => "Alle Checkpoints erreicht!",
+        "Out of time" => "Zeit abgelaufen",
+        "Rules" => "Regeln",
+        "Modern" => "Modern",
+        "Classic (NES)" => "Klassisch (NES)",
+        "Sprint (40 lines)" => "Sprint (40 Reihen)",
+        "Lines left:" => "Verbleibende Reihen:",
+        "40 lines cleared!" => "40 Reihen abgeräumt!",
+        "Endless classic play. Clear lines and survive as long as you can." => "Endloses klassisches Spiel. Räume Reihen ab und halte so lange durch wie möglich.",
+        "Clear 40 lines as fast as you can." => "Räume 40 Reihen so schnell wie möglich ab.",
+        "Clearing lines earns items you can use with E." => "Abgeräumte Reihen bringen Gegenstände, die du mit E einsetzt.",
+        "After a clear, loose groups of blocks fall and can set off chains." => "Nach dem Abräumen fallen lose Gruppen und können Ketten auslösen.",
+        "Score as much as you can in two minutes." => "Erziele in zwei Minuten so viele Punkte wie möglich.",
+        "No game over: the stack trims itself when it gets too high." => "Kein Game Over: Der Stapel kürzt sich, wenn er zu hoch wird.",
+        "Dig through rows of garbage as fast as you can." => "Grabe dich so schnell wie möglich durch Müllreihen.",
+        "Locked blocks fade away, so remember your stack." => "Gesetzte Blöcke verblassen, also merk dir deinen Stapel.",
+        "Levels 0 to 999 with gravity up to 20G. Earn a grade." => "Level 0 bis 999 mit Schwerkraft bis 20G. Verdiene dir einen Rang.",
+        "Complete rotating objectives for bonus points." => "Erfülle wechselnde Ziele für Bonuspunkte.",
+        "The same pieces for everyone today, one attempt per day." => "Heute für alle dieselben Teile, ein Versuch pro Tag.",
+        "Pick pieces, turn gravity off and save snapshots." => "Wähle Teile, schalte die Schwerkraft ab und speichere Zustände.",
+        "Send garbage to a computer opponent until it tops out." => "Schicke der CPU Müll, bis sie überläuft.",
+        "Two players on one keyboard: arrow keys against WASD." => "Zwei Spieler an einer Tastatur: Pfeiltasten gegen WASD.",
+        "Play someone over the network by address or room code." => "Spiele übers Netzwerk per Adresse oder Raumcode.",
+        "Outlast a crowd of bots while garbage flies everywhere." => "Überlebe eine Horde Bots, während überall Müll fliegt.",
+        "Reach each line checkpoint before its time runs out." => "Erreiche jeden Checkpoint, bevor seine Zeit abläuft.",
+        "Starting level" => "Startlevel",
+        "Seed (optional):" => "Seed (optional):",
+        "Seed:" => "Seed:",
+        "Copy" => "Kopieren",
+        "Hold" => "Halten",
+        "Next" => "Nächste",
+        "Theme" => "Design",
+        "Reload" => "Neu laden",
+        "Skin" => "Skin",
+        "Flat colors" => "Flache Farben",
+        "Fullscreen (F11)" => "Vollbild (F11)",
+        "Grid lines and border" => "Gitterlinien und Rahmen",
+        "Screen shake" => "Bildschirmwackeln",
+        "Resume" => "Fortsetzen",
+        "Restart" => "Neu starten",
+        "Settings" => "Einstellungen",
+        "Quit to menu" => "Zum Menü",
+        "Gameplay" => "Spiel",
+        "Controls" => "Steuerung",
+        "Audio" => "Audio",
+        "Display" => "Anzeige",
+        "Effects volume" => "Effektlautstärke",
+        "Music volume" => "Musiklautstärke",
+        "Back" => "Zurück",
+        "Drop interval (ms)" => "Fallintervall (ms)",
+        "Move left" => "Nach links",
+        "Move right" => "Nach rechts",
+        "Soft drop" => "Schneller fallen",
+        "Hard drop" => "Sofort fallen",
+        "Use item" => "Gegenstand benutzen",
+        "Give up" => "Aufgeben",
+        "Live stats (PPS, APM, KPP)" => "Live-Statistiken (PPS, APM, KPP)",
+        "Press any key" => "Beliebige Taste drücken",
+        "Level" => "Level",
+        "speed up!" => "schneller!",
+        "UI scale" => "UI-Skalierung",
+        "Focus mode (hide score and labels)" => "Fokusmodus (Punkte und Texte ausblenden)",
+        "Fancy graphics (glow)" => "Aufwendige Grafik (Leuchten)",
+        "Needs the OpenGL renderer" => "Benötigt den OpenGL-Renderer",
+        "Cell style" => "Zellenstil",
+        "Rounded" => "Abgerundet",
+        "Flat" => "Flach",
+        "Beveled" => "Abgeschrägt",
+        "Outlined" => "Umriss",
+        "Show pressed keys (for streaming)" => "Gedrückte Tasten anzeigen (fürs Streaming)",
+        "Splits" => "Zwischenzeiten",
+        "New best splits!" => "Neue beste Zwischenzeiten!",
+        "Stack height" => "Stapelhöhe",
+        "Pieces" => "Teile",
+        "I drought:" => "I-Dürre:",
+        "No I for" => "Kein I seit",
+        "Pieces dealt since the last I piece" => "Teile seit dem letzten I",
+        "Rotate clockwise" => "Rechts drehen",
+        "Rotate counterclockwise" => "Links drehen",
+        "Key was taken; swapped:" => "Taste war belegt; getauscht:",
+        "Press a key or button..." => "Taste oder Knopf drücken...",
+        "Device:" => "Gerät:",
+        "Keyboard" => "Tastatur",
+        "Gamepad" => "Controller",
+        "Stick deadzone" => "Stick-Totzone",
+        "Touch controls (swipe and tap the board)" => "Touch-Steuerung (über das Feld wischen und tippen)",
+        "Mouse controls (piece follows the cursor)" => "Maussteuerung (Teil folgt dem Mauszeiger)",
+        "Left click rotates, the wheel rotates the other way, right click drops" => "Linksklick dreht, das Mausrad dreht andersherum, Rechtsklick lässt fallen",
+        "Handling" => "Steuerungsgefühl",
+        "Delayed auto shift" => "Verzögerung bis Dauerbewegung",
+        "Auto repeat rate" => "Wiederholrate",
+        "Soft drop speed" => "Tempo beim schnellen Fallen",
+        "Hold left or right to try it:" => "Links oder rechts halten zum Ausprobieren:",
+        "Guideline" => "Standard",
+        "Arrows only" => "Nur Pfeiltasten",
+        "Left-handed" => "Linkshänder",
+        "Custom" => "Eigene",
+        "Control profile" => "Steuerungsprofil",
+        "Save profile" => "Profil speichern",
+        "Delete profile" => "Profil löschen",
+        "One hand (left side)" => "Eine Hand (linke Seite)",
+        "One hand (right side)" => "Eine Hand (rechte Seite)",
+        "Controller rumble" => "Controller-Vibration",
+        "On-screen keyboard" => "Bildschirmtastatur",
+        "Delete" => "Löschen",
+        "Done" => "Fertig",
+        "Quit game" => "Spiel beenden",
+        "Abandon current game?" => "Aktuelles Spiel aufgeben?",
+        "Quit the game? The current run will be lost." => "Spiel beenden? Der aktuelle Lauf geht verloren.",
+        "Yes" => "Ja",
+        "No" => "Nein",
+        "One-switch mode (the drop key places pieces)" => "Ein-Tasten-Modus (die Fallen-Taste setzt Teile)",
+        "Rotation, then column, cycle by themselves; press to pick each" => "Erst Drehung, dann Spalte wechseln von selbst; drücken, um jeweils zu wählen",
+        "Scan step (ms)" => "Scan-Schritt (ms)",
+        "Press to keep this rotation" => "Drücken, um diese Drehung zu behalten",
+        "Press to drop here" => "Drücken, um hier fallen zu lassen",
+        "Menu music" => "Menümusik",
+        "Gameplay music" => "Spielmusik",
+        "Korobeiniki" => "Korobeiniki",
+        "Roost" => "Schlafplatz",
+        "Off" => "Aus",
+        "Master volume" => "Gesamtlautstärke",
+        "Built-in sounds" => "Eingebaute Klänge",
+        "Sound pack" => "Soundpaket",
+        "Announce each piece and the next with a sound" => "Jedes Teil und das nächste mit einem Ton ansagen",
+        "No scores yet" => "Noch keine Punktzahlen",
+        "Only finished runs go on this leaderboard" => "Nur beendete Runden kommen in diese Bestenliste",
+        "Save & Quit" => "Speichern und beenden",
+        "Continue" => "Fortsetzen",
+        "Replay" => "Wiederholung",
+        "Replays" => "Wiederholungen",
+        "No replays yet" => "Noch keine Wiederholungen",
+        "Watch" => "Ansehen",
+        "Watch replay" => "Wiederholung ansehen",
+        "Close" => "Schließen",
+        "Stats" => "Statistiken",
+        "Games played" => "Gespielte Spiele",
+        "Lines cleared" => "Geräumte Reihen",
+        "Pieces placed" => "Gesetzte Steine",
+        "Tetrises" => "Tetris",
+        "T-spins" => "T-Spins",
+        "Play time" => "Spielzeit",
+        "Best PPS" => "Bester PPS",
+        "Finesse faults:" => "Finesse-Fehler:",
+        "Export stats" => "Statistiken exportieren",
+        "Every game so far, as a CSV file" => "Alle bisherigen Spiele als CSV-Datei",
+        "Saved to" => "Gespeichert unter",
+        "The last run was interrupted." => "Die letzte Runde wurde unterbrochen.",
+        "Discard" => "Verwerfen",
+        "unverified" => "unbestätigt",
+        "This entry doesn't match its signature; the file may have been edited" => "Dieser Eintrag passt nicht zu seiner Signatur; die Datei wurde womöglich bearbeitet",
+        "legacy" => "alt",
+        "Carried over from an older version's score file" => "Aus der Punktedatei einer älteren Version übernommen",
+        "Retry" => "Erneut versuchen",
+        "Dismiss" => "Schließen",
+        "Couldn't save the settings:" => "Einstellungen konnten nicht gespeichert werden:",
+        "Couldn't read the settings:" => "Einstellungen konnten nicht gelesen werden:",
+        "Couldn't save the high scores:" => "Bestenliste konnte nicht gespeichert werden:",
+        "Couldn't save the lifetime stats:" => "Gesamtstatistik konnte nicht gespeichert werden:",
+        "Couldn't save the run:" => "Runde konnte nicht gespeichert werden:",
+        "Theme not found:" => "Design nicht gefunden:",
+        "Couldn't save the key bindings:" => "Tastenbelegung konnte nicht gespeichert werden:",
+        "Couldn't save the control profiles:" => "Steuerungsprofile konnten nicht gespeichert werden:",
+        "Couldn't save the daily results:" => "Tagesergebnisse konnten nicht gespeichert werden:",
+        "Couldn't save the profiles:" => "Profile konnten nicht gespeichert werden:",
+        "Couldn't save the best splits:" => "Beste Zwischenzeiten konnten nicht gespeichert werden:",
+        "Couldn't save the replay:" => "Wiederholung konnte nicht gespeichert werden:",
+        "Couldn't save the game history:" => "Spielverlauf konnte nicht gespeichert werden:",
+        "Couldn't autosave the run:" => "Runde konnte nicht automatisch gespeichert werden:",
+        "Couldn't load the sound pack:" => "Soundpaket konnte nicht geladen werden:",
+        "Connecting..." => "Verbinde...",
+        "Accept" => "Annehmen",
+        "Decline" => "Ablehnen",
+        "Waiting for your opponent to answer..." => "Warte auf die Antwort des Gegners...",
+        "Your opponent declined the rematch." => "Der Gegner hat die Revanche abgelehnt.",
+        "The host wants a rematch" => "Der Gastgeber will eine Revanche",
+        "Waiting for the host to offer a rematch..." => "Warte, bis der Gastgeber eine Revanche anbietet...",
+        "Best of" => "Best of",
+        "best of" => "Best of",
+        "Round" => "Runde",
+        "Winner" => "Sieger",
+        "Opponent lines" => "Reihen des Gegners",
+        "Attack" => "Angriff",
+        "PPS" => "PPS",
+        "Time" => "Zeit",
+        "Sudden death" => "Sudden Death",
+        "You" => "Du",
+        "Opponent" => "Gegner",
+        "Player 1" => "Spieler 1",
+        "Player 2" => "Spieler 2",
+        "You win the round" => "Du gewinnst die Runde",
+        "Your opponent wins the round" => "Der Gegner gewinnt die Runde",
+        "Player 1 wins the round" => "Spieler 1 gewinnt die Runde",
+        "Player 2 wins the round" => "Spieler 2 gewinnt die Runde",
+        "Sudden death: cheese race" => "Sudden Death: Wettgraben",
+        "The rounds are split evenly. Both boards start on the same garbage, and the first to dig out wins." => "Die Runden stehen unentschieden. Beide Felder beginnen mit demselben Müll, und wer sich zuerst freigräbt, gewinnt.",
+        "Next round" => "Nächste Runde",
+        "Waiting for the host to start the next round..." => "Warte, bis der Gastgeber die nächste Runde startet...",
+        "Press to fall here" => "Drücken, um hier zu fallen",
+        "Nobody" => "Niemand",
+        "Player 2 profile" => "Profil von Spieler 2",
+        _ => return None,
+    })
+}
+
+fn portuguese(text: &str) -> Option<&'static str> {
+    Some(match text {
+        "Start Game" => "Iniciar jogo",
+        "High Scores:" => "Recordes:",
+        "Score:" => "Pontuação:",
+        "Level:" => "Nível:",
+        "Game Paused" => "Jogo pausado",
+        "Game Over!" => "Fim de jogo!",
+        "Enter Name:" => "Digite seu nome:",
+        "Submit Score" => "Enviar pontuação",
+        "Back to Start" => "Voltar ao início",
+        "Language" => "Idioma",
+        "When unfocused" => "Sem foco",
+        "Pause" => "Pausar",
+        "Keep running (reduced redraws)" => "Continuar (menos redesenho)",
+        "Keep running" => "Continuar",
+        "Mode" => "Modo",
+        "Marathon" => "Maratona",
+        "Party (items)" => "Festa (itens)",
+        "Item:" => "Item:",
+        "Clear bottom rows" => "Limpar linhas de baixo",
+        "Slow your fall" => "Queda mais lenta",
+        "Shuffle your bottom rows" => "Embaralhar suas linhas de baixo",
+        "Randomizer" => "Aleatorizador",
+        "Classic (memoryless)" => "Clássico (sem memória)",
+        "7-bag" => "Saco de 7",
+        "Sticky gravity" => "Gravidade pegajosa",
+        "Rematch" => "Revanche",
+        "Change rules" => "Mudar regras",
+        "Ultra (2 minutes)" => "Ultra (2 minutos)",
+        "Lines:" => "Linhas:",
+        "Time left:" => "Tempo restante:",
+        "Time:" => "Tempo:",
+        "Copy board" => "Copiar tabuleiro",
+        "Load board" => "Carregar tabuleiro",
+        "Start with this board" => "Começar com este tabuleiro",
+        "Zen (endless)" => "Zen (sem fim)",
+        "Dig race" => "Corrida de escavação",
+        "Garbage rows" => "Linhas de lixo",
+        "Garbage left:" => "Lixo restante:",
+        "All garbage cleared!" => "Todo o lixo removido!",
+        "Invisible stack" => "Pilha invisível",
+        "Beginner column hints" => "Guias de coluna para iniciantes",
+        "Master (20G)" => "Mestre (20G)",
+        "Grade:" => "Grau:",
+        "Section:" => "Seção:",
+        "Section" => "Seção",
+        "Profile" => "Perfil",
+        "Crow color" => "Cor do corvo",
+        "Accessory" => "Acessório",
+        "Add profile" => "Adicionar perfil",
+        "Black" => "Preto",
+        "Blue" => "Azul",
+        "White" => "Branco",
+        "Red" => "Vermelho",
+        "Green" => "Verde",
+        "None" => "Nenhum",
+        "Top hat" => "Cartola",
+        "Crown" => "Coroa",
+        "Scarf" => "Cachecol",
+        "Monocle" => "Monóculo",
+        "Missions" => "Missões",
+        "Mission:" => "Missão:",
+        "Completed:" => "Concluídas:",
+        "Missions completed:" => "Missões concluídas:",
+        "Lines" => "Linhas",
+        "4-line clears" => "Limpezas de 4 linhas",
+        "T-spin clears" => "Linhas com T-spin",
+        "T-spin doubles" => "T-spin duplos",
+        "Combo" => "Combo",
+        "Daily challenge" => "Desafio diário",
+        "Nobody has played today's challenge yet." => "Ninguém jogou o desafio de hoje ainda.",
+        "lines" => "linhas",
+        "You've already played today's challenge. Come back tomorrow!" => "Você já jogou o desafio de hoje. Volte amanhã!",
+        "Practice (sandbox)" => "Treino (sandbox)",
+        "Practice tools" => "Ferramentas de treino",
+        "Random" => "Aleatória",
+        "Next piece" => "Próxima peça",
+        "Gravity" => "Gravidade",
+        "Save snapshot" => "Salvar estado",
+        "Restore" => "Restaurar",
+        "Battle vs CPU" => "Batalha contra a CPU",
+        "CPU lines:" => "Linhas da CPU:",
+        "Incoming garbage:" => "Lixo a caminho:",
+        "You win!" => "Você venceu!",
+        "The CPU wins" => "A CPU venceu",
+        "2 players (same keyboard)" => "2 jogadores (mesmo teclado)",
+        "Player 2 lines:" => "Linhas do jogador 2:",
+        "Player 2: W rotate, A/D move, S drop" => "Jogador 2: W girar, A/D mover, S descer",
+        "Player 1 wins!" => "O jogador 1 venceu!",
+        "Player 2 wins!" => "O jogador 2 venceu!",
+        "Online 1v1" => "1 contra 1 online",
+        "Connection lost" => "Conexão perdida",
+        "Host game" => "Hospedar partida",
+        "Address or room code:" => "Endereço ou código da sala:",
+        "Join game" => "Entrar",
+        "Room code:" => "Código da sala:",
+        "Waiting for an opponent to join..." => "Aguardando um oponente...",
+        "Not connected" => "Desconectado",
+        "Opponent connected" => "Oponente conectado",
+        "Start match" => "Iniciar partida",
+        "Connected. Waiting for the host to start..." => "Conectado. Aguardando o anfitrião...",
+        "Leave" => "Sair",
+        "Opponent lines:" => "Linhas do oponente:",
+        "Your opponent wins" => "Seu oponente venceu",
+        "Battle royale (bots)" => "Battle royale (bots)",
+        "Finish off (highest stack)" => "Finalizar (pilha mais alta)",
+        "Leader (lowest stack)" => "Líder (pilha mais baixa)",
+        "Players left:" => "Jogadores restantes:",
+        "Target" => "Alvo",
+        "Placement:" => "Colocação:",
+        "Time attack (checkpoints)" => "Contra o tempo (checkpoints)",
+        "Checkpoint" => "Checkpoint",
+        "All checkpoints reached!" => "Todos os checkpoints alcançados!",
+        "Out of time" => "Acabou o tempo",
+        "Rules" => "Regras",
+        "Modern" => "Moderno",
+        "Classic (NES)" => "Clássico (NES)",
+        "Sprint (40 lines)" => "Sprint (40 linhas)",
+        "Lines left:" => "Linhas restantes:",
+        "40 lines cleared!" => "40 linhas limpas!",
+        "Endless classic play. Clear lines and survive as long as you can." => "Jogo clássico sem fim. Limpe linhas e sobreviva o máximo que puder.",
+        "Clear 40 lines as fast as you can." => "Limpe 40 linhas o mais rápido possível.",
+        "Clearing lines earns items you can use with E." => "Limpar linhas dá itens que você usa com E.",
+        "After a clear, loose groups of blocks fall and can set off chains." => "Depois de limpar, grupos soltos caem e podem formar correntes.",
+        "Score as much as you can in two minutes." => "Faça o máximo de pontos em dois minutos.",
+        "No game over: the stack trims itself when it gets too high." => "Sem fim de jogo: a pilha se apara quando fica alta demais.",
+        "Dig through rows of garbage as fast as you can." => "Cave pelas linhas de lixo o mais rápido possível.",
+        "Locked blocks fade away, so remember your stack." => "Os blocos fixados somem, então lembre da sua pilha.",
+        "Levels 0 to 999 with gravity up to 20G. Earn a grade." => "Níveis 0 a 999 com gravidade até 20G. Conquiste um grau.",
+        "Complete rotating objectives for bonus points." => "Complete objetivos que mudam para ganhar pontos extras.",
+        "The same pieces for everyone today, one attempt per day." => "As mesmas peças para todos hoje, uma tentativa por dia.",
+        "Pick pieces, turn gravity off and save snapshots." => "Escolha peças, desligue a gravidade e salve estados.",
+        "Send garbage to a computer opponent until it tops out." => "Mande lixo para a CPU até ela transbordar.",
+        "Two players on one keyboard: arrow keys against WASD." => "Dois jogadores em um teclado: setas contra WASD.",
+        "Play someone over the network by address or room code." => "Jogue com alguém pela rede por endereço ou código da sala.",
+        "Outlast a crowd of bots while garbage flies everywhere." => "Sobreviva a uma multidão de bots com lixo voando para todo lado.",
+        "Reach each line checkpoint before its time runs out." => "Alcance cada checkpoint antes que o tempo acabe.",
+        "Starting level" => "Nível inicial",
+        "Seed (optional):" => "Semente (opcional):",
+        "Seed:" => "Semente:",
+        "Copy" => "Copiar",
+        "Hold" => "Reserva",
+        "Next" => "Próxima",
+        "Theme" => "Tema",
+        "Reload" => "Recarregar",
+        "Skin" => "Aparência",
+        "Flat colors" => "Cores sólidas",
+        "Fullscreen (F11)" => "Tela cheia (F11)",
+        "Grid lines and border" => "Grade e borda",
+        "Screen shake" => "Tremor de tela",
+        "Resume" => "Continuar",
+        "Restart" => "Reiniciar",
+        "Settings" => "Configurações",
+        "Quit to menu" => "Sair para o menu",
+        "Gameplay" => "Jogo",
+        "Controls" => "Controles",
+        "Audio" => "Áudio",
+        "Display" => "Tela",
+        "Effects volume" => "Volume dos efeitos",
+        "Music volume" => "Volume da música",
+        "Back" => "Voltar",
+        "Drop interval (ms)" => "Intervalo de queda (ms)",
+        "Move left" => "Mover para a esquerda",
+        "Move right" => "Mover para a direita",
+        "Soft drop" => "Descer",
+        "Hard drop" => "Queda rápida",
+        "Use item" => "Usar item",
+        "Give up" => "Desistir",
+        "Live stats (PPS, APM, KPP)" => "Estatísticas ao vivo (PPS, APM, KPP)",
+        "Press any key" => "Pressione qualquer tecla",
+        "Level" => "Nível",
+        "speed up!" => "mais rápido!",
+        "UI scale" => "Escala da interface",
+        "Focus mode (hide score and labels)" => "Modo foco (ocultar pontuação e textos)",
+        "Fancy graphics (glow)" => "Gráficos avançados (brilho)",
+        "Needs the OpenGL renderer" => "Requer o renderizador OpenGL",
+        "Cell style" => "Estilo das células",
+        "Rounded" => "Arredondado",
+        "Flat" => "Plano",
+        "Beveled" => "Chanfrado",
+        "Outlined" => "Contorno",
+        "Show pressed keys (for streaming)" => "Mostrar teclas pressionadas (para streaming)",
+        "Splits" => "Parciais",
+        "New best splits!" => "Novos melhores parciais!",
+        "Stack height" => "Altura da pilha",
+        "Pieces" => "Peças",
+        "I drought:" => "Seca de I:",
+        "No I for" => "Sem I há",
+        "Pieces dealt since the last I piece" => "Peças distribuídas desde a última I",
+        "Rotate clockwise" => "Girar para a direita",
+        "Rotate counterclockwise" => "Girar para a esquerda",
+        "Key was taken; swapped:" => "Tecla já usada; trocadas:",
+        "Press a key or button..." => "Pressione uma tecla ou botão...",
+        "Device:" => "Dispositivo:",
+        "Keyboard" => "Teclado",
+        "Gamepad" => "Controle",
+        "Stick deadzone" => "Zona morta do analógico",
+        "Touch controls (swipe and tap the board)" => "Controles de toque (deslize e toque no tabuleiro)",
+        "Mouse controls (piece follows the cursor)" => "Controle pelo mouse (a peça segue o cursor)",
+        "Left click rotates, the wheel rotates the other way, right click drops" => "Clique esquerdo gira, a roda gira para o outro lado, clique direito solta",
+        "Handling" => "Manuseio",
+        "Delayed auto shift" => "Atraso do deslocamento automático",
+        "Auto repeat rate" => "Taxa de repetição",
+        "Soft drop speed" => "Velocidade da queda suave",
+        "Hold left or right to try it:" => "Segure esquerda ou direita para testar:",
+        "Guideline" => "Padrão",
+        "Arrows only" => "Só setas",
+        "Left-handed" => "Canhoto",
+        "Custom" => "Personalizado",
+        "Control profile" => "Perfil de controles",
+        "Save profile" => "Salvar perfil",
+        "Delete profile" => "Excluir perfil",
+        "One hand (left side)" => "Uma mão (lado esquerdo)",
+        "One hand (right side)" => "Uma mão (lado direito)",
+        "Controller rumble" => "Vibração do controle",
+        "On-screen keyboard" => "Teclado na tela",
+        "Delete" => "Apagar",
+        "Done" => "Pronto",
+        "Quit game" => "Sair do jogo",
+        "Abandon current game?" => "Abandonar o jogo atual?",
+        "Quit the game? The current run will be lost." => "Sair do jogo? A partida atual será perdida.",
+        "Yes" => "Sim",
+        "No" => "Não",
+        "One-switch mode (the drop key places pieces)" => "Modo de um botão (a tecla de queda posiciona as peças)",
+        "Rotation, then column, cycle by themselves; press to pick each" => "A rotação e depois a coluna mudam sozinhas; pressione para escolher cada uma",
+        "Scan step (ms)" => "Passo da varredura (ms)",
+        "Press to keep this rotation" => "Pressione para manter esta rotação",
+        "Press to drop here" => "Pressione para soltar aqui",
+        "Menu music" => "Música do menu",
+        "Gameplay music" => "Música de jogo",
+        "Korobeiniki" => "Korobeiniki",
+        "Roost" => "Poleiro",
+        "Off" => "Desligada",
+        "Master volume" => "Volume geral",
+        "Built-in sounds" => "Sons embutidos",
+        "Sound pack" => "Pacote de sons",
+        "Announce each piece and the next with a sound" => "Anunciar cada peça e a próxima com um som",
+        "No scores yet" => "Ainda sem pontuações",
+        "Only finished runs go on this leaderboard" => "Só partidas concluídas entram nesta tabela",
+        "Save & Quit" => "Salvar e sair",
+        "Continue" => "Continuar",
+        "Replay" => "Replay",
+        "Replays" => "Replays",
+        "No replays yet" => "Nenhum replay ainda",
+        "Watch" => "Assistir",
+        "Watch replay" => "Assistir replay",
+        "Close" => "Fechar",
+        "Stats" => "Estatísticas",
+        "Games played" => "Partidas jogadas",
+        "Lines cleared" => "Linhas completadas",
+        "Pieces placed" => "Peças colocadas",
+        "Tetrises" => "Tetris",
+        "T-spins" => "T-spins",
+        "Play time" => "Tempo de jogo",
+        "Best PPS" => "Melhor PPS",
+        "Finesse faults:" => "Erros de finesse:",
+        "Export stats" => "Exportar estatísticas",
+        "Every game so far, as a CSV file" => "Todas as partidas até agora, num arquivo CSV",
+        "Saved to" => "Salvo em",
+        "The last run was interrupted." => "A última partida foi interrompida.",
+        "Discard" => "Descartar",
+        "unverified" => "não verificado",
+        "This entry doesn't match its signature; the file may have been edited" => "Esta entrada não corresponde à sua assinatura; o arquivo pode ter sido editado",
+        "legacy" => "antiga",
+        "Carried over from an older version's score file" => "Trazida do arquivo de pontuações de uma versão anterior",
+        "Retry" => "Tentar de novo",
+        "Dismiss" => "Fechar",
+        "Couldn't save the settings:" => "Não foi possível salvar as configurações:",
+        "Couldn't read the settings:" => "Não foi possível ler as configurações:",
+        "Couldn't save the high scores:" => "Não foi possível salvar os recordes:",
+        "Couldn't save the lifetime stats:" => "Não foi possível salvar as estatísticas:",
+        "Couldn't save the run:" => "Não foi possível salvar a partida:",
+        "Theme not found:" => "Tema não encontrado:",
+        "Couldn't save the key bindings:" => "Não foi possível salvar os controles:",
+        "Couldn't save the control profiles:" => "Não foi possível salvar os perfis de controle:",
+        "Couldn't save the daily results:" => "Não foi possível salvar os resultados diários:",
+        "Couldn't save the profiles:" => "Não foi possível salvar os perfis:",
+        "Couldn't save the best splits:" => "Não foi possível salvar os melhores parciais:",
+        "Couldn't save the replay:" => "Não foi possível salvar o replay:",
+        "Couldn't save the game history:" => "Não foi possível salvar o histórico:",
+        "Couldn't autosave the run:" => "Não foi possível salvar a partida automaticamente:",
+        "Couldn't load the sound pack:" => "Não foi possível carregar o pacote de sons:",
+        "Connecting..." => "Conectando...",
+        "Accept" => "Aceitar",
+        "Decline" => "Recusar",
+        "Waiting for your opponent to answer..." => "Aguardando a resposta do oponente...",
+        "Your opponent declined the rematch." => "O oponente recusou a revanche.",
+        "The host wants a rematch" => "O anfitrião quer uma revanche",
+        "Waiting for the host to offer a rematch..." => "Aguardando o anfitrião oferecer uma revanche...",
+        "Best of" => "Melhor de",
+        "best of" => "melhor de",
+        "Round" => "Rodada",
+        "Winner" => "Vencedor",
+        "Opponent lines" => "Linhas do oponente",
+        "Attack" => "Ataque",
+        "PPS" => "PPS",
+        "Time" => "Tempo",
+        "Sudden death" => "Morte súbita",
+        "You" => "Você",
+        "Opponent" => "Oponente",
+        "Player 1" => "Jogador 1",
+        "Player 2" => "Jogador 2",
+        "You win the round" => "Você vence a rodada",
+        "Your opponent wins the round" => "O oponente vence a rodada",
+        "Player 1 wins the round" => "O jogador 1 vence a rodada",
+        "Player 2 wins the round" => "O jogador 2 vence a rodada",
+        "Sudden death: cheese race" => "Morte súbita: corrida de escavação",
+        "The rounds are split evenly. Both boards start on the same garbage, and the first to dig out wins." => "As rodadas estão empatadas. Os dois tabuleiros começam com o mesmo lixo, e vence quem escavar primeiro.",
+        "Next round" => "Próxima rodada",
+        "Waiting for the host to start the next round..." => "Aguardando o anfitrião iniciar a próxima rodada...",
+        "Press to fall here" => "Pressione para cair aqui",
+        "Nobody" => "Ninguém",
+        "Player 2 profile" => "Perfil do jogador 2",
+        _ => return None,
+    })
+}
@@ -0,0 +1,63 @@
+// On-screen display of the game keys being held, for streams and tutorials.
+// Each key is drawn with its current binding and lights up while held.
+
+use eframe::egui;
+
+use crate::controls::{Action, Binding};
+use crate::CrowsTetris;
+
+const KEY_SIZE: egui::Vec2 = egui::vec2(34.0, 22.0);
+const KEY_GAP: f32 = 3.0;
+const HELD_COLOR: egui::Color32 = egui::Color32::from_rgb(250, 220, 90);
+
+// Laid out like the keys on a keyboard: (action, column, row), in key widths
+const LAYOUT: [(Action, f32, f32); 7] = [
+    (Action::Hold, 0.0, 0.0),
+    (Action::RotateCounterclockwise, 1.0, 0.0),
+    (Action::Rotate, 2.0, 0.0),
+    (Action::MoveLeft, 0.0, 1.0),
+    (Action::SoftDrop, 1.0, 1.0),
+    (Action::MoveRight, 2.0, 1.0),
+    (Action::HardDrop, 0.5, 2.0),
+];
+
+impl CrowsTetris {
+    pub(crate) fn render_input_display(&self, ctx: &egui::Context) {
+        if !self.show_input_display {
+            return;
+        }
+        let held: Vec<bool> = ctx.input(|input| {
+            LAYOUT.iter().map(|&(action, _, _)| self.controls.held(input, action)).collect()
+        });
+
+        egui::Area::new(egui::Id::new("input_display"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(4.0, -4.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                let size = egui::vec2(3.0 * KEY_SIZE.x + 2.0 * KEY_GAP, 3.0 * KEY_SIZE.y + 2.0 * KEY_GAP);
+                let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                let painter = ui.painter();
+                for (&(action, column, row), &down) in LAYOUT.iter().zip(&held) {
+                    let min = rect.min + egui::vec2(column * (KEY_SIZE.x + KEY_GAP), row * (KEY_SIZE.y + KEY_GAP));
+                    // Hard drop is the wide key along the bottom, like a space bar
+                    let width = if action == Action::HardDrop { 2.0 * KEY_SIZE.x + KEY_GAP } else { KEY_SIZE.x };
+                    let key = egui::Rect::from_min_size(min, egui::vec2(width, KEY_SIZE.y));
+                    let (fill, text) = if down {
+                        (HELD_COLOR, egui::Color32::BLACK)
+                    } else {
+                        (egui::Color32::from_black_alpha(150), egui::Color32::from_gray(200))
+                    };
+                    painter.rect_filled(key, 3.0, fill);
+                    let label = self.controls.bindings().get(&action).map_or("-".to_string(), Binding::label);
+                    painter.text(
+                        key.center(),
+                        egui::Align2::CENTER_CENTER,
+                        label,
+                        egui::FontId::proportional(9.0),
+                        text,
+                    );
+                }
+            });
+    }
+}
@@ -0,0 +1,110 @@
+// The frame's piece inputs go through a queue that the game step empties.
+// While no piece can take them (the countdown, a line clear between lock
+// and spawn) moves, rotations and holds wait in the queue and go to the next
+// piece the moment it appears, instead of being lost.
+
+use eframe::egui;
+use std::collections::VecDeque;
+
+use crate::audio::SoundEvent;
+use crate::controls::Action;
+use crate::replay::ReplayEvent;
+use crate::{CrowsTetris, GameMode, GameState};
+
+// Inputs that can wait for the next piece; a buffered hard drop would throw it away unseen
+const BUFFERED: [Action; 5] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Rotate,
+    Action::RotateCounterclockwise,
+    Action::Hold,
+];
+// Enough for a slide across the board and a few turns; anything past it is dropped
+pub const INPUT_QUEUE_LEN: usize = 48;
+// The order the rest of a frame's presses are applied in
+const PRESSES: [Action; 6] = [
+    Action::UseItem,
+    Action::Hold,
+    Action::RotateCounterclockwise,
+    Action::Rotate,
+    Action::SoftDrop,
+    Action::HardDrop,
+];
+
+pub fn push_input(queue: &mut VecDeque<Action>, action: Action, frozen: bool) {
+    if (!frozen || BUFFERED.contains(&action)) && queue.len() < INPUT_QUEUE_LEN {
+        queue.push_back(action);
+    }
+}
+
+impl CrowsTetris {
+    // The piece can't be moved before the countdown ends, or until it appears after a line clear
+    pub(crate) fn piece_frozen(&self) -> bool {
+        self.counting_down() || self.clearing()
+    }
+
+    pub(crate) fn queue_input(&mut self, ctx: &egui::Context) {
+        let frozen = self.piece_frozen();
+        let steps = self.shift_steps(ctx);
+        let step = if steps > 0 { Action::MoveRight } else { Action::MoveLeft };
+        for _ in 0..steps.abs() {
+            push_input(&mut self.input_queue, step, frozen);
+        }
+        for action in PRESSES {
+            if action == Action::UseItem && self.mode != GameMode::Party {
+                continue;
+            }
+            // In one-switch mode the hard drop input is the switch, which places the piece its own way
+            if action == Action::HardDrop && (self.one_switch || !self.hard_drop_allowed()) {
+                continue;
+            }
+            if self.action_pressed(ctx, action) {
+                push_input(&mut self.input_queue, action, frozen);
+            }
+        }
+    }
+
+    // Applies the queued inputs in order, stopping if the piece locks and the next one isn't out yet
+    pub(crate) fn step_input(&mut self) {
+        let mut moved = false;
+        while !self.piece_frozen() && self.state == GameState::Playing {
+            let Some(action) = self.input_queue.pop_front() else {
+                break;
+            };
+            match action {
+                Action::MoveLeft | Action::MoveRight => {
+                    moved |= self.shift_piece(if action == Action::MoveRight { 1 } else { -1 });
+                }
+                Action::UseItem => self.use_item(),
+                Action::Hold => self.hold_piece(),
+                Action::RotateCounterclockwise | Action::Rotate => self.rotate_block(action == Action::Rotate),
+                Action::SoftDrop => {
+                    if self.mode == GameMode::Practice && !self.practice_gravity {
+                        self.practice_drop();
+                    }
+                }
+                Action::HardDrop => self.hard_drop(),
+                Action::Pause | Action::Abandon | Action::Quit => {}
+            }
+        }
+        if moved {
+            self.audio.play(SoundEvent::Move);
+        }
+    }
+
+    // Moves the falling piece a column if there's room
+    pub(crate) fn shift_piece(&mut self, dx: i32) -> bool {
+        self.record_replay(ReplayEvent::Input(if dx > 0 { Action::MoveRight } else { Action::MoveLeft }));
+        let Some(block) = self.active_block.as_ref() else {
+            return false;
+        };
+        if self.check_collision_with_position((block.position.0 + dx, block.position.1)) {
+            return false;
+        }
+        if let Some(block) = self.active_block.as_mut() {
+            block.position.0 += dx;
+        }
+        self.last_move_rotated = false;
+        true
+    }
+}
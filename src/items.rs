@@ -0,0 +1,72 @@
+// Party mode power-ups, granted at random for clearing lines. Party mode is
+// played alone, so every item works on the player's own board.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+use crate::audio::SoundEvent;
+use crate::{CrowsTetris, GameMode};
+
+const ITEM_CHANCE_PER_LINE: f64 = 0.25;
+const CLEARED_ROWS: usize = 2;
+const SHUFFLED_ROWS: usize = 4;
+const SLOW_FALL_DURATION: Duration = Duration::from_secs(10);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Item {
+    ClearRows,
+    SlowFall,
+    ShuffleRows,
+}
+
+impl Item {
+    pub fn label(self) -> &'static str {
+        match self {
+            Item::ClearRows => "Clear bottom rows",
+            Item::SlowFall => "Slow your fall",
+            Item::ShuffleRows => "Shuffle your bottom rows",
+        }
+    }
+
+    fn random(rng: &mut impl Rng) -> Item {
+        match rng.random_range(0..3) {
+            0 => Item::ClearRows,
+            1 => Item::SlowFall,
+            _ => Item::ShuffleRows,
+        }
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn maybe_grant_item(&mut self, lines_cleared: usize) {
+        if self.mode != GameMode::Party || lines_cleared == 0 || self.held_item.is_some() {
+            return;
+        }
+
+        let chance = (ITEM_CHANCE_PER_LINE * lines_cleared as f64).min(1.0);
+        if self.game_rng.random_bool(chance) {
+            self.held_item = Some(Item::random(&mut self.game_rng));
+        }
+    }
+
+    pub(crate) fn use_item(&mut self) {
+        let Some(item) = self.held_item.take() else {
+            return;
+        };
+        self.audio.play(SoundEvent::Item);
+
+        match item {
+            Item::ClearRows => self.remove_bottom_rows(CLEARED_ROWS),
+            Item::SlowFall => {
+                self.slowed_until = Some(Instant::now() + SLOW_FALL_DURATION);
+            }
+            // Can open a hole up as easily as it fills one in
+            Item::ShuffleRows => {
+                for row in self.grid.iter_mut().rev().take(SHUFFLED_ROWS) {
+                    row.shuffle(&mut self.game_rng);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+// Lifetime statistics: totals over every game played, kept in
+// lifetime_stats.toml and added to at the end of each run, and the Stats
+// screen that shows them.
+
+use eframe::egui;
+use std::fs;
+use std::io;
+use std::time::Duration;
+use toml_edit::{value, DocumentMut};
+
+use crate::file_errors::Retry;
+use crate::{data_dir, history, CrowsTetris, GameState, RunResult};
+
+pub const LIFETIME_STATS_FILE: &str = "lifetime_stats.toml";
+// Runs shorter than this don't count toward the best pace, or a quick drop or two would top it
+const MIN_PIECES_FOR_PPS: u32 = 20;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LifetimeStats {
+    pub games: u32,
+    pub lines: u64,
+    pub pieces: u64,
+    pub tetrises: u32,
+    pub t_spins: u32,
+    pub play_time: Duration,
+    pub best_pps: f64,
+}
+
+impl LifetimeStats {
+    pub fn add_run(&mut self, result: &RunResult, tetrises: u32, t_spins: u32) {
+        self.games += 1;
+        self.lines += result.lines as u64;
+        self.pieces += result.pieces as u64;
+        self.tetrises += tetrises;
+        self.t_spins += t_spins;
+        self.play_time += result.duration;
+        if result.pieces >= MIN_PIECES_FOR_PPS {
+            self.best_pps = self.best_pps.max(result.pieces_per_second());
+        }
+    }
+}
+
+pub fn load_lifetime_stats() -> LifetimeStats {
+    let Some(document) = fs::read_to_string(data_dir::path(LIFETIME_STATS_FILE))
+        .ok()
+        .and_then(|text| text.parse::<DocumentMut>().ok())
+    else {
+        return LifetimeStats::default();
+    };
+    let number = |key| document.get(key).and_then(|item| item.as_integer()).unwrap_or(0).max(0);
+    LifetimeStats {
+        games: number("games") as u32,
+        lines: number("lines") as u64,
+        pieces: number("pieces") as u64,
+        tetrises: number("tetrises") as u32,
+        t_spins: number("t_spins") as u32,
+        play_time: Duration::from_millis(number("play_time_ms") as u64),
+        best_pps: document.get("best_pps").and_then(|item| item.as_float()).unwrap_or(0.0).max(0.0),
+    }
+}
+
+pub fn save_lifetime_stats(stats: &LifetimeStats) -> io::Result<()> {
+    let mut document = DocumentMut::new();
+    document["games"] = value(stats.games as i64);
+    document["lines"] = value(stats.lines as i64);
+    document["pieces"] = value(stats.pieces as i64);
+    document["tetrises"] = value(stats.tetrises as i64);
+    document["t_spins"] = value(stats.t_spins as i64);
+    document["play_time_ms"] = value(stats.play_time.as_millis() as i64);
+    document["best_pps"] = value(stats.best_pps);
+    data_dir::write_atomic(LIFETIME_STATS_FILE, &document.to_string())
+}
+
+// Hours and minutes; a run's minutes, seconds and milliseconds are too fine for a lifetime
+fn format_hours(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+impl CrowsTetris {
+    pub(crate) fn record_lifetime_stats(&mut self) {
+        let Some(result) = &self.last_result else {
+            return;
+        };
+        self.lifetime_stats.add_run(result, self.tetrises, self.t_spins);
+        self.save_lifetime();
+    }
+
+    pub(crate) fn save_lifetime(&mut self) {
+        let saved = save_lifetime_stats(&self.lifetime_stats);
+        self.check_saved(saved, Retry::SaveLifetimeStats);
+    }
+
+    pub(crate) fn render_stats_screen(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.heading(self.tr("Stats"));
+                ui.add_space(20.0);
+
+                let stats = &self.lifetime_stats;
+                egui::Grid::new("lifetime_stats").num_columns(2).spacing([40.0, 6.0]).show(ui, |ui| {
+                    let rows = [
+                        ("Games played", stats.games.to_string()),
+                        ("Lines cleared", stats.lines.to_string()),
+                        ("Pieces placed", stats.pieces.to_string()),
+                        ("Tetrises", stats.tetrises.to_string()),
+                        ("T-spins", stats.t_spins.to_string()),
+                        ("Play time", format_hours(stats.play_time)),
+                        ("Best PPS", format!("{:.2}", stats.best_pps)),
+                    ];
+                    for (label, value) in rows {
+                        ui.label(self.tr(label));
+                        ui.strong(value);
+                        ui.end_row();
+                    }
+                });
+
+                ui.add_space(20.0);
+                if ui.button(self.tr("Export stats")).on_hover_text(self.tr("Every game so far, as a CSV file")).clicked() {
+                    self.stats_export = Some(history::export_stats());
+                }
+                match &self.stats_export {
+                    Some(Ok(path)) => {
+                        ui.label(format!("{} {}", self.tr("Saved to"), path.display()));
+                    }
+                    Some(Err(error)) => {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+                    None => {}
+                }
+
+                ui.add_space(20.0);
+                if ui.button(self.tr("Back to Start")).clicked() {
+                    self.state = GameState::StartScreen;
+                }
+            });
+        });
+    }
+}
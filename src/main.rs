@@ -1,31 +1,427 @@
+mod audio;
+mod autosave;
+mod battle;
+mod bloom;
+mod board_text;
+mod classic;
+mod clears;
+mod config;
+mod confirm;
+mod controls;
+mod daily;
+mod data_dir;
+mod debug;
+mod effects;
+mod file_errors;
+mod finesse;
+mod gamepad;
+mod handling;
+mod high_scores;
+mod hot_reload;
+mod history;
+mod garbage;
+mod hold;
+mod i18n;
+mod input_display;
+mod input_queue;
+mod items;
+mod lifetime;
+mod master;
+mod mouse;
+mod music;
+mod name_entry;
+mod mission;
+mod one_switch;
+mod online;
+mod pause;
+mod practice;
+mod profile;
+mod randomizer;
+mod replay;
+mod replay_viewer;
+mod rng_audit;
+mod rounds;
+mod self_test;
+mod settings;
+mod skin;
+mod sound_pack;
+mod splits;
+mod stats;
+mod sticky;
+mod suspend;
+mod theme;
+mod time_attack;
+mod title;
+mod touch;
+
+use audio::{Mixer, SoundEvent};
+use battle::{RivalBoard, Targeting, GARBAGE_METER_WIDTH};
+use bloom::{GlowRenderer, GlowSprite, GLOW_RADIUS};
+use classic::Ruleset;
+use confirm::Confirm;
+use controls::{Action, Controls};
+use daily::DailyResult;
+use effects::{Effect, LevelUp, LineClearAnimation, Shake};
+use gamepad::Gamepads;
+use handling::{AutoShift, Handling};
+use high_scores::{load_high_scores, HighScore};
 use eframe::egui;
-use std::fs::{self, OpenOptions};
-use std::io::{self, BufRead, Write};
-use rand::Rng;
+use i18n::Language;
+use items::Item;
+use lifetime::LifetimeStats;
+use master::{MASTER_LOCK_DELAY, MASTER_MAX_LEVEL};
+use mission::Mission;
+use music::MusicTrack;
+use one_switch::OneSwitch;
+use online::{Message, OnlineSession};
+use practice::Snapshot;
+use profile::Profile;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use randomizer::{PieceGenerator, Randomizer};
+use replay::{Replay, ReplayEvent};
+use replay_viewer::Playback;
+use rng_audit::AuditReport;
+use rounds::VersusMatch;
+use serde::{Deserialize, Serialize};
+use skin::Skin;
+use stats::PieceTally;
+use theme::{CellStyle, Theme};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
+use time_attack::TIME_ATTACK_LINES;
+use touch::TouchGesture;
 
-const HIGH_SCORE_FILE: &str = "high_scores.txt";
 const GRID_WIDTH: usize = 40;
 const GRID_HEIGHT: usize = 21;
+const BACKGROUND_REPAINT_INTERVAL: Duration = Duration::from_millis(500);
+// Keeps the HUD clocks ticking even when gravity is slow; the run timer shows milliseconds
+const HUD_REPAINT_INTERVAL: Duration = Duration::from_millis(16);
+// Gravity waits this long when a run starts or resumes
+const COUNTDOWN: Duration = Duration::from_secs(3);
+const ULTRA_DURATION: Duration = Duration::from_secs(120);
+const ZEN_PIECE_SCORE: i32 = 10;
+const SPRINT_LINES: u32 = 40;
+// Smallest a board cell is drawn; below this the screen scrolls instead
+const MIN_CELL_SIZE: f32 = 4.0;
+// The layout is made for a window this size; other sizes zoom everything to match
+const DESIGN_WINDOW_SIZE: egui::Vec2 = egui::vec2(420.0, 540.0);
+const MIN_WINDOW_SIZE: egui::Vec2 = egui::vec2(300.0, 380.0);
+const SIDE_PANEL_WIDTH: f32 = 64.0;
+const MAX_DIG_ROWS: usize = 15;
+const INVISIBLE_DELAY: Duration = Duration::from_secs(1);
+const INVISIBLE_FADE: Duration = Duration::from_millis(500);
+
+type Grid = [[u8; GRID_WIDTH]; GRID_HEIGHT];
+// When each cell of the grid was filled, used to fade out the stack in invisible mode
+type AgeGrid = [[Option<Instant>; GRID_WIDTH]; GRID_HEIGHT];
 
 struct CrowsTetris {
     state: GameState,
     score: i32,
-    high_scores: Vec<(String, i32)>,
+    high_scores: Vec<HighScore>,
     new_high_score_name: String,
+    // The key picked on the on-screen keyboard for the name: (column, row)
+    name_cursor: (usize, usize),
     is_paused: bool,
-    grid: [[u8; GRID_WIDTH]; GRID_HEIGHT],
+    grid: Grid,
     active_block: Option<Block>,
     last_update: Instant, // Timer for block movement
     drop_speed: Duration,
-    selected_difficulty: Option<String>,
+    language: Language,
+    background_behavior: BackgroundBehavior,
+    was_focused: bool,
+    mode: GameMode,
+    held_item: Option<Item>,
+    slowed_until: Option<Instant>,
+    randomizer: Randomizer,
+    ruleset: Ruleset,
+    start_level: u32,
+    piece_generator: PieceGenerator,
+    piece_rng: StdRng,
+    // Everything else random in a run (garbage holes, items, missions, rival boards), kept apart
+    // from the pieces so garbage arriving at different times online doesn't change them
+    game_rng: StdRng,
+    // Seed typed on the start screen; a random one is picked when it's empty
+    seed_text: String,
+    run_seed: u64,
+    rng_audit: Vec<AuditReport>,
+    changing_rules: bool,
+    controls: Controls,
+    gamepads: Gamepads,
+    handling: Handling,
+    auto_shift: AutoShift,
+    soft_dropping: bool,
+    // The block on the settings screen's strip for trying the handling
+    handling_test: AutoShift,
+    handling_test_column: i32,
+    // Rotation and column scan by themselves and one input picks them
+    one_switch: bool,
+    one_switch_state: OneSwitch,
+    scan_interval: Duration,
+    // Swipe and tap the board to play; turned on by the first touch
+    touch_controls: bool,
+    touch_gesture: Option<TouchGesture>,
+    // The piece follows the mouse, which clicks and scrolls to turn and drop it
+    mouse_controls: bool,
+    // Scrolling not yet turned into a rotation
+    mouse_scroll: f32,
+    // Actions touches or the mouse made on the board this frame
+    pointer_actions: Vec<Action>,
+    // Piece inputs waiting for the game step, or for the next piece
+    input_queue: VecDeque<Action>,
+    lines: u32,
+    pieces_placed: u32,
+    // When the stack reached the top, for the animation before the results
+    top_out_at: Option<Instant>,
+    level_up: Option<LevelUp>,
+    show_debug: bool,
+    // Set when running on OpenGL, which the glow effect needs
+    glow_renderer: Option<GlowRenderer>,
+    fancy_graphics: bool,
+    ui_scale: f32,
+    window_size: egui::Vec2,
+    // Where the window's corner was, in the same units as its size; None until the system says
+    window_position: Option<egui::Pos2>,
+    fullscreen: bool,
+    frame_times: VecDeque<f32>,
+    // The animated title comes before the menu at launch
+    at_title: bool,
+    title_pieces: Vec<title::TitlePiece>,
+    title_updated: Instant,
+    keys_pressed: u32,
+    attack: u32,
+    show_live_stats: bool,
+    // Only the board, hold box and next queue during play
+    focus_mode: bool,
+    show_input_display: bool,
+    run_started: Instant,
+    paused_time: Duration,
+    paused_at: Option<Instant>,
+    // While counting down the run is still paused as far as the clock is concerned
+    countdown_until: Option<Instant>,
+    pause_selection: usize,
+    // Where the settings screen goes back to
+    settings_return: GameState,
+    // The action waiting for a new key on the settings screen
+    rebinding: Option<Action>,
+    // A question about discarding the run, and whether the game was paused before it was asked
+    confirming: Option<(Confirm, bool)>,
+    // The last rebinding took a key from another action, which got the old key: (rebound, other)
+    rebind_swap: Option<(Action, Action)>,
+    // Typed in settings to save the bindings as a control profile
+    new_control_profile_name: String,
+    last_result: Option<RunResult>,
+    board_text: String,
+    board_text_error: Option<String>,
+    audio: Mixer,
+    menu_music: MusicTrack,
+    // Plays a sound for each new piece and the one after it
+    announce_pieces: bool,
+    gameplay_music: MusicTrack,
+    dig_rows: usize,
+    // Rounds in a versus or online match, and the match being played
+    best_of: u32,
+    versus_match: Option<VersusMatch>,
+    // Index into profiles of whoever plays the second board in local versus
+    player_two_profile: Option<usize>,
+    locked_at: AgeGrid,
+    show_trajectory: bool,
+    show_grid_lines: bool,
+    level: u32,
+    landed_at: Option<Instant>,
+    section_times: Vec<Duration>,
+    section_started: Duration,
+    // Run time at every 10 lines, in Sprint and Marathon
+    splits: Vec<Duration>,
+    best_splits: splits::BestSplits,
+    // Run time and stack height after every piece
+    stack_heights: Vec<(Duration, usize)>,
+    piece_tally: PieceTally,
+    profiles: Vec<Profile>,
+    active_profile: usize,
+    new_profile_name: String,
+    last_move_rotated: bool,
+    combo: u32,
+    // This run's Tetrises and T-spins, for the lifetime stats
+    tetrises: u32,
+    t_spins: u32,
+    // Movement presses for the falling piece and whether it was soft dropped, for judging finesse
+    piece_presses: u32,
+    piece_soft_dropped: bool,
+    finesse_faults: u32,
+    lifetime_stats: LifetimeStats,
+    // Where the last "Export stats" wrote to, or why it couldn't
+    stats_export: Option<Result<PathBuf, String>>,
+    // The combo as the meter shows it, easing after the real count
+    combo_meter: f32,
+    combo_meter_moving: bool,
+    mission: Mission,
+    missions_completed: u32,
+    daily_results: Vec<DailyResult>,
+    next_piece: Option<BlockType>,
+    next_queue: VecDeque<BlockType>,
+    held_piece: Option<BlockType>,
+    // Only one hold per piece, until it locks
+    hold_used: bool,
+    practice_gravity: bool,
+    snapshots: Vec<Snapshot>,
+    rivals: Vec<RivalBoard>,
+    targeting: Targeting,
+    incoming_garbage: usize,
+    online: Option<OnlineSession>,
+    online_address: String,
+    online_error: Option<String>,
+    line_clear: Option<LineClearAnimation>,
+    effects: Vec<Effect>,
+    effects_updated: Instant,
+    shake: Option<Shake>,
+    screen_shake: bool,
+    themes: Vec<Theme>,
+    theme: usize,
+    skin: Option<Skin>,
+    cell_style: CellStyle,
+    skin_error: Option<String>,
+    sound_pack_error: Option<String>,
+    // Whether there's a run saved with Save & Quit to continue
+    has_suspended_run: bool,
+    // An autosave was found at launch
+    has_interrupted_run: bool,
+    autosaved_at: Option<Instant>,
+    watched: hot_reload::WatchedFiles,
+    file_errors: Vec<file_errors::FileError>,
+    // Finished games still to be added to the history file
+    unsaved_games: Vec<history::GameRecord>,
+    // What this run's replay has so far; None when it isn't being recorded
+    replay_log: Option<Vec<(Duration, ReplayEvent)>>,
+    replay_board: String,
+    last_replay: Option<Replay>,
+    playback: Option<Playback>,
+    replay_error: Option<String>,
+    // Loaded theme backgrounds by path; None if the file couldn't be loaded
+    background_textures: HashMap<String, Option<egui::TextureHandle>>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum GameState {
     StartScreen,
     Playing,
     GameOver,
+    RngAudit,
+    Settings,
+    Stats,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+enum GameMode {
+    Marathon,
+    Sprint,
+    Party,
+    Sticky,
+    Ultra,
+    Zen,
+    Dig,
+    Invisible,
+    Master,
+    Mission,
+    Daily,
+    Practice,
+    Battle,
+    Versus,
+    Online,
+    Royale,
+    TimeAttack,
+}
+
+impl GameMode {
+    const ALL: [GameMode; 17] = [
+        GameMode::Marathon,
+        GameMode::Sprint,
+        GameMode::Party,
+        GameMode::Sticky,
+        GameMode::Ultra,
+        GameMode::Zen,
+        GameMode::Dig,
+        GameMode::Invisible,
+        GameMode::Master,
+        GameMode::Mission,
+        GameMode::Daily,
+        GameMode::Practice,
+        GameMode::Battle,
+        GameMode::Versus,
+        GameMode::Online,
+        GameMode::Royale,
+        GameMode::TimeAttack,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            GameMode::Marathon => "Marathon",
+            GameMode::Sprint => "Sprint (40 lines)",
+            GameMode::Party => "Party (items)",
+            GameMode::Sticky => "Sticky gravity",
+            GameMode::Ultra => "Ultra (2 minutes)",
+            GameMode::Zen => "Zen (endless)",
+            GameMode::Dig => "Dig race",
+            GameMode::Invisible => "Invisible stack",
+            GameMode::Master => "Master (20G)",
+            GameMode::Mission => "Missions",
+            GameMode::Daily => "Daily challenge",
+            GameMode::Practice => "Practice (sandbox)",
+            GameMode::Battle => "Battle vs CPU",
+            GameMode::Versus => "2 players (same keyboard)",
+            GameMode::Online => "Online 1v1",
+            GameMode::Royale => "Battle royale (bots)",
+            GameMode::TimeAttack => "Time attack (checkpoints)",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            GameMode::Marathon => "Endless classic play. Clear lines and survive as long as you can.",
+            GameMode::Sprint => "Clear 40 lines as fast as you can.",
+            GameMode::Party => "Clearing lines earns items you can use with E.",
+            GameMode::Sticky => "After a clear, loose groups of blocks fall and can set off chains.",
+            GameMode::Ultra => "Score as much as you can in two minutes.",
+            GameMode::Zen => "No game over: the stack trims itself when it gets too high.",
+            GameMode::Dig => "Dig through rows of garbage as fast as you can.",
+            GameMode::Invisible => "Locked blocks fade away, so remember your stack.",
+            GameMode::Master => "Levels 0 to 999 with gravity up to 20G. Earn a grade.",
+            GameMode::Mission => "Complete rotating objectives for bonus points.",
+            GameMode::Daily => "The same pieces for everyone today, one attempt per day.",
+            GameMode::Practice => "Pick pieces, turn gravity off and save snapshots.",
+            GameMode::Battle => "Send garbage to a computer opponent until it tops out.",
+            GameMode::Versus => "Two players on one keyboard: arrow keys against WASD.",
+            GameMode::Online => "Play someone over the network by address or room code.",
+            GameMode::Royale => "Outlast a crowd of bots while garbage flies everywhere.",
+            GameMode::TimeAttack => "Reach each line checkpoint before its time runs out.",
+        }
+    }
+}
+
+// What the game does while the window doesn't have focus.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+enum BackgroundBehavior {
+    Pause,
+    Reduced,
+    Full,
+}
+
+impl BackgroundBehavior {
+    const ALL: [BackgroundBehavior; 3] = [
+        BackgroundBehavior::Pause,
+        BackgroundBehavior::Reduced,
+        BackgroundBehavior::Full,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            BackgroundBehavior::Pause => "Pause",
+            BackgroundBehavior::Reduced => "Keep running (reduced redraws)",
+            BackgroundBehavior::Full => "Keep running",
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -39,141 +435,794 @@ enum BlockType {
     L,
 }
 
+impl BlockType {
+    const ALL: [BlockType; 7] = [
+        BlockType::I,
+        BlockType::O,
+        BlockType::T,
+        BlockType::S,
+        BlockType::Z,
+        BlockType::J,
+        BlockType::L,
+    ];
+
+    // Grid cells store the type of the piece that filled them, with 0 meaning empty
+    fn cell(self) -> u8 {
+        self as u8 + 1
+    }
+
+    fn from_cell(cell: u8) -> Option<BlockType> {
+        BlockType::ALL.get((cell as usize).checked_sub(1)?).copied()
+    }
+
+    fn letter(self) -> char {
+        match self {
+            BlockType::I => 'I',
+            BlockType::O => 'O',
+            BlockType::T => 'T',
+            BlockType::S => 'S',
+            BlockType::Z => 'Z',
+            BlockType::J => 'J',
+            BlockType::L => 'L',
+        }
+    }
+
+    fn from_letter(letter: char) -> Option<BlockType> {
+        let letter = letter.to_ascii_uppercase();
+        BlockType::ALL.iter().copied().find(|block_type| block_type.letter() == letter)
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            BlockType::I => egui::Color32::from_rgb(0, 240, 240),
+            BlockType::O => egui::Color32::from_rgb(240, 240, 0),
+            BlockType::T => egui::Color32::from_rgb(160, 0, 240),
+            BlockType::S => egui::Color32::from_rgb(0, 240, 0),
+            BlockType::Z => egui::Color32::from_rgb(240, 0, 0),
+            BlockType::J => egui::Color32::from_rgb(0, 0, 240),
+            BlockType::L => egui::Color32::from_rgb(240, 160, 0),
+        }
+    }
+}
+
+// Summary of a finished run, shown on the game over screen
 #[derive(Debug, Clone)]
+struct RunResult {
+    mode: GameMode,
+    completed: bool,
+    score: i32,
+    lines: u32,
+    pieces: u32,
+    duration: Duration,
+    level: u32,
+    grade: Option<&'static str>,
+    section_times: Vec<Duration>,
+    missions_completed: u32,
+    placement: Option<usize>,
+    seed: u64,
+    splits: Vec<Duration>,
+    stack_heights: Vec<(Duration, usize)>,
+    // The best run's splits as they were before this run, to compare against
+    previous_best_splits: Vec<Duration>,
+    new_best_splits: bool,
+    finesse_faults: u32,
+}
+
+impl RunResult {
+    fn pieces_per_second(&self) -> f64 {
+        self.pieces as f64 / self.duration.as_secs_f64().max(1.0)
+    }
+}
+
+// How visible a locked cell of the given age is in invisible mode, from 1.0 down to 0.0
+fn invisible_cell_visibility(age: Duration) -> f32 {
+    let fading_for = age.saturating_sub(INVISIBLE_DELAY);
+    (1.0 - fading_for.as_secs_f32() / INVISIBLE_FADE.as_secs_f32()).max(0.0)
+}
+
+// Minutes, seconds and milliseconds, e.g. 1:05.250
+fn format_time(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    format!("{}:{:02}.{:03}", millis / 60_000, millis / 1000 % 60, millis % 1000)
+}
+
+#[derive(Debug, Clone, PartialEq)]
 struct Block {
     block_type: BlockType,
     position: (i32, i32),
     shape: Vec<Vec<u8>>,
 }
 
-fn load_high_scores() -> Vec<(String, i32)> {
-    if let Ok(file) = fs::File::open(HIGH_SCORE_FILE) {
-        io::BufReader::new(file)
-            .lines()
-            .filter_map(|line| {
-                let line = line.ok()?;
-                let parts: Vec<&str> = line.split(',').collect();
-                if parts.len() == 2 {
-                    let name = parts[0].to_string();
-                    if let Ok(score) = parts[1].parse::<i32>() {
-                        return Some((name, score));
-                    }
-                }
-                None
-            })
-            .collect()
-    } else {
-        vec![]
+impl Block {
+    fn new(block_type: BlockType) -> Self {
+        let shape = match block_type {
+            BlockType::I => vec![vec![1, 1, 1, 1]],
+            BlockType::O => vec![vec![1, 1], vec![1, 1]],
+            BlockType::T => vec![vec![0, 1, 0], vec![1, 1, 1]],
+            BlockType::S => vec![vec![0, 1, 1], vec![1, 1, 0]],
+            BlockType::Z => vec![vec![1, 1, 0], vec![0, 1, 1]],
+            BlockType::J => vec![vec![1, 0, 0], vec![1, 1, 1]],
+            BlockType::L => vec![vec![0, 0, 1], vec![1, 1, 1]],
+        };
+
+        Block {
+            block_type,
+            position: (GRID_WIDTH as i32 / 2 - shape[0].len() as i32 / 2, 0), // Starts at the top center
+            shape,
+        }
+    }
+}
+
+// FNV-1a: quick, and the same on every platform and version, unlike the standard library's hasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+// A typed seed: a number is used as is, any other text is hashed so words
+// work as seeds too. Empty means no seed.
+fn seed_from_text(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
     }
+    Some(text.parse().unwrap_or_else(|_| fnv1a(text.as_bytes())))
 }
 
-fn save_high_scores(high_scores: &[(String, i32)]) {
-    if let Ok(mut file) = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open(HIGH_SCORE_FILE)
-    {
-        for (name, score) in high_scores {
-            writeln!(file, "{},{}", name, score).ok();
-        }
+// The piece after a quarter turn under the given rules, before checking there's room for it
+fn turned(ruleset: Ruleset, block: &Block, clockwise: bool) -> Block {
+    match ruleset {
+        Ruleset::Modern => Block {
+            shape: if clockwise {
+                rotate_shape(&block.shape)
+            } else {
+                rotate_shape_counterclockwise(&block.shape)
+            },
+            ..block.clone()
+        },
+        Ruleset::Classic => classic::rotated(block, clockwise),
     }
 }
 
+fn rotate_shape(shape: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    (0..shape[0].len())
+        .map(|i| shape.iter().rev().map(|row| row[i]).collect())
+        .collect()
+}
+
+fn rotate_shape_counterclockwise(shape: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    (0..shape[0].len())
+        .rev()
+        .map(|i| shape.iter().map(|row| row[i]).collect())
+        .collect()
+}
+
 impl Default for CrowsTetris {
     fn default() -> Self {
+        Self::new(Mixer::new())
+    }
+}
+
+impl CrowsTetris {
+    fn new(audio: Mixer) -> Self {
+        let (themes, theme_errors) = theme::load_themes();
         Self {
             state: GameState::StartScreen,
             score: 0,
             high_scores: load_high_scores(),
             new_high_score_name: String::new(),
+            name_cursor: (0, 0),
             is_paused: false,
             grid: [[0; GRID_WIDTH]; GRID_HEIGHT],
             active_block: None,
             last_update: Instant::now(),
             drop_speed: Duration::from_millis(125),
-            selected_difficulty: None,
+            language: Language::detect(),
+            background_behavior: BackgroundBehavior::Pause,
+            was_focused: true,
+            mode: GameMode::Marathon,
+            held_item: None,
+            slowed_until: None,
+            randomizer: Randomizer::Memoryless,
+            ruleset: Ruleset::Modern,
+            start_level: 0,
+            piece_generator: PieceGenerator::new(Randomizer::Memoryless),
+            piece_rng: StdRng::from_os_rng(),
+            game_rng: StdRng::from_os_rng(),
+            seed_text: String::new(),
+            run_seed: 0,
+            rng_audit: Vec::new(),
+            changing_rules: false,
+            controls: Controls::load(),
+            gamepads: Gamepads::new(None),
+            handling: Handling::default(),
+            auto_shift: AutoShift::default(),
+            soft_dropping: false,
+            handling_test: AutoShift::default(),
+            handling_test_column: 0,
+            one_switch: false,
+            one_switch_state: OneSwitch::default(),
+            scan_interval: Duration::from_millis(800),
+            touch_controls: false,
+            touch_gesture: None,
+            mouse_controls: false,
+            mouse_scroll: 0.0,
+            pointer_actions: Vec::new(),
+            input_queue: VecDeque::new(),
+            lines: 0,
+            pieces_placed: 0,
+            top_out_at: None,
+            level_up: None,
+            show_debug: false,
+            glow_renderer: None,
+            fancy_graphics: false,
+            ui_scale: 1.0,
+            window_size: DESIGN_WINDOW_SIZE,
+            window_position: None,
+            fullscreen: false,
+            frame_times: VecDeque::new(),
+            at_title: true,
+            title_pieces: Vec::new(),
+            title_updated: Instant::now(),
+            keys_pressed: 0,
+            attack: 0,
+            show_live_stats: true,
+            focus_mode: false,
+            show_input_display: false,
+            run_started: Instant::now(),
+            paused_time: Duration::ZERO,
+            paused_at: None,
+            countdown_until: None,
+            pause_selection: 0,
+            settings_return: GameState::StartScreen,
+            rebinding: None,
+            confirming: None,
+            rebind_swap: None,
+            new_control_profile_name: String::new(),
+            last_result: None,
+            board_text: String::new(),
+            board_text_error: None,
+            audio,
+            menu_music: MusicTrack::Roost,
+            announce_pieces: false,
+            gameplay_music: MusicTrack::Korobeiniki,
+            dig_rows: 10,
+            best_of: 3,
+            versus_match: None,
+            player_two_profile: None,
+            locked_at: [[None; GRID_WIDTH]; GRID_HEIGHT],
+            show_trajectory: false,
+            show_grid_lines: true,
+            level: 0,
+            landed_at: None,
+            section_times: Vec::new(),
+            splits: Vec::new(),
+            best_splits: splits::load_best_splits(),
+            stack_heights: Vec::new(),
+            piece_tally: PieceTally::default(),
+            section_started: Duration::ZERO,
+            profiles: profile::load_profiles(),
+            active_profile: 0,
+            new_profile_name: String::new(),
+            last_move_rotated: false,
+            combo: 0,
+            tetrises: 0,
+            t_spins: 0,
+            piece_presses: 0,
+            piece_soft_dropped: false,
+            finesse_faults: 0,
+            lifetime_stats: lifetime::load_lifetime_stats(),
+            stats_export: None,
+            combo_meter: 0.0,
+            combo_meter_moving: false,
+            mission: Mission::random(None, &mut rand::rng()),
+            missions_completed: 0,
+            daily_results: daily::load_daily_results(),
+            next_piece: None,
+            next_queue: VecDeque::new(),
+            held_piece: None,
+            hold_used: false,
+            practice_gravity: true,
+            snapshots: Vec::new(),
+            rivals: Vec::new(),
+            targeting: Targeting::Random,
+            incoming_garbage: 0,
+            online: None,
+            online_address: String::new(),
+            online_error: None,
+            line_clear: None,
+            effects: Vec::new(),
+            effects_updated: Instant::now(),
+            shake: None,
+            screen_shake: true,
+            themes,
+            theme: 0,
+            skin: None,
+            cell_style: CellStyle::Rounded,
+            skin_error: None,
+            sound_pack_error: None,
+            has_suspended_run: suspend::has_suspended_run(),
+            has_interrupted_run: autosave::has_interrupted_run(),
+            autosaved_at: None,
+            watched: hot_reload::WatchedFiles::new(),
+            unsaved_games: Vec::new(),
+            file_errors: theme_errors.into_iter().map(|error| file_errors::FileError::new(error, None)).collect(),
+            replay_log: None,
+            replay_board: String::new(),
+            last_replay: None,
+            playback: None,
+            replay_error: None,
+            background_textures: HashMap::new(),
         }
     }
 }
 
 impl CrowsTetris {
+    fn tr(&self, text: &'static str) -> &'static str {
+        self.language.tr(text)
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.themes[self.theme]
+    }
+
+    fn action_pressed(&self, ctx: &egui::Context, action: Action) -> bool {
+        ctx.input(|i| self.controls.pressed(i, action)) || self.pointer_actions.contains(&action)
+    }
+
     fn reset_game(&mut self) {
         self.state = GameState::Playing;
         self.score = 0;
         self.is_paused = false;
         self.grid = [[0; GRID_WIDTH]; GRID_HEIGHT];
+        self.locked_at = [[None; GRID_WIDTH]; GRID_HEIGHT];
+        self.next_piece = None;
+        self.next_queue.clear();
+        self.piece_tally = PieceTally::default();
+        self.input_queue.clear();
+        self.held_piece = None;
+        self.hold_used = false;
+        self.line_clear = None;
+        self.effects.clear();
+        self.shake = None;
+        self.top_out_at = None;
+        self.level_up = None;
+        let (randomizer, seed) = match self.mode {
+            GameMode::Daily => {
+                // Same pieces for everyone: a fixed randomizer seeded from the date
+                self.start_daily_attempt();
+                (Randomizer::SevenBag, daily::seed(daily::today()))
+            }
+            // Both players get the pieces from the seed the host sent
+            GameMode::Online => (Randomizer::SevenBag, self.online.as_ref().map_or(0, |session| session.seed)),
+            _ => (self.randomizer, seed_from_text(&self.seed_text).unwrap_or_else(|| rand::rng().random())),
+        };
+        self.piece_generator = PieceGenerator::new(randomizer);
+        self.seed_run(seed);
         self.active_block = Some(self.generate_random_block());
+        self.last_update = Instant::now();
+        self.held_item = None;
+        self.slowed_until = None;
+        self.changing_rules = false;
+        self.lines = 0;
+        self.pieces_placed = 0;
+        self.keys_pressed = 0;
+        self.attack = 0;
+        self.run_started = Instant::now();
+        self.paused_time = Duration::ZERO;
+        self.paused_at = None;
+        self.start_countdown();
+        self.last_result = None;
+        self.level = if self.ruleset == Ruleset::Classic && self.mode != GameMode::Master {
+            self.start_level
+        } else {
+            0
+        };
+        self.landed_at = None;
+        self.section_times.clear();
+        self.splits.clear();
+        self.stack_heights.clear();
+        self.section_started = Duration::ZERO;
+        self.last_move_rotated = false;
+        self.combo = 0;
+        self.tetrises = 0;
+        self.t_spins = 0;
+        self.finesse_faults = 0;
+        self.reset_finesse();
+        self.combo_meter = 0.0;
+        self.mission = Mission::random(None, &mut self.game_rng);
+        self.missions_completed = 0;
+        self.practice_gravity = true;
+        self.snapshots.clear();
+        self.start_rivals();
+        self.start_match();
+        self.playback = None;
+        self.replay_log = Some(Vec::new());
+
+        if self.mode == GameMode::Dig {
+            self.add_garbage_rows(self.dig_rows);
+        }
+    }
+
+    // Both of the run's random number generators come from its one seed; the other one's has
+    // its bits flipped so it doesn't draw the same numbers as the pieces
+    fn seed_run(&mut self, seed: u64) {
+        self.run_seed = seed;
+        self.piece_rng = StdRng::seed_from_u64(seed);
+        self.game_rng = StdRng::seed_from_u64(!seed);
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        if paused == self.is_paused {
+            return;
+        }
+
+        self.is_paused = paused;
+        if paused {
+            self.pause_selection = 0;
+            self.countdown_until = None;
+            self.paused_at.get_or_insert_with(Instant::now);
+        } else {
+            self.start_countdown();
+        }
+    }
+
+    fn start_countdown(&mut self) {
+        let now = Instant::now();
+        self.paused_at.get_or_insert(now);
+        self.countdown_until = Some(now + COUNTDOWN);
+    }
+
+    fn counting_down(&self) -> bool {
+        self.countdown_until.is_some()
+    }
+
+    // Starts the clock once the countdown is over
+    fn update_countdown(&mut self) {
+        if self.countdown_until.is_some_and(|until| Instant::now() >= until) {
+            self.countdown_until = None;
+            if let Some(paused_at) = self.paused_at.take() {
+                self.paused_time += paused_at.elapsed();
+            }
+            self.last_update = Instant::now();
+        }
     }
 
-    fn generate_random_block(&self) -> Block {
-        let block_type = match rand::rng().random_range(0..7) {
-            0 => BlockType::I,
-            1 => BlockType::O,
-            2 => BlockType::T,
-            3 => BlockType::S,
-            4 => BlockType::Z,
-            5 => BlockType::J,
-            _ => BlockType::L,
+    // Time spent actually playing this run, not counting pauses
+    fn play_time(&self) -> Duration {
+        if let Some(playback) = &self.playback {
+            return playback.position();
+        }
+        let paused = self.paused_time + self.paused_at.map_or(Duration::ZERO, |at| at.elapsed());
+        self.run_started.elapsed().saturating_sub(paused)
+    }
+
+    // Whether the run met its mode's finishing condition rather than topping out or being abandoned
+    fn goal_reached(&self) -> bool {
+        match self.mode {
+            GameMode::Ultra => self.play_time() >= ULTRA_DURATION,
+            GameMode::Sprint => self.lines >= SPRINT_LINES,
+            GameMode::Dig => self.garbage_rows_left() == 0,
+            GameMode::Master => self.level >= MASTER_MAX_LEVEL,
+            GameMode::TimeAttack => self.lines >= TIME_ATTACK_LINES,
+            GameMode::Battle | GameMode::Royale => self.rivals_defeated(),
+            GameMode::Versus | GameMode::Online => self.rivals_defeated() || self.won_cheese_race(),
+            _ => false,
+        }
+    }
+
+    // Whether the selected rules allow starting a run right now
+    fn can_start(&self) -> bool {
+        match self.mode {
+            GameMode::Daily => !self.daily_attempted(),
+            // Online matches are started by the host from the lobby
+            GameMode::Online => false,
+            _ => true,
+        }
+    }
+
+    fn end_run(&mut self) {
+        // The run in a replay isn't one of the player's; the viewer just stops at its end
+        if let Some(playback) = &mut self.playback {
+            playback.pause();
+            return;
+        }
+        self.state = GameState::GameOver;
+        if self.new_high_score_name.is_empty() {
+            self.new_high_score_name = self.active_profile().name.clone();
+        }
+        self.audio.play(SoundEvent::GameOver);
+        let previous_best_splits = self.best_splits(self.mode).to_vec();
+        let new_best_splits = self.save_splits_if_best();
+        self.last_result = Some(RunResult {
+            mode: self.mode,
+            completed: self.goal_reached(),
+            score: self.score,
+            lines: self.lines,
+            pieces: self.pieces_placed,
+            duration: self.play_time(),
+            level: self.level,
+            grade: self.master_grade(),
+            section_times: self.section_times.clone(),
+            missions_completed: self.missions_completed,
+            placement: (self.mode == GameMode::Royale).then(|| self.placement()),
+            seed: self.run_seed,
+            splits: self.splits.clone(),
+            stack_heights: self.stack_heights.clone(),
+            previous_best_splits,
+            new_best_splits,
+            finesse_faults: self.finesse_faults,
+        });
+        if self.mode == GameMode::Daily {
+            self.record_daily_result();
+        }
+        let completed = self.last_result.as_ref().is_some_and(|result| result.completed);
+        if self.mode == GameMode::Online && !self.rivals_defeated() {
+            self.send_online(if completed { Message::DugOut } else { Message::Lost });
+        }
+        self.record_round(completed);
+        self.save_run_replay();
+        self.record_lifetime_stats();
+        self.record_game_history();
+    }
+
+    fn current_drop_speed(&self) -> Duration {
+        let base = match (self.mode, self.ruleset) {
+            (GameMode::Master, _) => master::drop_interval(self.level),
+            (_, Ruleset::Classic) => classic::drop_interval(self.level),
+            _ => self.drop_speed,
         };
 
-        let shape = match block_type {
-            BlockType::I => vec![vec![1, 1, 1, 1]],
-            BlockType::O => vec![vec![1, 1], vec![1, 1]],
-            BlockType::T => vec![vec![0, 1, 0], vec![1, 1, 1]],
-            BlockType::S => vec![vec![0, 1, 1], vec![1, 1, 0]],
-            BlockType::Z => vec![vec![1, 1, 0], vec![0, 1, 1]],
-            BlockType::J => vec![vec![1, 0, 0], vec![1, 1, 1]],
-            BlockType::L => vec![vec![0, 0, 1], vec![1, 1, 1]],
+        match self.slowed_until {
+            Some(until) if Instant::now() < until => base * 2,
+            _ => base,
+        }
+    }
+
+    // Time between gravity steps, shortened while soft drop is held
+    fn gravity_interval(&self) -> Duration {
+        self.current_drop_speed() / self.soft_drop_factor()
+    }
+
+    // How long a piece may rest on the stack before it locks
+    fn lock_delay(&self) -> Duration {
+        match self.mode {
+            GameMode::Master => MASTER_LOCK_DELAY,
+            _ => self.current_drop_speed(),
+        }
+    }
+
+    // How far the active piece has sunk toward the row below since the last gravity
+    // step, from 0 to 1, so it can be drawn falling smoothly instead of a cell at a time
+    fn fall_progress(&self) -> f32 {
+        if self.state != GameState::Playing
+            || self.is_paused
+            || self.counting_down()
+            || (self.mode == GameMode::Practice && !self.practice_gravity)
+            || self.one_switch_scanning()
+        {
+            return 0.0;
+        }
+        let Some(block) = &self.active_block else {
+            return 0.0;
         };
+        if self.check_collision_with_position((block.position.0, block.position.1 + 1)) {
+            return 0.0;
+        }
+        (self.last_update.elapsed().as_secs_f32() / self.gravity_interval().as_secs_f32()).min(1.0)
+    }
 
-        Block {
-            block_type,
-            position: (GRID_WIDTH as i32 / 2 - shape[0].len() as i32 / 2, 0), // Starts at the top center
-            shape,
+    fn apply_gravity(&mut self) {
+        if (self.mode == GameMode::Ultra && self.play_time() >= ULTRA_DURATION) || self.checkpoint_missed() {
+            self.end_run();
+        }
+
+        // The next piece waits for the countdown and until the cleared rows have collapsed
+        if self.counting_down() || self.clearing() {
+            self.last_update = Instant::now();
+            return;
+        }
+
+        // Scanning takes a while, so in one-switch mode pieces only come down when dropped.
+        // A replay's gravity and locks come from its log.
+        if (self.mode == GameMode::Practice && !self.practice_gravity) || self.one_switch_scanning() || self.watching_replay() {
+            return;
+        }
+
+        // Catch up on steps missed between sparse frames so the game keeps its pace
+        // even when redraws are throttled in the background.
+        while self.state == GameState::Playing && self.last_update.elapsed() >= self.gravity_interval() {
+            self.last_update += self.gravity_interval();
+            self.record_replay(ReplayEvent::Gravity);
+            self.move_block_down();
+        }
+
+        if self.state == GameState::Playing && self.landed_at.is_some_and(|at| at.elapsed() >= self.lock_delay()) {
+            // The piece may have been slid off the ledge since it landed
+            let grounded = self.active_block.as_ref().is_some_and(|block| {
+                self.check_collision_with_position((block.position.0, block.position.1 + 1))
+            });
+
+            if grounded {
+                self.record_replay(ReplayEvent::Lock);
+                self.lock_and_spawn();
+            } else {
+                self.landed_at = None;
+            }
+        }
+    }
+
+    fn schedule_repaint(&self, ctx: &egui::Context, focused: bool) {
+        if self.state != GameState::Playing || self.is_paused {
+            return;
         }
 
+        if self.watching_replay() {
+            ctx.request_repaint();
+        } else if !focused && self.background_behavior == BackgroundBehavior::Reduced {
+            ctx.request_repaint_after(BACKGROUND_REPAINT_INTERVAL);
+        } else if self.effects_running() || self.counting_down() || self.fall_progress() > 0.0 {
+            ctx.request_repaint();
+        } else {
+            let mut next_event = self
+                .gravity_interval()
+                .saturating_sub(self.last_update.elapsed())
+                .min(HUD_REPAINT_INTERVAL);
+            if let Some(landed_at) = self.landed_at {
+                next_event = next_event.min(self.lock_delay().saturating_sub(landed_at.elapsed()));
+            }
+            ctx.request_repaint_after(next_event);
+        }
+    }
+
+    fn generate_random_block(&mut self) -> Block {
+        // In practice mode the player may have picked the next piece
+        let block_type = match self.next_piece.take() {
+            Some(block_type) => block_type,
+            None => {
+                while self.next_queue.len() <= hold::NEXT_QUEUE_LEN {
+                    self.next_queue.push_back(self.piece_generator.next(&mut self.piece_rng));
+                }
+                self.next_queue.pop_front().unwrap()
+            }
+        };
+        self.count_dealt(block_type);
+        if self.announce_pieces {
+            self.audio.announce(block_type, self.next_queue.front().copied());
+        }
+        Block::new(block_type)
     }
 
     fn move_block_down(&mut self) {
         if let Some(block) = self.active_block.as_ref() {
-            let position = block.position;
-            let collided = self.check_collision_with_position(position);
+            let below = (block.position.0, block.position.1 + 1);
+            let collided = self.check_collision_with_position(below);
 
             if !collided {
-                let mut blck = self.active_block.as_mut().unwrap();
+                let blck = self.active_block.as_mut().unwrap();
                 blck.position.1 += 1;
-            } else {
-                self.lock_block();  
-                self.clear_lines();
-                self.active_block = Some(self.generate_random_block());
+                self.landed_at = None;
+                self.last_move_rotated = false;
+            } else if self.landed_at.is_none() {
+                self.landed_at = Some(Instant::now());
+            }
+        }
+    }
 
-                let new_block = self.generate_random_block();
-                if self.check_collision_with_position(new_block.position) {
-                    self.state = GameState::GameOver;
-                } else {
-                    self.active_block = Some(new_block);
-                }
+    // Drops the piece straight to the floor and locks it at once
+    fn hard_drop(&mut self) {
+        self.record_replay(ReplayEvent::Input(Action::HardDrop));
+        if !self.hard_drop_allowed() {
+            return;
+        }
+        let Some(mut block) = self.active_block.clone() else {
+            return;
+        };
+        let from = block.position.1;
+        while !self.collides(&block.shape, (block.position.0, block.position.1 + 1)) {
+            block.position.1 += 1;
+        }
+        if block.position.1 != from {
+            self.last_move_rotated = false;
+        }
+        self.add_drop_trail(&block, from);
+        self.gamepads.rumble(gamepad::HARD_DROP_RUMBLE);
+        self.active_block = Some(block);
+        self.judge_finesse();
+        self.lock_and_spawn();
+    }
+
+    fn lock_and_spawn(&mut self) {
+        self.landed_at = None;
+        self.hold_used = false;
+        self.reset_finesse();
+        let t_spin = self.active_block.as_ref().is_some_and(|block| self.is_t_spin(block));
+        self.last_move_rotated = false;
+        let level_before = self.level;
+        self.lock_block();
+        self.pieces_placed += 1;
+        if self.mode == GameMode::Zen {
+            self.score += ZEN_PIECE_SCORE;
+        }
+        let mut lines_cleared = self.clear_lines();
+        if lines_cleared > 0 && self.mode == GameMode::Sticky {
+            lines_cleared += self.resolve_sticky_chain();
+        }
+        self.lines += lines_cleared as u32;
+        self.record_splits();
+        self.record_stack_height();
+        if self.ruleset == Ruleset::Classic && self.mode != GameMode::Master {
+            self.score_classic_clear(lines_cleared);
+        }
+        let clear = self.record_clear(lines_cleared, t_spin);
+        self.count_attack(lines_cleared);
+        self.track_mission(&clear);
+        self.shake_for_clear(&clear);
+        self.gamepads.rumble(gamepad::clear_rumble(lines_cleared));
+        self.audio.play(match lines_cleared {
+            0 => SoundEvent::Lock,
+            1..=3 => SoundEvent::LineClear,
+            _ => SoundEvent::Tetris,
+        });
+        self.maybe_grant_item(lines_cleared);
+
+        if self.mode == GameMode::Master {
+            self.advance_master_level(lines_cleared);
+        }
+        self.announce_level_up(level_before);
+
+        self.exchange_garbage(lines_cleared);
+
+        if matches!(
+            self.mode,
+            GameMode::Sprint | GameMode::Dig | GameMode::Master | GameMode::TimeAttack | GameMode::Versus | GameMode::Online
+        ) && self.goal_reached()
+        {
+            self.end_run();
+            return;
+        }
+
+        let new_block = self.generate_random_block();
+        if self.mode == GameMode::Zen {
+            // Zen never ends: trim the stack from the bottom until the new piece fits
+            let mut trimmed = 0;
+            while trimmed < GRID_HEIGHT && self.collides(&new_block.shape, new_block.position) {
+                self.remove_bottom_rows(1);
+                trimmed += 1;
             }
         }
+
+        if self.collides(&new_block.shape, new_block.position) {
+            self.top_out_at = Some(Instant::now());
+            self.end_run();
+        } else {
+            self.active_block = Some(new_block);
+        }
     }
 
     fn check_collision_with_position(&self, position: (i32, i32)) -> bool {
+        self.active_block
+            .as_ref()
+            .is_some_and(|block| self.collides(&block.shape, position))
+    }
+
+    fn collides(&self, shape: &[Vec<u8>], position: (i32, i32)) -> bool {
         let (x, y) = position;
 
-        if let Some(block) = &self.active_block {
-            for (dy, row) in block.shape.iter().enumerate() {
-                for (dx, cell) in row.iter().enumerate() {
-                    if *cell != 0 {
-                        let grid_x = x + dx as i32;
-                        let grid_y = y + dy as i32;
+        for (dy, row) in shape.iter().enumerate() {
+            for (dx, cell) in row.iter().enumerate() {
+                if *cell != 0 {
+                    let grid_x = x + dx as i32;
+                    let grid_y = y + dy as i32;
 
-                        if grid_x < 0 || grid_x >= (GRID_WIDTH as i32) - 1 || grid_y >= (GRID_HEIGHT as i32) - 1 {
-                            return true;
-                        }
+                    if grid_x < 0 || grid_x >= GRID_WIDTH as i32 || grid_y >= GRID_HEIGHT as i32 {
+                        return true;
+                    }
 
-                        if self.grid[grid_y as usize][grid_x as usize] != 0 {
-                            return true;
-                        }
+                    // Cells poking out above the top of the board can't hit anything
+                    if grid_y >= 0 && self.grid[grid_y as usize][grid_x as usize] != 0 {
+                        return true;
                     }
                 }
             }
@@ -186,11 +1235,12 @@ impl CrowsTetris {
         if let Some(block) = &self.active_block {
             for (dy, row) in block.shape.iter().enumerate() {
                 for (dx, &cell) in row.iter().enumerate() {
-                    if cell == 1 {
+                    if cell != 0 {
                         let x = block.position.0 + dx as i32;
                         let y = block.position.1 + dy as i32;
                         if y >= 0 && x >= 0 && x < GRID_WIDTH as i32 && y < GRID_HEIGHT as i32 {
-                            self.grid[y as usize][x as usize] = cell;
+                            self.grid[y as usize][x as usize] = block.block_type.cell();
+                            self.locked_at[y as usize][x as usize] = Some(Instant::now());
                         }
                     }
                 }
@@ -198,229 +1248,836 @@ impl CrowsTetris {
         }
     }
 
-    fn clear_lines(&mut self) {
+    fn remove_bottom_rows(&mut self, count: usize) {
+        for _ in 0..count {
+            self.grid.copy_within(0..GRID_HEIGHT - 1, 1);
+            self.grid[0] = [0; GRID_WIDTH];
+            self.locked_at.copy_within(0..GRID_HEIGHT - 1, 1);
+            self.locked_at[0] = [None; GRID_WIDTH];
+        }
+    }
+
+    fn clear_lines(&mut self) -> usize {
         let mut new_grid = [[0; GRID_WIDTH]; GRID_HEIGHT];
+        let mut new_locked_at = [[None; GRID_WIDTH]; GRID_HEIGHT];
         let mut new_row = GRID_HEIGHT - 1;
+        let mut cleared_rows = vec![];
 
         for y in (0..GRID_HEIGHT).rev() {
             // Copy non-full rows downward
-            if !self.grid[y].iter().all(|&cell| cell == 1) {
+            if !self.grid[y].iter().all(|&cell| cell != 0) {
                 new_grid[new_row] = self.grid[y];
-                if new_row > 0 {
-                    new_row -= 1;
-                }
+                new_locked_at[new_row] = self.locked_at[y];
+                new_row = new_row.saturating_sub(1);
             } else {
-                self.score += 100;
+                // The Classic ruleset scores whole clears instead, once they're counted
+                if self.ruleset != Ruleset::Classic {
+                    self.score += 100;
+                }
+                cleared_rows.push(y);
             }
         }
 
+        let lines_cleared = cleared_rows.len();
+        if lines_cleared > 0 {
+            self.emit_clear_particles(&cleared_rows);
+            self.line_clear = Some(LineClearAnimation::new(self.grid, self.locked_at, cleared_rows));
+        }
         self.grid = new_grid;
+        self.locked_at = new_locked_at;
+        lines_cleared
     }
 
-    fn render_grid(&self, ui: &mut egui::Ui) {
-        let mut grid_with_block = self.grid.clone();
+    // Returns where the board was drawn
+    fn render_grid(&self, ui: &mut egui::Ui) -> egui::Rect {
+        // While rows are clearing the board from before the clear is shown, without the next piece
+        let animation = self
+            .line_clear
+            .as_ref()
+            .filter(|animation| self.state == GameState::Playing && animation.is_running());
+        let (grid, locked_at) = match animation {
+            Some(animation) => (animation.grid, &animation.locked_at),
+            None => (self.grid, &self.locked_at),
+        };
+        let active_block = self.active_block.as_ref().filter(|_| animation.is_none());
 
-        if let Some(block) = &self.active_block {
+        let mut grid_with_block = grid;
+        let mut visibility = [[1.0_f32; GRID_WIDTH]; GRID_HEIGHT];
+        // Cells of the falling piece, drawn part of the way toward the next row
+        let mut falling = [[false; GRID_WIDTH]; GRID_HEIGHT];
+        let fall = self.fall_progress();
+
+        if self.mode == GameMode::Invisible && self.state == GameState::Playing {
+            for (y, row) in locked_at.iter().enumerate() {
+                for (x, locked_at) in row.iter().enumerate() {
+                    if let Some(locked_at) = locked_at {
+                        visibility[y][x] = invisible_cell_visibility(locked_at.elapsed());
+                    }
+                }
+            }
+        }
+
+        if let Some(block) = active_block {
             for (dy, row) in block.shape.iter().enumerate() {
                 for (dx, &cell) in row.iter().enumerate() {
-                    if cell == 1 {
+                    if cell != 0 {
                         let x = block.position.0 + dx as i32;
                         let y = block.position.1 + dy as i32;
                         if x >= 0 && x < GRID_WIDTH as i32 && y >= 0 && y < GRID_HEIGHT as i32 {
-                            grid_with_block[y as usize][x as usize] = 1;
+                            grid_with_block[y as usize][x as usize] = block.block_type.cell();
+                            visibility[y as usize][x as usize] = 1.0;
+                            falling[y as usize][x as usize] = true;
                         }
                     }
                 }
             }
         }
-        for row in &grid_with_block {
-            let row_str: String = row.iter().map(|&cell| if cell == 1 { "■" } else { "0" }).collect();
-            //println!("{}", row_str);
-            ui.label(row_str);
+        // Beginner aid: tint the columns under the falling piece all the way to the floor
+        let mut in_trajectory = [[false; GRID_WIDTH]; GRID_HEIGHT];
+        if self.show_trajectory && self.state == GameState::Playing {
+            if let Some(block) = active_block {
+                let below_piece = (block.position.1 + block.shape.len() as i32).max(0) as usize;
+                for dx in 0..block.shape[0].len() {
+                    let x = block.position.0 + dx as i32;
+                    if x < 0 || x >= GRID_WIDTH as i32 {
+                        continue;
+                    }
+                    for row in in_trajectory.iter_mut().skip(below_piece) {
+                        row[x as usize] = true;
+                    }
+                }
+            }
         }
 
-        if let Some(block) = &self.active_block {
-            ui.label(format!("Active Block at {:?}", block.position));
+        // Square cells as large as the space left allows
+        let meter_width = if self.garbage_meter_shown() { GARBAGE_METER_WIDTH } else { 0.0 };
+        let available = ui.available_size();
+        let cell_size = ((available.x - meter_width) / GRID_WIDTH as f32)
+            .min(available.y / GRID_HEIGHT as f32)
+            .max(MIN_CELL_SIZE);
+        let board_size = egui::vec2(GRID_WIDTH as f32, GRID_HEIGHT as f32) * cell_size;
+        let (rect, _) = ui.allocate_exact_size(board_size + egui::vec2(meter_width, 0.0), egui::Sense::hover());
+        let rect = rect.translate(self.shake_offset());
+        // Incoming garbage queues up just left of the board
+        let (meter, rect) = rect.split_left_right_at_x(rect.min.x + meter_width);
+        if meter_width > 0.0 {
+            self.draw_garbage_meter(ui.painter(), meter, cell_size);
+        }
+        let painter = ui.painter_at(rect);
+        let theme = self.theme();
+        painter.rect_filled(rect, 2.0, theme.board);
+        if self.show_grid_lines {
+            for x in 1..GRID_WIDTH {
+                let x = rect.min.x + x as f32 * cell_size;
+                painter.vline(x, rect.y_range(), egui::Stroke::new(1.0, theme.grid_lines));
+            }
+            for y in 1..GRID_HEIGHT {
+                let y = rect.min.y + y as f32 * cell_size;
+                painter.hline(rect.x_range(), y, egui::Stroke::new(1.0, theme.grid_lines));
+            }
         }
-    }
 
-    fn rotate_block(&mut self) {
-        if let Some(block) = self.active_block.as_ref() {
-            let original_shape = block.shape.clone();
-            let rotated_shape: Vec<Vec<u8>> = (0..block.shape[0].len())
-                .map(|i| block.shape.iter().rev().map(|row| row[i]).collect())
-                .collect();
+        let (row_offsets, cleared_rows) = animation.map_or(
+            ([0.0; GRID_HEIGHT], [None; GRID_HEIGHT]),
+            LineClearAnimation::row_layout,
+        );
+        let trajectory_color = egui::Color32::from_rgb(255, 230, 150).gamma_multiply(0.25);
+        let mut glow = Vec::new();
+        for (y, row) in grid_with_block.iter().enumerate() {
+            let top = rect.min.y + (y as f32 + row_offsets[y]) * cell_size;
+            if let Some(remaining) = cleared_rows[y] {
+                // A cleared row flashes white, then squashes flat as the stack drops onto it
+                let height = cell_size * remaining;
+                let row_rect = egui::Rect::from_min_size(
+                    egui::pos2(rect.min.x, top + (cell_size - height) / 2.0),
+                    egui::vec2(rect.width(), height),
+                );
+                painter.rect_filled(row_rect, 0.0, egui::Color32::WHITE.gamma_multiply(remaining));
+                for x in 0..GRID_WIDTH {
+                    glow.push(GlowSprite {
+                        center: egui::pos2(rect.min.x + (x as f32 + 0.5) * cell_size, row_rect.center().y),
+                        radius: cell_size * GLOW_RADIUS,
+                        color: egui::Color32::WHITE.gamma_multiply(0.25 * remaining),
+                    });
+                }
+                continue;
+            }
 
+            for (x, &cell) in row.iter().enumerate() {
+                let sunk = if falling[y][x] { fall * cell_size } else { 0.0 };
+                let min = egui::pos2(rect.min.x + x as f32 * cell_size, top + sunk);
+                let cell_rect = egui::Rect::from_min_size(min, egui::Vec2::splat(cell_size));
+                if falling[y][x] {
+                    glow.push(GlowSprite {
+                        center: cell_rect.center(),
+                        radius: cell_size * GLOW_RADIUS,
+                        color: theme.cell_color(cell).unwrap_or_default().gamma_multiply(0.3),
+                    });
+                }
+                match theme.cell_color(cell) {
+                    Some(color) if visibility[y][x] > 0.0 => match &self.skin {
+                        Some(skin) => skin.paint_cell(&painter, cell_rect, cell, visibility[y][x]),
+                        None => self.cell_style.paint(&painter, cell_rect, color.gamma_multiply(visibility[y][x])),
+                    },
+                    _ if in_trajectory[y][x] => {
+                        painter.rect_filled(cell_rect.shrink(cell_size * 0.06), cell_size * 0.2, trajectory_color);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.paint_glow(&painter, rect, &glow);
+        self.draw_effects(&painter, rect, cell_size);
+        self.draw_danger(&painter, rect, cell_size, ui.input(|i| i.time));
+        self.draw_top_out(&painter, rect, cell_size);
+        self.draw_level_up(&painter, rect);
+        if let Some(until) = self.countdown_until.filter(|_| self.state == GameState::Playing && !self.is_paused) {
+            let seconds_left = until.saturating_duration_since(Instant::now()).as_secs() + 1;
+            painter.rect_filled(rect, 2.0, egui::Color32::from_black_alpha(100));
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                seconds_left.to_string(),
+                egui::FontId::proportional(rect.height() * 0.5),
+                egui::Color32::WHITE,
+            );
+        }
+        if self.show_grid_lines {
+            painter.rect_stroke(rect.shrink(1.0), 2.0, egui::Stroke::new(2.0, theme.border));
+        }
+        rect
+    }
 
-            if !self.check_collision_with_position(block.position) {
-                let mut blck = self.active_block.as_mut().unwrap();
-                blck.shape = rotated_shape;
+    fn rotate_block(&mut self, clockwise: bool) {
+        self.record_replay(ReplayEvent::Input(if clockwise { Action::Rotate } else { Action::RotateCounterclockwise }));
+        if let Some(block) = self.active_block.as_ref() {
+            let rotated = turned(self.ruleset, block, clockwise);
+            if !self.collides(&rotated.shape, rotated.position) {
+                self.active_block = Some(rotated);
+                self.last_move_rotated = true;
+                self.audio.play(SoundEvent::Rotate);
             }
         }
     }
 }
 
 impl eframe::App for CrowsTetris {
-    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        scale_to_window(ctx, self.ui_scale);
+        let fullscreen = ctx.input(|i| i.viewport().fullscreen).unwrap_or(false);
+        // Remembered to open at next time, unless it's the whole screen
+        self.fullscreen = fullscreen;
+        if !fullscreen {
+            self.window_size = ctx.screen_rect().size() * ctx.zoom_factor();
+            if let Some(outer) = ctx.input(|i| i.viewport().outer_rect) {
+                self.window_position = Some(outer.min * ctx.zoom_factor());
+            }
+        }
+        if ctx.input(|i| i.key_pressed(egui::Key::F11)) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(!fullscreen));
+        }
+        if fullscreen {
+            letterbox(ctx);
+        }
+
+        let focused = ctx.input(|i| i.viewport().focused).unwrap_or(true);
+        if self.was_focused && !focused
+            && self.state == GameState::Playing
+            && self.background_behavior == BackgroundBehavior::Pause
+        {
+            self.set_paused(true);
+        }
+        self.was_focused = focused;
+
+        if ctx.input(|i| i.any_touches()) {
+            self.touch_controls = true;
+        }
+        touch::apply_touch_style(ctx, self.touch_controls);
+
+        self.poll_online();
+        self.hot_reload(ctx);
+        self.update_debug_overlay(ctx);
+
+        self.render_confirmation(ctx);
+        self.report_control_save_errors();
+        self.render_file_errors(ctx);
         match self.state {
             GameState::StartScreen => self.render_start_screen(ctx),
             GameState::Playing => self.render_gameplay(ctx),
             GameState::GameOver => self.render_game_over(ctx),
+            GameState::RngAudit => self.render_rng_audit(ctx),
+            GameState::Stats => self.render_stats_screen(ctx),
+            GameState::Settings => self.render_settings_screen(ctx),
         }
 
-        if ctx.input(|i| i.key_pressed(egui::Key::Q)) {
-            let ctx = ctx.clone();
-            std::thread::spawn(move || {
-                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-            });
+        self.autosave();
+        self.schedule_repaint(ctx, focused);
+        self.audio.set_music(self.music_cue());
+        self.audio.tension = self.music_tension();
+        self.audio.update();
+
+        // Not while typing a name or picking a new key, where the key means something else
+        let quit_allowed = self.confirming.is_none() && self.rebinding.is_none() && !ctx.wants_keyboard_input();
+        if quit_allowed && self.action_pressed(ctx, Action::Quit) {
+            self.ask(ctx, Confirm::Quit);
+        }
+    }
+
+    fn raw_input_hook(&mut self, ctx: &egui::Context, raw_input: &mut egui::RawInput) {
+        self.poll_gamepads(ctx, raw_input);
+    }
+
+    fn on_exit(&mut self, gl: Option<&eframe::glow::Context>) {
+        self.save_settings();
+        if let (Some(renderer), Some(gl)) = (self.glow_renderer.take(), gl) {
+            renderer.destroy(gl);
         }
     }
 }
 
 impl CrowsTetris {
     fn render_start_screen(&mut self, ctx: &egui::Context) {
+        if self.at_title {
+            self.render_title_screen(ctx);
+            return;
+        }
+        if ctx.input(|i| i.key_pressed(egui::Key::F12)) {
+            self.rng_audit = rng_audit::run_audit();
+            self.state = GameState::RngAudit;
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.vertical_centered(|ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| ui.vertical_centered(|ui| {
                 ui.heading("Crow's Tetris");
+                profile::draw_crow(ui, self.active_profile().avatar, 64.0);
+                ui.label(&self.active_profile().name);
                 ui.add_space(10.0);
 
-                if ui.button("Start Game").clicked() {
+                self.render_rule_pickers(ui);
+
+                self.render_interrupted_run(ui);
+                if self.has_suspended_run && ui.button(self.tr("Continue")).clicked() {
+                    self.continue_suspended_run();
+                }
+                if ui.add_enabled(self.can_start(), egui::Button::new(self.tr("Start Game"))).clicked() {
                     self.reset_game();
                 }
 
+                egui::CollapsingHeader::new(self.tr("Load board")).show(ui, |ui| {
+                    ui.label(self.tr("Paste a board, one row per line (I O T S Z J L or . for empty):"));
+                    ui.add(egui::TextEdit::multiline(&mut self.board_text)
+                        .code_editor()
+                        .desired_rows(6));
+
+                    if let Some(error) = &self.board_text_error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+
+                    if ui.add_enabled(self.can_start(), egui::Button::new(self.tr("Start with this board"))).clicked() {
+                        match board_text::board_from_ascii(&self.board_text) {
+                            Ok(grid) => {
+                                self.board_text_error = None;
+                                self.reset_game();
+                                self.grid = grid;
+                            }
+                            Err(error) => self.board_text_error = Some(error),
+                        }
+                    }
+                });
+
+                if self.mode == GameMode::Daily {
+                    ui.add_space(30.0);
+                    self.render_daily_leaderboard(ui);
+                }
+
                 ui.add_space(30.0);
-                ui.heading("High Scores:");
-                for (i, (name, score)) in self.high_scores.iter().take(10).enumerate() {
-                    ui.label(format!("{}. {} - {}", i + 1, name, score));
+                self.render_high_scores(ui, self.mode);
+
+                ui.add_space(30.0);
+                self.render_replay_list(ui);
+
+                egui::CollapsingHeader::new(self.tr("Profile")).show(ui, |ui| {
+                    self.render_profile_editor(ui);
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button(self.tr("Stats")).clicked() {
+                        self.state = GameState::Stats;
+                    }
+                    if ui.button(self.tr("Settings")).clicked() {
+                        self.open_settings();
+                    }
+                });
+            }));
+        });
+    }
+
+    pub(crate) fn render_skin_picker(&mut self, ui: &mut egui::Ui) {
+        let flat = self.tr("Flat colors");
+        let mut picked = None;
+        egui::ComboBox::from_label(self.tr("Skin"))
+            .selected_text(self.skin.as_ref().map_or(flat, |skin| skin.name.as_str()))
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(self.skin.is_none(), flat).clicked() {
+                    picked = Some(None);
+                }
+                for name in skin::skin_names() {
+                    let selected = self.skin.as_ref().is_some_and(|skin| skin.name == name);
+                    if ui.selectable_label(selected, &name).clicked() {
+                        picked = Some(Some(name));
+                    }
                 }
             });
-        });
+
+        // Skins are loaded when picked, so a changed file can be reloaded by picking it again
+        match picked {
+            Some(None) => {
+                self.skin = None;
+                self.skin_error = None;
+            }
+            Some(Some(name)) => match skin::load_skin(ui.ctx(), &name) {
+                Ok(skin) => {
+                    self.skin = Some(skin);
+                    self.skin_error = None;
+                }
+                Err(error) => {
+                    self.skin = None;
+                    self.skin_error = Some(error);
+                }
+            },
+            None => {}
+        }
+        if let Some(error) = &self.skin_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
     }
 
-    fn render_gameplay(&mut self, ctx: &egui::Context) {
-        egui::CentralPanel::default()
-            .frame(egui::Frame::default().fill(egui::Color32::DARK_RED))
-            .show(ctx, |ui| {
-                let score_label = egui::RichText::new(format!("Score: {}", self.score))
-                    .size(21.0)
-                    .strong();
+    fn render_rule_pickers(&mut self, ui: &mut egui::Ui) {
+        ui.label(self.tr("Mode"));
+        ui.horizontal_wrapped(|ui| {
+            for mode in GameMode::ALL {
+                ui.selectable_value(&mut self.mode, mode, self.language.tr(mode.label()));
+            }
+        });
+        ui.label(egui::RichText::new(self.tr(self.mode.description())).italics());
+        ui.add_space(5.0);
 
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
-                    ui.label(score_label);
-                    ui.add_space(20.0);
-                });
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+        egui::ComboBox::from_label(self.tr("Rules"))
+            .selected_text(self.tr(self.ruleset.label()))
+            .show_ui(ui, |ui| {
+                for ruleset in Ruleset::ALL {
+                    ui.selectable_value(&mut self.ruleset, ruleset, self.language.tr(ruleset.label()));
+                }
+            });
 
-                    ui.add_space(20.0);
-                    ui.label("Level: 0");
+        if self.ruleset == Ruleset::Classic && self.mode != GameMode::Master {
+            let label = self.tr("Starting level");
+            ui.add(egui::Slider::new(&mut self.start_level, 0..=classic::MAX_START_LEVEL).text(label));
+        }
+
+        if self.mode == GameMode::Daily {
+            if self.daily_attempted() {
+                ui.label(self.tr("You've already played today's challenge. Come back tomorrow!"));
+            }
+        } else if self.mode == GameMode::Online {
+            self.render_online_lobby(ui);
+        } else {
+            egui::ComboBox::from_label(self.tr("Randomizer"))
+                .selected_text(self.tr(self.randomizer.label()))
+                .show_ui(ui, |ui| {
+                    for randomizer in Randomizer::ALL {
+                        ui.selectable_value(&mut self.randomizer, randomizer, self.language.tr(randomizer.label()));
+                    }
                 });
 
+            ui.horizontal(|ui| {
+                ui.label(self.tr("Seed (optional):"));
+                ui.text_edit_singleline(&mut self.seed_text);
+            });
+        }
 
+        if self.mode == GameMode::Dig {
+            let label = self.tr("Garbage rows");
+            ui.add(egui::Slider::new(&mut self.dig_rows, 1..=MAX_DIG_ROWS).text(label));
+        }
+        if matches!(self.mode, GameMode::Versus | GameMode::Online) {
+            let label = self.tr("Best of");
+            ui.add(egui::Slider::new(&mut self.best_of, 1..=rounds::MAX_BEST_OF).text(label));
+        }
+        if self.mode == GameMode::Versus {
+            self.render_player_two_picker(ui);
+        }
+    }
 
-                ui.add_space(10.0);
+    // Mode-specific progress above the board: time, goals, grade and so on
+    fn render_mode_hud(&self, ui: &mut egui::Ui) {
+        // The run clock; it leaves out pauses and countdowns. Ultra counts down instead, below.
+        if self.mode != GameMode::Ultra {
+            ui.label(egui::RichText::new(format_time(self.play_time())).monospace().size(18.0).strong());
+        }
 
-                if ctx.input(|i| i.key_pressed(egui::Key::Space)) {
-                    self.is_paused = !self.is_paused;
-                }
+        if self.mode == GameMode::Sprint {
+            ui.label(format!("{} {}", self.tr("Lines left:"), SPRINT_LINES.saturating_sub(self.lines)));
+        }
+        self.render_live_split(ui);
 
-                let now = Instant::now();
-                if now.duration_since(self.last_update) >= self.drop_speed {
-                    self.last_update = now; // Reset timer at actual execution
-                    self.move_block_down();
-                }
+        if self.mode == GameMode::Dig {
+            ui.label(format!("{} {}", self.tr("Garbage left:"), self.garbage_rows_left()));
+        }
 
-                if self.is_paused {
-                    ui.vertical_centered(|ui| {
-                        ui.label("Game Paused");
-                    });
+        if let Some(grade) = self.master_grade() {
+            ui.label(format!("{} {}   {} {}",
+                self.tr("Grade:"), grade,
+                self.tr("Section:"), format_time(self.current_section_time())));
+        }
 
-                    return;
-                }
+        if self.mode == GameMode::Mission {
+            ui.label(format!("{} {} {}/{}   {} {}",
+                self.tr("Mission:"), self.tr(self.mission.kind.label()),
+                self.mission.progress, self.mission.target,
+                self.tr("Completed:"), self.missions_completed));
+        }
 
-                self.render_grid(ui);
+        if self.mode == GameMode::TimeAttack {
+            if let Some((lines, _)) = self.next_checkpoint() {
+                ui.label(egui::RichText::new(format!("{} {} {}: {}",
+                    self.tr("Checkpoint"), lines, self.tr("lines"), format_time(self.checkpoint_time_left())))
+                    .size(21.0)
+                    .strong());
+            }
+        }
+
+        if self.mode == GameMode::Ultra {
+            let remaining = ULTRA_DURATION.saturating_sub(self.play_time());
+            ui.label(egui::RichText::new(format!("{} {}", self.tr("Time left:"), format_time(remaining)))
+                .monospace()
+                .size(21.0)
+                .strong());
+        }
+    }
 
-                if ctx.input(|i| i.key_pressed(egui::Key::ArrowLeft)) {
-                    let new_position = self.active_block.as_ref()
-                        .map(|block| (block.position.0 - 1, block.position.1))
-                        .unwrap_or((0, 0));
+    fn render_gameplay(&mut self, ctx: &egui::Context) {
+        // With a background image the panels are left see-through, and only the board is shaded
+        let background = if self.paint_level_background(ctx) {
+            egui::Color32::TRANSPARENT
+        } else {
+            self.theme().background
+        };
+        let text_color = self.theme().text;
+        let panel_frame = egui::Frame::default()
+            .fill(background)
+            .inner_margin(8.0);
+        egui::SidePanel::left("hold_panel")
+            .resizable(false)
+            .exact_width(SIDE_PANEL_WIDTH)
+            .frame(panel_frame)
+            .show(ctx, |ui| {
+                ui.visuals_mut().override_text_color = Some(text_color);
+                self.render_hold_panel(ui);
+                if !self.focus_mode {
+                    ui.add_space(20.0);
+                    self.render_i_drought(ui);
+                    self.render_piece_tally(ui);
+                }
+            });
+        egui::SidePanel::right("next_panel")
+            .resizable(false)
+            .exact_width(SIDE_PANEL_WIDTH)
+            .frame(panel_frame)
+            .show(ctx, |ui| {
+                ui.visuals_mut().override_text_color = Some(text_color);
+                self.render_next_panel(ui);
+                if self.touch_controls {
+                    ui.add_space(10.0);
+                    if ui.button(self.tr("Pause")).clicked() {
+                        self.set_paused(true);
+                    }
+                }
+            });
+        self.render_input_display(ctx);
 
-                    let has_collision = self.check_collision_with_position(new_position);
+        egui::CentralPanel::default()
+            .frame(egui::Frame::default().fill(background))
+            .show(ctx, |ui| {
+                ui.visuals_mut().override_text_color = Some(text_color);
+                if !self.focus_mode {
+                    self.render_mode_hud(ui);
+                }
+                if self.one_switch {
+                    self.render_one_switch_prompt(ui);
+                }
+                ui.add_space(10.0);
 
-                    if !has_collision {
-                        if let Some(block) = self.active_block.as_mut() {
-                            block.position.0 -= 1;
-                        }
+                if self.confirming.is_none() && self.action_pressed(ctx, Action::Pause) {
+                    if self.watching_replay() {
+                        self.toggle_replay_pause();
+                    } else {
+                        self.set_paused(!self.is_paused);
                     }
                 }
 
-                if ctx.input(|i| i.key_pressed(egui::Key::ArrowRight)) {
-                    let new_position = self.active_block.as_ref()
-                        .map(|block| (block.position.0 + 1, block.position.1))
-                        .unwrap_or((0, 0));
+                if self.is_paused {
+                    self.render_pause_menu(ui);
+                    return;
+                }
 
-                    let has_collision = self.check_collision_with_position(new_position);
+                if self.mode == GameMode::Practice {
+                    self.render_practice_tools(ui);
+                }
+                self.render_replay_controls(ui);
 
-                    if !has_collision {
-                        if let Some(block) = self.active_block.as_mut() {
-                            block.position.0 += 1;
-                        }
+                self.update_countdown();
+                self.step_playback();
+                let soft_drop = ctx.input(|i| self.controls.held(i, Action::SoftDrop));
+                self.set_soft_dropping(soft_drop);
+                self.apply_gravity();
+                if !self.rivals.is_empty() {
+                    if !self.counting_down() {
+                        self.update_rivals(ctx);
                     }
+                    self.render_rivals(ui);
+                }
+
+                self.update_effects();
+                if matches!(self.mode, GameMode::Versus | GameMode::Online) {
+                    profile::draw_player(ui, self.active_profile());
                 }
+                let board = self.render_grid(ui);
 
-                if ctx.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
-                    self.rotate_block();
-                    ui.label("Rotated");
+                self.pointer_actions.clear();
+                if !self.watching_replay() {
+                    if self.mouse_controls {
+                        self.read_mouse_controls(ctx, board);
+                    } else {
+                        self.read_touch_gestures(ctx, board);
+                    }
+                    if !self.piece_frozen() {
+                        self.count_keys(ctx);
+                        self.count_finesse_presses(ctx);
+                    }
+                    self.queue_input(ctx);
+                    self.step_input();
+                    if self.one_switch {
+                        self.update_one_switch(ctx);
+                    }
                 }
-                if ctx.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
-                    ui.label("Moved Down");
+
+                if self.mode == GameMode::Party && !self.focus_mode {
+                    let held = self.held_item.map_or("-", |item| self.tr(item.label()));
+                    ui.label(format!("{} {} [E]", self.tr("Item:"), held));
                 }
 
-                if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-                    self.state = GameState::GameOver;
+                if self.confirming.is_none() && self.action_pressed(ctx, Action::Abandon) {
+                    if self.watching_replay() {
+                        self.close_replay();
+                    } else {
+                        self.ask(ctx, Confirm::Abandon);
+                    }
                 }
             });
     }
 
     fn render_game_over(&mut self, ctx: &egui::Context) {
+        // A run that topped out shows the board filling up before the results
+        if self.topping_out() {
+            egui::CentralPanel::default()
+                .frame(egui::Frame::default().fill(self.theme().background))
+                .show(ctx, |ui| ui.vertical_centered(|ui| self.render_grid(ui)));
+            ctx.request_repaint();
+            return;
+        }
+        if self.between_rounds() {
+            self.render_intermission(ctx);
+            return;
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.vertical_centered(|ui| {
-                ui.heading("Game Over!");
-                ui.add_space(140.0);
-                ui.label("Enter Name:");
-                ui.text_edit_singleline(&mut self.new_high_score_name);
+            egui::ScrollArea::vertical().show(ui, |ui| ui.vertical_centered(|ui| {
+                ui.heading(self.tr("Game Over!"));
+                ui.add_space(20.0);
+
+                if let Some(result) = &self.last_result {
+                    ui.label(self.tr(result.mode.label()));
+                    if result.completed && result.mode == GameMode::Sprint {
+                        ui.strong(self.tr("40 lines cleared!"));
+                    }
+                    if result.completed && result.mode == GameMode::Dig {
+                        ui.strong(self.tr("All garbage cleared!"));
+                    }
+                    if result.mode == GameMode::TimeAttack {
+                        ui.strong(self.tr(if result.completed { "All checkpoints reached!" } else { "Out of time" }));
+                    }
+                    if result.mode == GameMode::Battle {
+                        ui.strong(self.tr(if result.completed { "You win!" } else { "The CPU wins" }));
+                    }
+                    if let Some(placement) = result.placement {
+                        ui.strong(format!("{} #{} / {}", self.tr("Placement:"), placement, battle::ROYALE_BOTS + 1));
+                    }
+                    if result.mode == GameMode::Online {
+                        ui.strong(self.tr(if result.completed { "You win!" } else { "Your opponent wins" }));
+                    }
+                    if result.mode == GameMode::Versus {
+                        ui.strong(self.tr(if result.completed { "Player 1 wins!" } else { "Player 2 wins!" }));
+                    }
+                    if self.versus_match.as_ref().is_some_and(|versus_match| versus_match.rounds.len() > 1) {
+                        ui.add_space(10.0);
+                        self.render_match_summary(ui);
+                        ui.add_space(10.0);
+                    }
+                    ui.label(format!("{} {}", self.tr("Score:"), result.score));
+                    ui.label(format!("{} {}", self.tr("Lines:"), result.lines));
+                    ui.label(format!("{} {}", self.tr("Time:"), format_time(result.duration)));
+                    ui.label(format!("PPS: {:.2}", result.pieces_per_second()));
+                    ui.label(format!("{} {}", self.tr("Finesse faults:"), result.finesse_faults));
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} {}", self.tr("Seed:"), result.seed));
+                        if ui.small_button(self.tr("Copy")).clicked() {
+                            ctx.copy_text(result.seed.to_string());
+                        }
+                    });
+                    if result.mode == GameMode::Mission {
+                        ui.label(format!("{} {}", self.tr("Missions completed:"), result.missions_completed));
+                    }
+
+                    if let Some(grade) = result.grade {
+                        ui.label(format!("{} {}", self.tr("Level:"), result.level));
+                        ui.strong(format!("{} {}", self.tr("Grade:"), grade));
+                        for (i, time) in result.section_times.iter().enumerate() {
+                            ui.label(format!("{} {}-{}: {}", self.tr("Section"), i * 100, i * 100 + 99, format_time(*time)));
+                        }
+                    }
+
+                    self.render_split_results(ui, result);
+
+                    if result.stack_heights.len() > 1 {
+                        ui.add_space(10.0);
+                        ui.strong(self.tr("Stack height"));
+                        let size = egui::vec2(ui.available_width().min(300.0), 80.0);
+                        stats::draw_stack_graph(ui, &result.stack_heights, size);
+                    }
+
+                    if result.mode == GameMode::Daily {
+                        ui.add_space(10.0);
+                        self.render_daily_leaderboard(ui);
+                    }
+
+                    // The stack was hidden during play, so show what it really looked like
+                    if result.mode == GameMode::Invisible {
+                        ui.add_space(10.0);
+                        self.render_grid(ui);
+                    }
+                }
+
+                if ui.button(self.tr("Copy board")).clicked() {
+                    ctx.copy_text(board_text::board_to_ascii(&self.grid));
+                }
+
+                if let Some(result) = &self.last_result {
+                    ui.add_space(20.0);
+                    self.render_high_scores(ui, result.mode);
+                }
+
+                ui.add_space(40.0);
+                self.render_name_entry(ui);
 
                 ui.add_space(33.0);
-                if ui.button("Submit Score").clicked() && !self.new_high_score_name.is_empty() {
-                    self.high_scores.push((self.new_high_score_name.clone(), self.score));
-                    self.high_scores
-                        .sort_by(|a, b| b.1.cmp(&a.1));
-                    self.high_scores.truncate(10);
-                    save_high_scores(&self.high_scores);
-                    self.new_high_score_name.clear();
-                    self.state = GameState::StartScreen;
+                ui.horizontal(|ui| {
+                    if self.mode == GameMode::Online {
+                        self.render_online_rematch(ui);
+                    } else if ui.add_enabled(self.can_start(), egui::Button::new(self.tr("Rematch"))).clicked() {
+                        self.reset_game();
+                    }
+                    if let Some(replay) = &self.last_replay {
+                        if ui.button(self.tr("Watch replay")).clicked() {
+                            self.watch_replay(replay.clone());
+                        }
+                    }
+                    if ui.button(self.tr("Change rules")).clicked() {
+                        self.changing_rules = !self.changing_rules;
+                    }
+                });
+
+                if self.changing_rules {
+                    ui.add_space(10.0);
+                    self.render_rule_pickers(ui);
+                    if ui.add_enabled(self.can_start(), egui::Button::new(self.tr("Start Game"))).clicked() {
+                        self.reset_game();
+                    }
                 }
 
                 ui.add_space(33.0);
-                if ui.button("Back to Start").clicked() {
+                if ui.button(self.tr("Back to Start")).clicked() {
                     self.state = GameState::StartScreen;
                 }
-            });
+            }));
         });
     }
 }
 
+// Zooms the whole UI (board, panels and text alike) so the designed layout fills the window,
+// times the player's UI scale
+fn scale_to_window(ctx: &egui::Context, ui_scale: f32) {
+    let window = ctx.screen_rect().size() * ctx.zoom_factor();
+    let zoom = (window.x / DESIGN_WINDOW_SIZE.x).min(window.y / DESIGN_WINDOW_SIZE.y) * ui_scale;
+    if zoom > 0.0 && (zoom - ctx.zoom_factor()).abs() > 0.01 {
+        ctx.set_zoom_factor(zoom);
+    }
+}
+
+// Black bars on two sides keep the game at its designed shape when the screen isn't
+fn letterbox(ctx: &egui::Context) {
+    let screen = ctx.screen_rect().size();
+    let aspect = DESIGN_WINDOW_SIZE.x / DESIGN_WINDOW_SIZE.y;
+    let bars = egui::Frame::none().fill(egui::Color32::BLACK);
+
+    let bar_width = (screen.x - screen.y * aspect) / 2.0;
+    let bar_height = (screen.y - screen.x / aspect) / 2.0;
+    if bar_width >= 1.0 {
+        let sides = [("letterbox_left", egui::panel::Side::Left), ("letterbox_right", egui::panel::Side::Right)];
+        for (id, side) in sides {
+            egui::SidePanel::new(side, id)
+                .resizable(false)
+                .show_separator_line(false)
+                .exact_width(bar_width)
+                .frame(bars)
+                .show(ctx, |_| {});
+        }
+    } else if bar_height >= 1.0 {
+        let sides = [
+            ("letterbox_top", egui::panel::TopBottomSide::Top),
+            ("letterbox_bottom", egui::panel::TopBottomSide::Bottom),
+        ];
+        for (id, side) in sides {
+            egui::TopBottomPanel::new(side, id)
+                .resizable(false)
+                .show_separator_line(false)
+                .exact_height(bar_height)
+                .frame(bars)
+                .show(ctx, |_| {});
+        }
+    }
+}
+
 fn main() {
-    let app = CrowsTetris::default();
-    let ctx = egui::Context::default();
-    let mut size = ctx.used_size();
-    size.x = 420.00;
-    size.y = 540.00;
+    if std::env::args().any(|arg| arg == "--self-test") {
+        std::process::exit(if self_test::run() { 0 } else { 1 });
+    }
+
+    data_dir::init();
+    let mut app = CrowsTetris::default();
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_resizable(false)
-            .with_inner_size(size),
+        viewport: settings::with_saved_window(egui::ViewportBuilder::default())
+            .with_resizable(true)
+            .with_min_inner_size(MIN_WINDOW_SIZE),
         ..Default::default()
     };
 
-    let _ = eframe::run_native("Crow's Tetris", options, Box::new( |_cc| Ok(Box::new(app)) ));
+    let _ = eframe::run_native("Crow's Tetris", options, Box::new(|cc| {
+        // The zoom follows the window size, so the keyboard shortcuts would only fight it
+        cc.egui_ctx.options_mut(|options| options.zoom_with_keyboard = false);
+        app.glow_renderer = cc.gl.as_ref().and_then(|gl| GlowRenderer::new(gl));
+        app.gamepads = Gamepads::connect();
+        app.load_settings(&cc.egui_ctx);
+        Ok(Box::new(app))
+    }));
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+// Master mode: levels 0-999 in sections of 100, gravity ramping up to 20G,
+// and a grade from 9 up to GM awarded for how far, how fast and how evenly
+// the player got there.
+
+use std::time::Duration;
+
+use crate::{CrowsTetris, GameMode};
+
+pub const MASTER_MAX_LEVEL: u32 = 999;
+pub const MASTER_LOCK_DELAY: Duration = Duration::from_millis(500);
+pub const SECTION_LENGTH: u32 = 100;
+const SECTION_PAR: Duration = Duration::from_secs(65);
+// Sections within this fraction of the average section time count as consistent
+const CONSISTENCY_TOLERANCE: f64 = 0.15;
+
+// (starting level, gravity in 1/256ths of a cell per frame at 60 fps)
+const GRAVITY_TABLE: [(u32, u32); 30] = [
+    (0, 4),
+    (30, 6),
+    (35, 8),
+    (40, 10),
+    (50, 12),
+    (60, 16),
+    (70, 32),
+    (80, 48),
+    (90, 64),
+    (100, 80),
+    (120, 96),
+    (140, 112),
+    (160, 128),
+    (170, 144),
+    (200, 4),
+    (220, 32),
+    (230, 64),
+    (233, 96),
+    (236, 128),
+    (239, 160),
+    (243, 192),
+    (247, 224),
+    (251, 256),
+    (300, 512),
+    (330, 768),
+    (360, 1024),
+    (400, 1280),
+    (420, 1024),
+    (450, 768),
+    (500, 5120),
+];
+
+const GRADES: [&str; 19] = [
+    "9", "8", "7", "6", "5", "4", "3", "2", "1", "S1", "S2", "S3", "S4", "S5", "S6", "S7", "S8", "S9", "GM",
+];
+
+pub fn drop_interval(level: u32) -> Duration {
+    let gravity = GRAVITY_TABLE
+        .iter()
+        .rev()
+        .find(|(start, _)| level >= *start)
+        .map_or(4, |&(_, gravity)| gravity);
+    Duration::from_secs_f64(256.0 / gravity as f64 / 60.0)
+}
+
+fn is_consistent(section_times: &[Duration]) -> bool {
+    if section_times.len() < 2 {
+        return false;
+    }
+
+    let mean = section_times.iter().map(Duration::as_secs_f64).sum::<f64>() / section_times.len() as f64;
+    section_times
+        .iter()
+        .all(|time| (time.as_secs_f64() - mean).abs() <= mean * CONSISTENCY_TOLERANCE)
+}
+
+// Level reached is worth up to 14 grades, sections under par up to 3 more and
+// an even pace one more. GM is reserved for a full, fast, consistent run.
+pub fn grade(level: u32, section_times: &[Duration]) -> &'static str {
+    let under_par = section_times.iter().filter(|&&time| time <= SECTION_PAR).count();
+    let consistent = is_consistent(section_times);
+
+    if level >= MASTER_MAX_LEVEL && under_par == section_times.len() && consistent {
+        return GRADES[GRADES.len() - 1];
+    }
+
+    let mut points = level as f64 / MASTER_MAX_LEVEL as f64 * 14.0 + under_par as f64 * 0.3;
+    if consistent {
+        points += 1.0;
+    }
+    GRADES[(points as usize).min(GRADES.len() - 2)]
+}
+
+impl CrowsTetris {
+    pub(crate) fn advance_master_level(&mut self, lines_cleared: usize) {
+        let previous = self.level;
+
+        // Placing a piece can't carry the level into a new section (or to 999); only clears can
+        if self.level % SECTION_LENGTH != SECTION_LENGTH - 1 && self.level != MASTER_MAX_LEVEL - 1 {
+            self.level += 1;
+        }
+        self.level = (self.level + lines_cleared as u32).min(MASTER_MAX_LEVEL);
+
+        if self.level / SECTION_LENGTH > previous / SECTION_LENGTH || self.level == MASTER_MAX_LEVEL {
+            let now = self.play_time();
+            self.section_times.push(now.saturating_sub(self.section_started));
+            self.section_started = now;
+        }
+    }
+
+    pub(crate) fn current_section_time(&self) -> Duration {
+        self.play_time().saturating_sub(self.section_started)
+    }
+
+    pub(crate) fn master_grade(&self) -> Option<&'static str> {
+        (self.mode == GameMode::Master).then(|| grade(self.level, &self.section_times))
+    }
+}
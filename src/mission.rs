@@ -0,0 +1,119 @@
+// Mission mode: the player works through a rotating list of objectives, each
+// worth bonus points, while the stack keeps coming as in Marathon.
+
+use rand::Rng;
+
+use crate::clears::ClearEvent;
+use crate::{CrowsTetris, GameMode};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ObjectiveKind {
+    Lines,
+    Tetrises,
+    TSpins,
+    TSpinDoubles,
+    Combo,
+}
+
+impl ObjectiveKind {
+    const ALL: [ObjectiveKind; 5] = [
+        ObjectiveKind::Lines,
+        ObjectiveKind::Tetrises,
+        ObjectiveKind::TSpins,
+        ObjectiveKind::TSpinDoubles,
+        ObjectiveKind::Combo,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ObjectiveKind::Lines => "Lines",
+            ObjectiveKind::Tetrises => "4-line clears",
+            ObjectiveKind::TSpins => "T-spin clears",
+            ObjectiveKind::TSpinDoubles => "T-spin doubles",
+            ObjectiveKind::Combo => "Combo",
+        }
+    }
+
+    // Smallest and largest target a mission of this kind can ask for
+    fn targets(self) -> (u32, u32) {
+        match self {
+            ObjectiveKind::Lines => (10, 25),
+            ObjectiveKind::Tetrises => (1, 3),
+            ObjectiveKind::TSpins => (1, 3),
+            ObjectiveKind::TSpinDoubles => (1, 2),
+            ObjectiveKind::Combo => (3, 5),
+        }
+    }
+
+    fn reward_per_target(self) -> i32 {
+        match self {
+            ObjectiveKind::Lines => 20,
+            ObjectiveKind::Tetrises => 600,
+            ObjectiveKind::TSpins => 500,
+            ObjectiveKind::TSpinDoubles => 1000,
+            ObjectiveKind::Combo => 200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Mission {
+    pub kind: ObjectiveKind,
+    pub target: u32,
+    pub progress: u32,
+}
+
+impl Mission {
+    // Picks a new objective, never the same kind twice in a row.
+    pub fn random(previous: Option<ObjectiveKind>, rng: &mut impl Rng) -> Mission {
+        let choices: Vec<ObjectiveKind> = ObjectiveKind::ALL
+            .iter()
+            .copied()
+            .filter(|&kind| Some(kind) != previous)
+            .collect();
+        let kind = choices[rng.random_range(0..choices.len())];
+        let (min, max) = kind.targets();
+
+        Mission {
+            kind,
+            target: rng.random_range(min..=max),
+            progress: 0,
+        }
+    }
+
+    pub fn reward(&self) -> i32 {
+        self.kind.reward_per_target() * self.target as i32
+    }
+
+    fn track(&mut self, clear: &ClearEvent) {
+        match self.kind {
+            ObjectiveKind::Lines => self.progress += clear.lines as u32,
+            ObjectiveKind::Tetrises if clear.lines >= 4 => self.progress += 1,
+            ObjectiveKind::TSpins if clear.t_spin && clear.lines > 0 => self.progress += 1,
+            ObjectiveKind::TSpinDoubles if clear.t_spin && clear.lines == 2 => self.progress += 1,
+            // A combo has to be built in one go, so progress is the best streak so far
+            ObjectiveKind::Combo => self.progress = self.progress.max(clear.combo),
+            _ => {}
+        }
+        self.progress = self.progress.min(self.target);
+    }
+
+    fn is_complete(&self) -> bool {
+        self.progress >= self.target
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn track_mission(&mut self, clear: &ClearEvent) {
+        if self.mode != GameMode::Mission {
+            return;
+        }
+
+        self.mission.track(clear);
+        if self.mission.is_complete() {
+            self.score += self.mission.reward();
+            self.missions_completed += 1;
+            self.mission = Mission::random(Some(self.mission.kind), &mut self.game_rng);
+        }
+    }
+}
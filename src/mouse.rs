@@ -0,0 +1,58 @@
+// Mouse controls for one-handed play: the falling piece follows the
+// cursor's column, left click rotates clockwise, the wheel rotates the other
+// way and right click hard drops.
+
+use eframe::egui;
+
+use crate::controls::Action;
+use crate::{CrowsTetris, GRID_WIDTH};
+
+// Scrolling this far, in points, turns the piece once; about one notch of a wheel
+const WHEEL_STEP: f32 = 40.0;
+
+// The column the piece's left edge goes to so that it's centred under the cursor
+pub fn target_column(cursor_x: f32, board: egui::Rect, piece_width: usize) -> i32 {
+    let cell_size = board.width() / GRID_WIDTH as f32;
+    let column = ((cursor_x - board.min.x) / cell_size).floor() as i32;
+    (column - piece_width as i32 / 2).clamp(0, (GRID_WIDTH - piece_width) as i32)
+}
+
+impl CrowsTetris {
+    // Turns this frame's mouse movement and clicks over the board into actions
+    pub(crate) fn read_mouse_controls(&mut self, ctx: &egui::Context, board: egui::Rect) {
+        let (hover, left, right, scroll) = ctx.input(|i| {
+            (
+                i.pointer.hover_pos(),
+                i.pointer.button_pressed(egui::PointerButton::Primary),
+                i.pointer.button_pressed(egui::PointerButton::Secondary),
+                i.raw_scroll_delta.y,
+            )
+        });
+        let Some(hover) = hover.filter(|&pos| board.contains(pos)) else {
+            self.mouse_scroll = 0.0;
+            return;
+        };
+
+        // A column a frame; gravity's repaints keep it going after the mouse stops
+        if let Some(block) = &self.active_block {
+            let target = target_column(hover.x, board, block.shape[0].len());
+            if target < block.position.0 {
+                self.pointer_actions.push(Action::MoveLeft);
+            } else if target > block.position.0 {
+                self.pointer_actions.push(Action::MoveRight);
+            }
+        }
+
+        if left {
+            self.pointer_actions.push(Action::Rotate);
+        }
+        if right && self.hard_drop_allowed() {
+            self.pointer_actions.push(Action::HardDrop);
+        }
+        self.mouse_scroll += scroll;
+        if self.mouse_scroll.abs() >= WHEEL_STEP {
+            self.mouse_scroll = 0.0;
+            self.pointer_actions.push(Action::RotateCounterclockwise);
+        }
+    }
+}
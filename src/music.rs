@@ -0,0 +1,168 @@
+// Background music: a looping track on the menus, another while playing, and
+// a short sting when a run ends. The gameplay track speeds up as the stack
+// nears the top. Each streams from an ogg file in the music folder the
+// game ships with, found next to the executable or, run from the source
+// tree, in the tree's own. A missing or broken file plays a built-in tune
+// made the same way as the sound effects instead.
+
+use eframe::egui;
+use rodio::source::{self, Source};
+use rodio::Decoder;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use crate::audio::{self, REST};
+use crate::{CrowsTetris, GameState};
+
+const MUSIC_DIR: &str = "music";
+// Quieter than the effects so they stay on top
+const MUSIC_AMPLITUDE: f32 = 0.08;
+
+const E: u64 = 150;
+const Q: u64 = 2 * E;
+const DQ: u64 = 3 * E;
+const H: u64 = 4 * E;
+
+// (frequency in Hz, length in ms) for each note; a rest is silence
+const KOROBEINIKI: &[(f32, u64)] = &[
+    (659.3, Q), (493.9, E), (523.3, E), (587.3, Q), (523.3, E), (493.9, E),
+    (440.0, Q), (440.0, E), (523.3, E), (659.3, Q), (587.3, E), (523.3, E),
+    (493.9, DQ), (523.3, E), (587.3, Q), (659.3, Q),
+    (523.3, Q), (440.0, Q), (440.0, Q), (REST, Q),
+    (REST, E), (587.3, Q), (698.5, E), (880.0, Q), (784.0, E), (698.5, E),
+    (659.3, DQ), (523.3, E), (659.3, Q), (587.3, E), (523.3, E),
+    (493.9, Q), (493.9, E), (523.3, E), (587.3, Q), (659.3, Q),
+    (523.3, Q), (440.0, Q), (440.0, Q), (REST, Q),
+];
+
+// Slow broken chords: A minor, F, C, G
+const ROOST: &[(f32, u64)] = &[
+    (220.0, Q), (261.6, Q), (329.6, Q), (261.6, Q),
+    (174.6, Q), (220.0, Q), (261.6, Q), (220.0, Q),
+    (261.6, Q), (329.6, Q), (392.0, Q), (329.6, Q),
+    (196.0, Q), (246.9, Q), (293.7, Q), (246.9, H),
+];
+
+// Waits out the game over sound effect before coming in
+const STING: &[(f32, u64)] = &[(REST, H), (440.0, Q), (392.0, Q), (349.2, Q), (329.6, 4 * H)];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum MusicTrack {
+    Korobeiniki,
+    Roost,
+    Off,
+}
+
+impl MusicTrack {
+    pub const ALL: [MusicTrack; 3] = [MusicTrack::Korobeiniki, MusicTrack::Roost, MusicTrack::Off];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MusicTrack::Korobeiniki => "Korobeiniki",
+            MusicTrack::Roost => "Roost",
+            MusicTrack::Off => "Off",
+        }
+    }
+
+    pub fn file(self) -> Option<&'static str> {
+        match self {
+            MusicTrack::Korobeiniki => Some("korobeiniki.ogg"),
+            MusicTrack::Roost => Some("roost.ogg"),
+            MusicTrack::Off => None,
+        }
+    }
+
+    fn notes(self) -> &'static [(f32, u64)] {
+        match self {
+            MusicTrack::Korobeiniki => KOROBEINIKI,
+            MusicTrack::Roost => ROOST,
+            MusicTrack::Off => &[],
+        }
+    }
+}
+
+// What the music sink should be playing
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MusicCue {
+    Loop(MusicTrack),
+    GameOver,
+}
+
+pub type MusicSource = Box<dyn Source<Item = f32> + Send>;
+
+fn tune(notes: &'static [(f32, u64)]) -> impl Iterator<Item = impl Source<Item = f32>> + Clone {
+    notes.iter().map(|&(frequency, ms)| audio::note(frequency, ms, MUSIC_AMPLITUDE))
+}
+
+// Not the working directory, which is wherever the game was started from
+pub fn music_path(file: &str) -> PathBuf {
+    let beside_exe = env::current_exe().ok().and_then(|exe| Some(exe.parent()?.join(MUSIC_DIR)));
+    let dir = beside_exe
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(MUSIC_DIR));
+    dir.join(file)
+}
+
+fn open(file: &str) -> Option<BufReader<File>> {
+    File::open(music_path(file)).ok().map(BufReader::new)
+}
+
+impl MusicCue {
+    // None for silence
+    pub fn source(self) -> Option<MusicSource> {
+        match self {
+            MusicCue::Loop(MusicTrack::Off) => None,
+            MusicCue::Loop(track) => {
+                let decoded = track.file().and_then(open).and_then(|file| Decoder::new_looped(file).ok());
+                Some(match decoded {
+                    Some(decoder) => Box::new(decoder.convert_samples()),
+                    None => Box::new(source::from_iter(tune(track.notes()).cycle())),
+                })
+            }
+            MusicCue::GameOver => {
+                let decoded = open("game_over.ogg").and_then(|file| Decoder::new(file).ok());
+                Some(match decoded {
+                    Some(decoder) => Box::new(decoder.convert_samples()),
+                    None => Box::new(source::from_iter(tune(STING))),
+                })
+            }
+        }
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn music_cue(&self) -> MusicCue {
+        match self.state {
+            GameState::Playing => MusicCue::Loop(self.gameplay_music),
+            GameState::GameOver => MusicCue::GameOver,
+            // Settings opened from a paused run keep the run's music going
+            GameState::Settings if self.settings_return == GameState::Playing => MusicCue::Loop(self.gameplay_music),
+            GameState::StartScreen | GameState::Settings | GameState::RngAudit | GameState::Stats => MusicCue::Loop(self.menu_music),
+        }
+    }
+
+    // Only a run in play is tense; the sting and the menus play at their normal speed
+    pub(crate) fn music_tension(&self) -> f32 {
+        if self.state == GameState::Playing {
+            self.danger()
+        } else {
+            0.0
+        }
+    }
+
+    pub(crate) fn render_music_settings(&mut self, ui: &mut egui::Ui) {
+        let language = self.language;
+        for (label, track) in [("Menu music", &mut self.menu_music), ("Gameplay music", &mut self.gameplay_music)] {
+            egui::ComboBox::from_label(language.tr(label))
+                .selected_text(language.tr(track.label()))
+                .show_ui(ui, |ui| {
+                    for option in MusicTrack::ALL {
+                        ui.selectable_value(track, option, language.tr(option.label()));
+                    }
+                });
+        }
+    }
+}
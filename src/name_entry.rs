@@ -0,0 +1,148 @@
+// High score name entry. Besides the text field there's an on-screen
+// keyboard that the arrow keys, or a controller's d-pad and A button, can
+// work, so a score can be saved without a physical keyboard.
+
+use eframe::egui;
+
+use std::time::Duration;
+
+use crate::controls::InputDevice;
+use crate::high_scores::{self, HighScore};
+use crate::{CrowsTetris, GameState};
+
+const PICKER_ROWS: [&str; 4] = ["ABCDEFGHIJ", "KLMNOPQRST", "UVWXYZ0123", "456789 -_."];
+const PICKER_COLUMNS: usize = 10;
+// Below the letters: delete and done, each half a row wide
+const COMMAND_ROW: usize = PICKER_ROWS.len();
+const MAX_NAME_LEN: usize = 16;
+const KEY_SIZE: f32 = 26.0;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PickerKey {
+    Char(char),
+    Delete,
+    Done,
+}
+
+pub fn picker_key((column, row): (usize, usize)) -> PickerKey {
+    match PICKER_ROWS.get(row) {
+        Some(chars) => PickerKey::Char(chars.chars().nth(column).unwrap_or(' ')),
+        None if column < PICKER_COLUMNS / 2 => PickerKey::Delete,
+        None => PickerKey::Done,
+    }
+}
+
+// Moves the cursor, wrapping around the edges; on the command row left and right jump between the two
+pub fn move_cursor((column, row): (usize, usize), dx: isize, dy: isize) -> (usize, usize) {
+    let rows = COMMAND_ROW + 1;
+    let row = (row as isize + dy).rem_euclid(rows as isize) as usize;
+    let step = if row == COMMAND_ROW { dx * (PICKER_COLUMNS / 2) as isize } else { dx };
+    let column = (column as isize + step).rem_euclid(PICKER_COLUMNS as isize) as usize;
+    (column, row)
+}
+
+impl CrowsTetris {
+    fn submit_high_score(&mut self) {
+        if self.new_high_score_name.is_empty() || !self.last_run_qualifies() {
+            return;
+        }
+        let result = self.last_result.as_ref();
+        self.add_high_score(HighScore {
+            name: self.new_high_score_name.clone(),
+            score: self.score,
+            lines: self.lines,
+            level: self.level,
+            mode: self.mode,
+            duration: result.map_or(Duration::ZERO, |result| result.duration),
+            timestamp: high_scores::now(),
+            seed: result.map_or(0, |result| result.seed),
+            replay_digest: self.last_replay.as_ref().map_or(0, high_scores::replay_digest),
+            verified: true,
+            legacy: false,
+        });
+        self.new_high_score_name.clear();
+        self.state = GameState::StartScreen;
+    }
+
+    fn press_picker_key(&mut self, key: PickerKey) {
+        match key {
+            PickerKey::Char(c) if self.new_high_score_name.chars().count() < MAX_NAME_LEN => {
+                self.new_high_score_name.push(c);
+            }
+            PickerKey::Char(_) => {}
+            PickerKey::Delete => {
+                self.new_high_score_name.pop();
+            }
+            PickerKey::Done => self.submit_high_score(),
+        }
+    }
+
+    pub(crate) fn render_name_entry(&mut self, ui: &mut egui::Ui) {
+        if !self.last_run_qualifies() {
+            ui.label(self.tr("Only finished runs go on this leaderboard"));
+            return;
+        }
+        ui.label(self.tr("Enter Name:"));
+        let typing = ui.text_edit_singleline(&mut self.new_high_score_name).has_focus();
+
+        // Opens by itself for controller players
+        let on_gamepad = matches!(self.controls.active_device(), InputDevice::Gamepad(_));
+        egui::CollapsingHeader::new(self.tr("On-screen keyboard"))
+            .open(on_gamepad.then_some(true))
+            .show(ui, |ui| self.render_name_picker(ui, typing));
+
+        ui.add_space(33.0);
+        if ui.button(self.tr("Submit Score")).clicked() {
+            self.submit_high_score();
+        }
+    }
+
+    // The arrow keys move around the keys and Enter presses one, unless the text field has them
+    fn render_name_picker(&mut self, ui: &mut egui::Ui, typing: bool) {
+        if !typing {
+            let (dx, dy, enter) = ui.input(|i| {
+                let axis = |less, more| i.key_pressed(more) as isize - i.key_pressed(less) as isize;
+                (
+                    axis(egui::Key::ArrowLeft, egui::Key::ArrowRight),
+                    axis(egui::Key::ArrowUp, egui::Key::ArrowDown),
+                    i.key_pressed(egui::Key::Enter),
+                )
+            });
+            self.name_cursor = move_cursor(self.name_cursor, dx, dy);
+            if enter {
+                self.press_picker_key(picker_key(self.name_cursor));
+            }
+        }
+
+        let mut clicked = None;
+        egui::Grid::new("name_picker").spacing(egui::vec2(2.0, 2.0)).show(ui, |ui| {
+            for (row, chars) in PICKER_ROWS.iter().enumerate() {
+                for (column, c) in chars.chars().enumerate() {
+                    let label = if c == ' ' { "␣".to_string() } else { c.to_string() };
+                    let key = egui::Button::new(label)
+                        .selected(self.name_cursor == (column, row))
+                        .min_size(egui::Vec2::splat(KEY_SIZE));
+                    if ui.add(key).clicked() {
+                        clicked = Some((column, row));
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        ui.horizontal(|ui| {
+            let width = egui::vec2(KEY_SIZE * PICKER_COLUMNS as f32 / 2.0, KEY_SIZE);
+            for (column, label) in [(0, self.tr("Delete")), (PICKER_COLUMNS / 2, self.tr("Done"))] {
+                let key = egui::Button::new(label)
+                    .selected(picker_key(self.name_cursor) == picker_key((column, COMMAND_ROW)))
+                    .min_size(width);
+                if ui.add(key).clicked() {
+                    clicked = Some((column, COMMAND_ROW));
+                }
+            }
+        });
+        if let Some(cursor) = clicked {
+            self.name_cursor = cursor;
+            self.press_picker_key(picker_key(cursor));
+        }
+    }
+}
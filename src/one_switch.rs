@@ -0,0 +1,100 @@
+// One-switch mode, for players with a single button. The falling piece
+// turns through its rotations on its own until the switch (the hard drop
+// input) is pressed, then sweeps across the board column by column until
+// it's pressed again, which drops it. Gravity waits while it scans. Under
+// Classic rules, with no hard drop, the second press stops the sweep and
+// gravity takes the piece down from there.
+
+use eframe::egui;
+use std::time::Instant;
+
+use crate::controls::Action;
+use crate::CrowsTetris;
+
+pub const MIN_SCAN_INTERVAL_MS: u64 = 300;
+pub const MAX_SCAN_INTERVAL_MS: u64 = 2000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ScanPhase {
+    Rotation,
+    Column,
+    // Classic rules: left to gravity in the column picked
+    Falling,
+}
+
+pub struct OneSwitch {
+    pub phase: ScanPhase,
+    last_step: Instant,
+    // Which way the column sweep is going; it turns around at the walls
+    direction: i32,
+    // The piece being placed, counted by pieces placed, so a new one starts over
+    piece: u32,
+}
+
+impl Default for OneSwitch {
+    fn default() -> Self {
+        Self {
+            phase: ScanPhase::Rotation,
+            last_step: Instant::now(),
+            direction: 1,
+            piece: 0,
+        }
+    }
+}
+
+impl CrowsTetris {
+    // Gravity waits until the piece is dropped or, under Classic rules, let go
+    pub(crate) fn one_switch_scanning(&self) -> bool {
+        self.one_switch && self.one_switch_state.phase != ScanPhase::Falling
+    }
+
+    pub(crate) fn update_one_switch(&mut self, ctx: &egui::Context) {
+        if self.piece_frozen() || self.active_block.is_none() {
+            return;
+        }
+        if self.one_switch_state.piece != self.pieces_placed {
+            self.one_switch_state = OneSwitch { piece: self.pieces_placed, ..OneSwitch::default() };
+        }
+
+        if self.action_pressed(ctx, Action::HardDrop) {
+            self.one_switch_state.last_step = Instant::now();
+            match self.one_switch_state.phase {
+                ScanPhase::Rotation => self.one_switch_state.phase = ScanPhase::Column,
+                ScanPhase::Column if self.hard_drop_allowed() => self.hard_drop(),
+                ScanPhase::Column => {
+                    self.one_switch_state.phase = ScanPhase::Falling;
+                    // Falls from here, not from when the scan started
+                    self.last_update = Instant::now();
+                }
+                ScanPhase::Falling => {}
+            }
+            return;
+        }
+
+        if self.one_switch_state.last_step.elapsed() < self.scan_interval {
+            return;
+        }
+        self.one_switch_state.last_step = Instant::now();
+        match self.one_switch_state.phase {
+            ScanPhase::Rotation => self.rotate_block(true),
+            ScanPhase::Falling => {}
+            ScanPhase::Column => {
+                let direction = self.one_switch_state.direction;
+                if !self.shift_piece(direction) {
+                    self.one_switch_state.direction = -direction;
+                    self.shift_piece(-direction);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn render_one_switch_prompt(&self, ui: &mut egui::Ui) {
+        let prompt = match self.one_switch_state.phase {
+            ScanPhase::Rotation => "Press to keep this rotation",
+            ScanPhase::Column if self.hard_drop_allowed() => "Press to drop here",
+            ScanPhase::Column => "Press to fall here",
+            ScanPhase::Falling => return,
+        };
+        ui.label(egui::RichText::new(self.tr(prompt)).strong());
+    }
+}
@@ -0,0 +1,578 @@
+// Online 1v1: one player hosts, the other joins by address or room code (the
+// host's address and port packed into hex). Both sides play the same piece
+// sequence from a seed the host picks, and trade garbage, board updates and
+// the final result as newline-separated text messages over TCP. On
+// connecting, each side first says hello with its player's name and crow.
+
+use eframe::egui;
+use rand::Rng;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, TcpListener, TcpStream, UdpSocket};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crate::board_text::{board_from_ascii, board_to_ascii};
+use crate::classic::Ruleset;
+use crate::profile::{self, Accessory, CrowAvatar, CrowColor, Profile};
+use crate::rounds::MAX_BEST_OF;
+use crate::settings::find_by_name;
+use crate::{CrowsTetris, GameMode, GameState, Grid, GRID_HEIGHT};
+
+const ONLINE_PORT: u16 = 7777;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// Longer names from the other side are cut short
+const MAX_OPPONENT_NAME_LEN: usize = 24;
+
+#[derive(Debug, PartialEq)]
+pub enum Message {
+    // Start a match with pieces from this seed
+    Seed { seed: u64, rules: MatchRules },
+    // The next round of the match, with pieces from this seed
+    NextRound(u64),
+    Garbage(usize),
+    Board(Box<Grid>, u32),
+    Lost,
+    // Won a sudden death cheese race
+    DugOut,
+    // Play again from the results screen with a new seed and maybe other rules; the host offers, the guest answers
+    Rematch { seed: u64, rules: MatchRules },
+    RematchAccepted,
+    RematchDeclined,
+    // Sent by each side on connecting: who's playing, and their crow
+    Hello(Profile),
+}
+
+// What the host picks for both players
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MatchRules {
+    pub ruleset: Ruleset,
+    pub best_of: u32,
+}
+
+fn ruleset_code(ruleset: Ruleset) -> &'static str {
+    match ruleset {
+        Ruleset::Modern => "MODERN",
+        Ruleset::Classic => "CLASSIC",
+    }
+}
+
+impl MatchRules {
+    fn encode(self) -> String {
+        format!("{} {}", ruleset_code(self.ruleset), self.best_of)
+    }
+
+    fn decode(text: &str) -> Option<MatchRules> {
+        let (ruleset, best_of) = text.split_once(' ')?;
+        let best_of: u32 = best_of.parse().ok()?;
+        Some(MatchRules {
+            ruleset: Ruleset::ALL.into_iter().find(|&rules| ruleset_code(rules) == ruleset)?,
+            best_of: best_of.clamp(1, MAX_BEST_OF),
+        })
+    }
+}
+
+impl Message {
+    pub fn encode(&self) -> String {
+        match self {
+            Message::Seed { seed, rules } => format!("SEED {} {}", seed, rules.encode()),
+            Message::NextRound(seed) => format!("ROUND {}", seed),
+            Message::Garbage(rows) => format!("GARBAGE {}", rows),
+            Message::Board(grid, lines) => format!("BOARD {} {}", lines, board_to_ascii(grid).replace('\n', "/")),
+            Message::Lost => "LOST".to_string(),
+            Message::DugOut => "DUG_OUT".to_string(),
+            Message::Rematch { seed, rules } => format!("REMATCH {} {}", seed, rules.encode()),
+            Message::RematchAccepted => "REMATCH_ACCEPTED".to_string(),
+            Message::RematchDeclined => "REMATCH_DECLINED".to_string(),
+            // The name goes last, since it can have spaces in it
+            Message::Hello(profile) => format!("HELLO {:?} {:?} {}", profile.avatar.color, profile.avatar.accessory, profile.name),
+        }
+    }
+
+    pub fn decode(line: &str) -> Option<Message> {
+        let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match kind {
+            "SEED" => {
+                let (seed, rules) = rest.split_once(' ')?;
+                Some(Message::Seed { seed: seed.parse().ok()?, rules: MatchRules::decode(rules)? })
+            }
+            "ROUND" => rest.parse().ok().map(Message::NextRound),
+            "GARBAGE" => rest.parse().ok().map(Message::Garbage),
+            "BOARD" => {
+                let (lines, board) = rest.split_once(' ')?;
+                let grid = board_from_ascii(&board.replace('/', "\n")).ok()?;
+                Some(Message::Board(Box::new(grid), lines.parse().ok()?))
+            }
+            "LOST" => Some(Message::Lost),
+            "DUG_OUT" => Some(Message::DugOut),
+            "REMATCH" => {
+                let (seed, rules) = rest.split_once(' ')?;
+                Some(Message::Rematch { seed: seed.parse().ok()?, rules: MatchRules::decode(rules)? })
+            }
+            "REMATCH_ACCEPTED" => Some(Message::RematchAccepted),
+            "REMATCH_DECLINED" => Some(Message::RematchDeclined),
+            "HELLO" => {
+                let mut parts = rest.splitn(3, ' ');
+                let color = find_by_name(&CrowColor::ALL, parts.next()?)?;
+                let accessory = find_by_name(&Accessory::ALL, parts.next()?)?;
+                let name: String = parts.next()?.trim().chars().take(MAX_OPPONENT_NAME_LEN).collect();
+                if name.is_empty() {
+                    return None;
+                }
+                Some(Message::Hello(Profile { name, avatar: CrowAvatar { color, accessory } }))
+            }
+            _ => None,
+        }
+    }
+}
+
+pub struct OnlineSession {
+    listener: Option<TcpListener>,
+    // A join still connecting, on a thread of its own so the window doesn't hang meanwhile
+    connecting: Option<Receiver<io::Result<TcpStream>>>,
+    stream: Option<TcpStream>,
+    received: Vec<u8>,
+    // What the socket wasn't ready to take yet, sent first next time
+    unsent: Vec<u8>,
+    pub is_host: bool,
+    pub seed: u64,
+    pub room_code: Option<String>,
+    pub rematch: Option<RematchOffer>,
+    // From their hello; none until it arrives
+    pub opponent: Option<Profile>,
+}
+
+// Where a rematch asked for after a match has got to
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RematchOffer {
+    // The host is waiting to hear back
+    Sent { seed: u64, rules: MatchRules },
+    // The guest hasn't answered yet
+    Received { seed: u64, rules: MatchRules },
+    Declined,
+}
+
+// The address other machines on the network can reach us at. Connecting a UDP
+// socket sends nothing, it only makes the OS pick the outgoing interface.
+fn local_ip() -> Ipv4Addr {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| socket.connect("8.8.8.8:80").map(|_| socket))
+        .and_then(|socket| socket.local_addr())
+        .ok()
+        .and_then(|addr| match addr {
+            SocketAddr::V4(addr) => Some(*addr.ip()),
+            SocketAddr::V6(_) => None,
+        })
+        .unwrap_or(Ipv4Addr::LOCALHOST)
+}
+
+fn room_code(addr: SocketAddrV4) -> String {
+    format!("{:012X}", (u64::from(u32::from(*addr.ip())) << 16) | u64::from(addr.port()))
+}
+
+// Accepts "ip:port", a bare IP (default port) or a room code
+fn parse_address(text: &str) -> Option<SocketAddr> {
+    let text = text.trim();
+    if let Ok(addr) = text.parse::<SocketAddr>() {
+        return Some(addr);
+    }
+    if let Ok(ip) = text.parse::<Ipv4Addr>() {
+        return Some(SocketAddr::V4(SocketAddrV4::new(ip, ONLINE_PORT)));
+    }
+    if text.len() == 12 {
+        let code = u64::from_str_radix(text, 16).ok()?;
+        let ip = Ipv4Addr::from((code >> 16) as u32);
+        return Some(SocketAddr::V4(SocketAddrV4::new(ip, code as u16)));
+    }
+    None
+}
+
+impl OnlineSession {
+    fn host() -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", ONLINE_PORT))?;
+        listener.set_nonblocking(true)?;
+        let port = listener.local_addr()?.port();
+
+        Ok(Self {
+            listener: Some(listener),
+            connecting: None,
+            stream: None,
+            received: Vec::new(),
+            unsent: Vec::new(),
+            is_host: true,
+            seed: 0,
+            room_code: Some(room_code(SocketAddrV4::new(local_ip(), port))),
+            rematch: None,
+            opponent: None,
+        })
+    }
+
+    fn join(address: &str) -> std::io::Result<Self> {
+        let addr = parse_address(address)
+            .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "not an address or room code"))?;
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+                .and_then(|stream| stream.set_nonblocking(true).map(|()| stream));
+            sender.send(stream).ok();
+        });
+
+        Ok(Self {
+            listener: None,
+            connecting: Some(receiver),
+            stream: None,
+            received: Vec::new(),
+            unsent: Vec::new(),
+            is_host: false,
+            seed: 0,
+            room_code: None,
+            rematch: None,
+            opponent: None,
+        })
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    pub fn is_connecting(&self) -> bool {
+        self.connecting.is_some()
+    }
+
+    pub fn send(&mut self, message: &Message) {
+        if self.stream.is_some() {
+            self.unsent.extend_from_slice(format!("{}\n", message.encode()).as_bytes());
+            self.flush();
+        }
+    }
+
+    // Writes as much of what's waiting as the socket takes now; a full socket isn't a lost connection
+    fn flush(&mut self) {
+        let Some(stream) = &mut self.stream else {
+            return;
+        };
+        while !self.unsent.is_empty() {
+            match stream.write(&self.unsent) {
+                Ok(0) => {
+                    self.stream = None;
+                    return;
+                }
+                Ok(written) => {
+                    self.unsent.drain(..written);
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return,
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(_) => {
+                    self.stream = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    // The join finishing, with the error if it couldn't connect
+    fn poll_connecting(&mut self) -> io::Result<()> {
+        let Some(connecting) = &self.connecting else {
+            return Ok(());
+        };
+        let stream = match connecting.try_recv() {
+            Ok(stream) => stream,
+            Err(TryRecvError::Empty) => return Ok(()),
+            Err(TryRecvError::Disconnected) => Err(io::Error::other("couldn't connect")),
+        };
+        self.connecting = None;
+        let stream = stream?;
+        stream.set_nodelay(true).ok();
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    // Everything that arrived since the last call. A dropped connection leaves the session disconnected.
+    fn poll(&mut self) -> Vec<Message> {
+        self.flush();
+        if let Some(listener) = &self.listener {
+            if let Ok((stream, _)) = listener.accept() {
+                if stream.set_nonblocking(true).is_ok() {
+                    stream.set_nodelay(true).ok();
+                    self.stream = Some(stream);
+                    self.listener = None;
+                }
+            }
+        }
+
+        let Some(stream) = &mut self.stream else {
+            return vec![];
+        };
+        let mut buffer = [0; 4096];
+        loop {
+            match stream.read(&mut buffer) {
+                Ok(0) => {
+                    self.stream = None;
+                    break;
+                }
+                Ok(read) => self.received.extend_from_slice(&buffer[..read]),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => {
+                    self.stream = None;
+                    break;
+                }
+            }
+        }
+
+        let mut messages = vec![];
+        while let Some(end) = self.received.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.received.drain(..=end).collect();
+            if let Some(message) = Message::decode(String::from_utf8_lossy(&line).trim_end()) {
+                messages.push(message);
+            }
+        }
+        messages
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn send_online(&mut self, message: Message) {
+        if let Some(session) = &mut self.online {
+            session.send(&message);
+        }
+    }
+
+    // Handles network traffic every frame, whatever screen is showing.
+    pub(crate) fn poll_online(&mut self) {
+        let Some(session) = &mut self.online else {
+            return;
+        };
+        let was_connected = session.is_connected();
+        if let Err(error) = session.poll_connecting() {
+            self.online = None;
+            self.online_error = Some(error.to_string());
+            return;
+        }
+        let messages = session.poll();
+        let connected = session.is_connected();
+        if connected && !was_connected {
+            self.send_online(Message::Hello(self.active_profile().clone()));
+        }
+
+        for message in messages {
+            match message {
+                Message::Seed { seed, rules } => self.start_online_round(seed, rules),
+                Message::NextRound(seed) if self.between_rounds() => {
+                    if let Some(session) = &mut self.online {
+                        session.seed = seed;
+                    }
+                    self.next_round();
+                }
+                Message::Rematch { seed, rules } => {
+                    if let Some(session) = &mut self.online {
+                        session.rematch = Some(RematchOffer::Received { seed, rules });
+                    }
+                }
+                Message::RematchAccepted => {
+                    if let Some(RematchOffer::Sent { seed, rules }) = self.online.as_ref().and_then(|session| session.rematch) {
+                        self.start_online_round(seed, rules);
+                    }
+                }
+                Message::RematchDeclined => {
+                    if let Some(session) = &mut self.online {
+                        session.rematch = Some(RematchOffer::Declined);
+                    }
+                }
+                Message::Hello(opponent) => {
+                    if let Some(session) = &mut self.online {
+                        session.opponent = Some(opponent);
+                    }
+                }
+                Message::Garbage(rows) if self.state == GameState::Playing => self.receive_garbage(rows),
+                Message::Board(grid, lines) => {
+                    if let Some(rival) = self.rivals.first_mut() {
+                        rival.show_remote(*grid, lines);
+                    }
+                }
+                Message::Lost if self.state == GameState::Playing => {
+                    if let Some(rival) = self.rivals.first_mut() {
+                        rival.topped_out = true;
+                    }
+                    self.end_run();
+                }
+                // The opponent won the cheese race
+                Message::DugOut if self.state == GameState::Playing => self.end_run(),
+                _ => {}
+            }
+        }
+
+        if was_connected && !connected {
+            self.online_error = Some(self.tr("Connection lost").to_string());
+            if self.state == GameState::Playing && self.mode == GameMode::Online {
+                self.end_run();
+            }
+        }
+    }
+
+    fn match_rules(&self) -> MatchRules {
+        MatchRules {
+            ruleset: self.ruleset,
+            best_of: self.best_of,
+        }
+    }
+
+    fn start_online_round(&mut self, seed: u64, rules: MatchRules) {
+        if let Some(session) = &mut self.online {
+            session.seed = seed;
+            session.rematch = None;
+        }
+        self.mode = GameMode::Online;
+        self.ruleset = rules.ruleset;
+        self.best_of = rules.best_of;
+        self.reset_game();
+    }
+
+    // The host picks the seed, so only the host can start a match
+    fn start_online_match(&mut self) {
+        let seed = rand::rng().random();
+        let rules = self.match_rules();
+        self.send_online(Message::Seed { seed, rules });
+        self.start_online_round(seed, rules);
+    }
+
+    // More than a board's worth can't be meant, and mustn't overflow
+    pub(crate) fn receive_garbage(&mut self, rows: usize) {
+        self.incoming_garbage = self.incoming_garbage.saturating_add(rows).min(GRID_HEIGHT);
+    }
+
+    // The host starts each round of the match too
+    pub(crate) fn start_next_online_round(&mut self) {
+        let seed = rand::rng().random();
+        self.send_online(Message::NextRound(seed));
+        if let Some(session) = &mut self.online {
+            session.seed = seed;
+        }
+        self.next_round();
+    }
+
+    // The results screen's Rematch for an online match: the host offers the rules now picked
+    // (so "Change rules" first changes them for both), and the guest accepts or declines
+    pub(crate) fn render_online_rematch(&mut self, ui: &mut egui::Ui) {
+        let Some(session) = self.online.as_ref().filter(|session| session.is_connected()) else {
+            ui.add_enabled(false, egui::Button::new(self.tr("Rematch")));
+            return;
+        };
+        match (session.is_host, session.rematch) {
+            (true, Some(RematchOffer::Sent { .. })) => {
+                ui.label(self.tr("Waiting for your opponent to answer..."));
+            }
+            (true, offer) => {
+                if offer == Some(RematchOffer::Declined) {
+                    ui.label(self.tr("Your opponent declined the rematch."));
+                }
+                if ui.button(self.tr("Rematch")).clicked() {
+                    let seed = rand::rng().random();
+                    let rules = self.match_rules();
+                    self.send_online(Message::Rematch { seed, rules });
+                    if let Some(session) = &mut self.online {
+                        session.rematch = Some(RematchOffer::Sent { seed, rules });
+                    }
+                }
+            }
+            (false, Some(RematchOffer::Received { seed, rules })) => {
+                ui.label(format!(
+                    "{} ({}, {} {})",
+                    self.tr("The host wants a rematch"),
+                    self.tr(rules.ruleset.label()),
+                    self.tr("best of"),
+                    rules.best_of
+                ));
+                if ui.button(self.tr("Accept")).clicked() {
+                    self.send_online(Message::RematchAccepted);
+                    self.start_online_round(seed, rules);
+                }
+                if ui.button(self.tr("Decline")).clicked() {
+                    self.send_online(Message::RematchDeclined);
+                    if let Some(session) = &mut self.online {
+                        session.rematch = None;
+                    }
+                }
+            }
+            (false, _) => {
+                ui.label(self.tr("Waiting for the host to offer a rematch..."));
+            }
+        }
+    }
+
+    pub(crate) fn opponent(&self) -> Option<&Profile> {
+        self.online.as_ref()?.opponent.as_ref()
+    }
+
+    fn render_opponent(&self, ui: &mut egui::Ui) {
+        if let Some(opponent) = self.opponent() {
+            profile::draw_player(ui, opponent);
+        }
+    }
+
+    pub(crate) fn render_online_lobby(&mut self, ui: &mut egui::Ui) {
+        if let Some(error) = &self.online_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        let Some(session) = &self.online else {
+            if ui.button(self.tr("Host game")).clicked() {
+                match OnlineSession::host() {
+                    Ok(session) => {
+                        self.online = Some(session);
+                        self.online_error = None;
+                    }
+                    Err(error) => self.online_error = Some(error.to_string()),
+                }
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(self.tr("Address or room code:"));
+                ui.text_edit_singleline(&mut self.online_address);
+            });
+            if ui.button(self.tr("Join game")).clicked() {
+                match OnlineSession::join(&self.online_address) {
+                    Ok(session) => {
+                        self.online = Some(session);
+                        self.online_error = None;
+                    }
+                    Err(error) => self.online_error = Some(error.to_string()),
+                }
+            }
+            return;
+        };
+
+        let connected = session.is_connected();
+        let connecting = session.is_connecting();
+        let is_host = session.is_host;
+        if let Some(code) = &session.room_code {
+            ui.label(format!("{} {}", self.tr("Room code:"), code));
+        }
+
+        match (connected, is_host) {
+            (false, true) => {
+                ui.label(self.tr("Waiting for an opponent to join..."));
+            }
+            (false, false) if connecting => {
+                ui.label(self.tr("Connecting..."));
+            }
+            (false, false) => {
+                ui.label(self.tr("Not connected"));
+            }
+            (true, true) => {
+                ui.label(self.tr("Opponent connected"));
+                self.render_opponent(ui);
+                if ui.button(self.tr("Start match")).clicked() {
+                    self.start_online_match();
+                }
+            }
+            (true, false) => {
+                ui.label(self.tr("Connected. Waiting for the host to start..."));
+                self.render_opponent(ui);
+            }
+        }
+
+        if ui.button(self.tr("Leave")).clicked() {
+            self.online = None;
+        }
+    }
+}
@@ -0,0 +1,100 @@
+// The pause menu, driven by mouse or by the arrow keys and Enter.
+
+use eframe::egui;
+
+use crate::{board_text, CrowsTetris, GameState};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum PauseItem {
+    Resume,
+    Restart,
+    Settings,
+    CopyBoard,
+    SaveAndQuit,
+    Quit,
+}
+
+impl PauseItem {
+    const ALL: [PauseItem; 6] = [
+        PauseItem::Resume,
+        PauseItem::Restart,
+        PauseItem::Settings,
+        PauseItem::CopyBoard,
+        PauseItem::SaveAndQuit,
+        PauseItem::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PauseItem::Resume => "Resume",
+            PauseItem::Restart => "Restart",
+            PauseItem::Settings => "Settings",
+            PauseItem::CopyBoard => "Copy board",
+            PauseItem::SaveAndQuit => "Save & Quit",
+            PauseItem::Quit => "Quit to menu",
+        }
+    }
+}
+
+impl CrowsTetris {
+    fn pause_item_enabled(&self, item: PauseItem) -> bool {
+        match item {
+            // Runs that can't be started again (the daily, online matches) can't be restarted either
+            PauseItem::Restart => self.can_start(),
+            PauseItem::SaveAndQuit => self.can_suspend() && !self.watching_replay(),
+            _ => true,
+        }
+    }
+
+    fn activate_pause_item(&mut self, ctx: &egui::Context, item: PauseItem) {
+        match item {
+            PauseItem::Resume => self.set_paused(false),
+            PauseItem::Restart => self.reset_game(),
+            PauseItem::Settings => self.open_settings(),
+            PauseItem::CopyBoard => ctx.copy_text(board_text::board_to_ascii(&self.grid)),
+            PauseItem::SaveAndQuit => self.suspend_run(),
+            PauseItem::Quit if self.watching_replay() => self.close_replay(),
+            PauseItem::Quit => {
+                self.end_run();
+                self.state = GameState::StartScreen;
+            }
+        }
+    }
+
+    pub(crate) fn render_pause_menu(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+
+        let count = PauseItem::ALL.len();
+        ctx.input(|input| {
+            if input.key_pressed(egui::Key::ArrowDown) {
+                self.pause_selection = (self.pause_selection + 1) % count;
+            }
+            if input.key_pressed(egui::Key::ArrowUp) {
+                self.pause_selection = (self.pause_selection + count - 1) % count;
+            }
+        });
+
+        let mut activated = None;
+        ui.vertical_centered(|ui| {
+            ui.add_space(40.0);
+            ui.heading(self.tr("Game Paused"));
+            ui.add_space(20.0);
+            for (i, item) in PauseItem::ALL.into_iter().enumerate() {
+                let button = egui::Button::new(self.tr(item.label()))
+                    .selected(i == self.pause_selection)
+                    .min_size(egui::vec2(160.0, 0.0));
+                if ui.add_enabled(self.pause_item_enabled(item), button).clicked() {
+                    activated = Some(item);
+                }
+            }
+        });
+
+        let selected = PauseItem::ALL[self.pause_selection];
+        if ctx.input(|input| input.key_pressed(egui::Key::Enter)) && self.pause_item_enabled(selected) {
+            activated = Some(selected);
+        }
+        if let Some(item) = activated {
+            self.activate_pause_item(&ctx, item);
+        }
+    }
+}
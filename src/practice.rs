@@ -0,0 +1,109 @@
+// Practice mode: a sandbox for drilling setups. The player can pick the next
+// piece, turn gravity off and drop pieces by hand, and save board snapshots
+// to jump back to.
+
+use eframe::egui;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::{AgeGrid, Block, BlockType, CrowsTetris, Grid};
+
+const MAX_SNAPSHOTS: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    grid: Grid,
+    locked_at: AgeGrid,
+    active_block: Option<Block>,
+    next_piece: Option<BlockType>,
+    next_queue: VecDeque<BlockType>,
+    held_piece: Option<BlockType>,
+    hold_used: bool,
+    score: i32,
+    lines: u32,
+}
+
+impl CrowsTetris {
+    fn save_snapshot(&mut self) {
+        if self.snapshots.len() >= MAX_SNAPSHOTS {
+            self.snapshots.remove(0);
+        }
+        self.snapshots.push(Snapshot {
+            grid: self.grid,
+            locked_at: self.locked_at,
+            active_block: self.active_block.clone(),
+            next_piece: self.next_piece,
+            next_queue: self.next_queue.clone(),
+            held_piece: self.held_piece,
+            hold_used: self.hold_used,
+            score: self.score,
+            lines: self.lines,
+        });
+    }
+
+    fn restore_snapshot(&mut self, index: usize) {
+        let snapshot = self.snapshots[index].clone();
+        self.grid = snapshot.grid;
+        self.locked_at = snapshot.locked_at;
+        self.active_block = snapshot.active_block;
+        self.next_piece = snapshot.next_piece;
+        self.next_queue = snapshot.next_queue;
+        self.held_piece = snapshot.held_piece;
+        self.hold_used = snapshot.hold_used;
+        self.score = snapshot.score;
+        self.lines = snapshot.lines;
+        self.landed_at = None;
+        self.last_update = Instant::now();
+    }
+
+    // With gravity off the piece only moves down when asked, and locks when asked again on the floor
+    pub(crate) fn practice_drop(&mut self) {
+        let grounded = self
+            .active_block
+            .as_ref()
+            .is_some_and(|block| self.check_collision_with_position((block.position.0, block.position.1 + 1)));
+
+        if grounded {
+            self.lock_and_spawn();
+        } else {
+            self.move_block_down();
+        }
+    }
+
+    pub(crate) fn render_practice_tools(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(self.tr("Practice tools")).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let random = self.tr("Random");
+                egui::ComboBox::from_label(self.tr("Next piece"))
+                    .selected_text(self.next_piece.map_or(random.to_string(), |piece| piece.letter().to_string()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.next_piece, None, random);
+                        for piece in BlockType::ALL {
+                            ui.selectable_value(&mut self.next_piece, Some(piece), piece.letter().to_string());
+                        }
+                    });
+
+                let label = self.tr("Gravity");
+                if ui.checkbox(&mut self.practice_gravity, label).changed() {
+                    self.last_update = Instant::now();
+                    self.landed_at = None;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button(self.tr("Save snapshot")).clicked() {
+                    self.save_snapshot();
+                }
+                let mut restore = None;
+                for i in 0..self.snapshots.len() {
+                    if ui.button(format!("{} {}", self.tr("Restore"), i + 1)).clicked() {
+                        restore = Some(i);
+                    }
+                }
+                if let Some(index) = restore {
+                    self.restore_snapshot(index);
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,270 @@
+// Player profiles: a name plus a customizable crow avatar.
+
+use eframe::egui;
+use std::fmt::Write;
+use std::fs;
+use std::io::{self, BufRead};
+
+use crate::data_dir;
+use crate::file_errors::Retry;
+
+pub const PROFILES_FILE: &str = "profiles.txt";
+const PLAYER_CROW_SIZE: f32 = 32.0;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CrowColor {
+    Black,
+    Blue,
+    White,
+    Red,
+    Green,
+}
+
+impl CrowColor {
+    pub const ALL: [CrowColor; 5] = [
+        CrowColor::Black,
+        CrowColor::Blue,
+        CrowColor::White,
+        CrowColor::Red,
+        CrowColor::Green,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CrowColor::Black => "Black",
+            CrowColor::Blue => "Blue",
+            CrowColor::White => "White",
+            CrowColor::Red => "Red",
+            CrowColor::Green => "Green",
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            CrowColor::Black => egui::Color32::from_rgb(30, 30, 40),
+            CrowColor::Blue => egui::Color32::from_rgb(40, 60, 140),
+            CrowColor::White => egui::Color32::from_rgb(230, 230, 235),
+            CrowColor::Red => egui::Color32::from_rgb(150, 30, 30),
+            CrowColor::Green => egui::Color32::from_rgb(30, 110, 60),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Accessory {
+    None,
+    TopHat,
+    Crown,
+    Scarf,
+    Monocle,
+}
+
+impl Accessory {
+    pub const ALL: [Accessory; 5] = [
+        Accessory::None,
+        Accessory::TopHat,
+        Accessory::Crown,
+        Accessory::Scarf,
+        Accessory::Monocle,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Accessory::None => "None",
+            Accessory::TopHat => "Top hat",
+            Accessory::Crown => "Crown",
+            Accessory::Scarf => "Scarf",
+            Accessory::Monocle => "Monocle",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CrowAvatar {
+    pub color: CrowColor,
+    pub accessory: Accessory,
+}
+
+impl Default for CrowAvatar {
+    fn default() -> Self {
+        Self {
+            color: CrowColor::Black,
+            accessory: Accessory::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub avatar: CrowAvatar,
+}
+
+fn find_by_label<T: Copy>(all: &[T], label: impl Fn(T) -> &'static str, text: &str) -> Option<T> {
+    all.iter().copied().find(|&item| label(item) == text)
+}
+
+pub fn load_profiles() -> Vec<Profile> {
+    let mut profiles: Vec<Profile> = if let Ok(file) = fs::File::open(data_dir::path(PROFILES_FILE)) {
+        io::BufReader::new(file)
+            .lines()
+            .filter_map(|line| {
+                let line = line.ok()?;
+                let parts: Vec<&str> = line.split(',').collect();
+                if parts.len() != 3 || parts[0].is_empty() {
+                    return None;
+                }
+                Some(Profile {
+                    name: parts[0].to_string(),
+                    avatar: CrowAvatar {
+                        color: find_by_label(&CrowColor::ALL, CrowColor::label, parts[1])?,
+                        accessory: find_by_label(&Accessory::ALL, Accessory::label, parts[2])?,
+                    },
+                })
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+
+    if profiles.is_empty() {
+        profiles.push(Profile {
+            name: "Player".to_string(),
+            avatar: CrowAvatar::default(),
+        });
+    }
+    profiles
+}
+
+pub fn save_profiles(profiles: &[Profile]) -> io::Result<()> {
+    let mut text = String::new();
+    for profile in profiles {
+        writeln!(text, "{},{},{}", profile.name, profile.avatar.color.label(), profile.avatar.accessory.label()).ok();
+    }
+    data_dir::write_atomic(PROFILES_FILE, &text)
+}
+
+pub fn draw_crow(ui: &mut egui::Ui, avatar: CrowAvatar, size: f32) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let unit = size / 10.0;
+    let at = |x: f32, y: f32| rect.min + egui::vec2(x * unit, y * unit);
+    let feathers = avatar.color.color();
+
+    // Body, head, beak and eye
+    painter.circle_filled(at(4.5, 6.5), 3.0 * unit, feathers);
+    painter.circle_filled(at(6.5, 3.8), 2.0 * unit, feathers);
+    painter.add(egui::Shape::convex_polygon(
+        vec![at(8.2, 3.3), at(9.8, 4.0), at(8.2, 4.6)],
+        egui::Color32::from_rgb(240, 180, 40),
+        egui::Stroke::NONE,
+    ));
+    painter.circle_filled(at(7.1, 3.4), 0.4 * unit, egui::Color32::WHITE);
+
+    match avatar.accessory {
+        Accessory::None => {}
+        Accessory::TopHat => {
+            let brim = egui::Rect::from_min_max(at(5.0, 1.8), at(8.2, 2.2));
+            let crown = egui::Rect::from_min_max(at(5.6, 0.2), at(7.6, 1.9));
+            painter.rect_filled(brim, 0.0, egui::Color32::from_gray(15));
+            painter.rect_filled(crown, 0.0, egui::Color32::from_gray(15));
+        }
+        Accessory::Crown => {
+            painter.add(egui::Shape::convex_polygon(
+                vec![at(5.2, 2.2), at(5.2, 0.8), at(6.5, 1.6), at(7.8, 0.8), at(7.8, 2.2)],
+                egui::Color32::GOLD,
+                egui::Stroke::NONE,
+            ));
+        }
+        Accessory::Scarf => {
+            painter.line_segment([at(4.8, 5.4), at(8.0, 5.4)], egui::Stroke::new(unit, egui::Color32::RED));
+            painter.line_segment([at(5.2, 5.4), at(4.6, 7.2)], egui::Stroke::new(unit * 0.8, egui::Color32::RED));
+        }
+        Accessory::Monocle => {
+            painter.circle_stroke(at(7.1, 3.4), 0.8 * unit, egui::Stroke::new(unit * 0.25, egui::Color32::GOLD));
+        }
+    }
+}
+
+// A crow with its player's name under it, next to a board in versus and online
+pub fn draw_player(ui: &mut egui::Ui, profile: &Profile) {
+    ui.vertical(|ui| {
+        draw_crow(ui, profile.avatar, PLAYER_CROW_SIZE);
+        ui.label(&profile.name);
+    });
+}
+
+impl crate::CrowsTetris {
+    pub(crate) fn active_profile(&self) -> &Profile {
+        &self.profiles[self.active_profile]
+    }
+
+    // Who plays the second board in local versus; nobody picked shows no crow
+    pub(crate) fn player_two_profile(&self) -> Option<&Profile> {
+        self.player_two_profile.and_then(|i| self.profiles.get(i))
+    }
+
+    pub(crate) fn render_player_two_picker(&mut self, ui: &mut egui::Ui) {
+        let selected = self.player_two_profile().map_or(self.tr("Nobody"), |profile| profile.name.as_str()).to_string();
+        let language = self.language;
+        egui::ComboBox::from_label(language.tr("Player 2 profile"))
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.player_two_profile, None, language.tr("Nobody"));
+                for (i, profile) in self.profiles.iter().enumerate() {
+                    ui.selectable_value(&mut self.player_two_profile, Some(i), profile.name.as_str());
+                }
+            });
+    }
+
+    pub(crate) fn render_profile_editor(&mut self, ui: &mut egui::Ui) {
+        let before = self.active_profile().avatar;
+
+        egui::ComboBox::from_label(self.tr("Profile"))
+            .selected_text(self.active_profile().name.clone())
+            .show_ui(ui, |ui| {
+                for (i, profile) in self.profiles.iter().enumerate() {
+                    ui.selectable_value(&mut self.active_profile, i, profile.name.as_str());
+                }
+            });
+
+        let language = self.language;
+        let avatar = &mut self.profiles[self.active_profile].avatar;
+        egui::ComboBox::from_label(language.tr("Crow color"))
+            .selected_text(language.tr(avatar.color.label()))
+            .show_ui(ui, |ui| {
+                for color in CrowColor::ALL {
+                    ui.selectable_value(&mut avatar.color, color, language.tr(color.label()));
+                }
+            });
+        egui::ComboBox::from_label(language.tr("Accessory"))
+            .selected_text(language.tr(avatar.accessory.label()))
+            .show_ui(ui, |ui| {
+                for accessory in Accessory::ALL {
+                    ui.selectable_value(&mut avatar.accessory, accessory, language.tr(accessory.label()));
+                }
+            });
+
+        if self.active_profile().avatar != before {
+            self.check_saved(save_profiles(&self.profiles), Retry::SaveProfiles);
+        }
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.new_profile_name);
+            // Commas would break the profiles file
+            let name = self.new_profile_name.trim().replace(',', "");
+            if ui.button(self.tr("Add profile")).clicked()
+                && !name.is_empty()
+                && !self.profiles.iter().any(|profile| profile.name == name)
+            {
+                self.profiles.push(Profile {
+                    name,
+                    avatar: CrowAvatar::default(),
+                });
+                self.active_profile = self.profiles.len() - 1;
+                self.new_profile_name.clear();
+                self.check_saved(save_profiles(&self.profiles), Retry::SaveProfiles);
+            }
+        });
+    }
+}
@@ -0,0 +1,64 @@
+// Piece randomizers. Each one turns an RNG into a stream of block types.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::BlockType;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Randomizer {
+    Memoryless,
+    SevenBag,
+}
+
+impl Randomizer {
+    pub const ALL: [Randomizer; 2] = [Randomizer::Memoryless, Randomizer::SevenBag];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Randomizer::Memoryless => "Classic (memoryless)",
+            Randomizer::SevenBag => "7-bag",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PieceGenerator {
+    randomizer: Randomizer,
+    bag: Vec<BlockType>,
+    // Pieces handed out so far, enough to replay the sequence from its seed
+    drawn: usize,
+}
+
+impl PieceGenerator {
+    pub fn new(randomizer: Randomizer) -> Self {
+        Self {
+            randomizer,
+            bag: Vec::new(),
+            drawn: 0,
+        }
+    }
+
+    pub fn next(&mut self, rng: &mut impl Rng) -> BlockType {
+        self.drawn += 1;
+        match self.randomizer {
+            Randomizer::Memoryless => BlockType::ALL[rng.random_range(0..BlockType::ALL.len())],
+            Randomizer::SevenBag => {
+                if self.bag.is_empty() {
+                    self.bag = BlockType::ALL.to_vec();
+                    self.bag.shuffle(rng);
+                }
+                self.bag.pop().unwrap()
+            }
+        }
+    }
+
+    // Pieces still to come from the current bag, empty for memoryless
+    pub fn bag(&self) -> &[BlockType] {
+        &self.bag
+    }
+
+    pub fn drawn(&self) -> usize {
+        self.drawn
+    }
+}
@@ -0,0 +1,287 @@
+// Replays. Solo runs log everything that moved the piece and when: each
+// input the game applied, each gravity step and each lock. Together with the
+// run's seed and starting board that plays the run back exactly, because the
+// timing that decided those steps (gravity, lock delay) is in the log instead
+// of being worked out again. Every run is kept in replays/last.toml and each
+// mode's personal best in replays/best_<mode>.toml; any replay file copied
+// into the replays folder can be watched from the start screen.
+
+use eframe::egui;
+use std::fs;
+use std::io;
+use std::time::Duration;
+use toml_edit::{value, DocumentMut};
+
+use crate::board_text::{board_from_ascii, board_to_ascii};
+use crate::classic::Ruleset;
+use crate::controls::Action;
+use crate::file_errors::Retry;
+use crate::high_scores::{self, leaderboard, ranking, Ranking};
+use crate::randomizer::Randomizer;
+use crate::settings::find_by_name;
+use crate::{data_dir, CrowsTetris, GameMode, RunResult};
+
+pub const REPLAYS_DIR: &str = "replays";
+const LAST_REPLAY: &str = "last.toml";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ReplayEvent {
+    Input(Action),
+    Gravity,
+    Lock,
+}
+
+impl ReplayEvent {
+    const ALL: [ReplayEvent; 8] = [
+        ReplayEvent::Input(Action::MoveLeft),
+        ReplayEvent::Input(Action::MoveRight),
+        ReplayEvent::Input(Action::Rotate),
+        ReplayEvent::Input(Action::RotateCounterclockwise),
+        ReplayEvent::Input(Action::Hold),
+        ReplayEvent::Input(Action::HardDrop),
+        ReplayEvent::Gravity,
+        ReplayEvent::Lock,
+    ];
+
+    // One letter per event keeps a whole run's log on a line
+    fn code(self) -> char {
+        match self {
+            ReplayEvent::Input(Action::MoveLeft) => 'L',
+            ReplayEvent::Input(Action::MoveRight) => 'R',
+            ReplayEvent::Input(Action::Rotate) => 'C',
+            ReplayEvent::Input(Action::RotateCounterclockwise) => 'W',
+            ReplayEvent::Input(Action::Hold) => 'H',
+            ReplayEvent::Input(Action::HardDrop) => 'D',
+            ReplayEvent::Input(_) => '?',
+            ReplayEvent::Gravity => 'g',
+            ReplayEvent::Lock => 'k',
+        }
+    }
+
+    fn from_code(code: char) -> Option<ReplayEvent> {
+        ReplayEvent::ALL.into_iter().find(|event| event.code() == code)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub mode: GameMode,
+    pub ruleset: Ruleset,
+    pub randomizer: Randomizer,
+    pub start_level: u32,
+    pub seed: u64,
+    pub board: String,
+    pub player: String,
+    pub score: i32,
+    pub lines: u32,
+    pub duration: Duration,
+    pub timestamp: u64,
+    // Play time of each event, in the order they happened
+    pub events: Vec<(Duration, ReplayEvent)>,
+}
+
+// Each event as the milliseconds since the one before followed by its letter, e.g. "1200R40R310D"
+pub fn encode_events(events: &[(Duration, ReplayEvent)]) -> String {
+    let mut previous = 0;
+    let mut text = String::new();
+    for &(time, event) in events {
+        let ms = time.as_millis() as u64;
+        text.push_str(&(ms - previous.min(ms)).to_string());
+        text.push(event.code());
+        previous = ms;
+    }
+    text
+}
+
+pub fn decode_events(text: &str) -> Result<Vec<(Duration, ReplayEvent)>, String> {
+    let mut events = Vec::new();
+    let (mut time, mut delta) = (0u64, String::new());
+    for code in text.chars() {
+        if code.is_ascii_digit() {
+            delta.push(code);
+            continue;
+        }
+        let event = ReplayEvent::from_code(code).ok_or_else(|| format!("unknown event '{}'", code))?;
+        time += delta.parse::<u64>().unwrap_or(0);
+        delta.clear();
+        events.push((Duration::from_millis(time), event));
+    }
+    Ok(events)
+}
+
+impl Replay {
+    pub fn to_toml(&self) -> String {
+        let mut document = DocumentMut::new();
+        document["mode"] = value(format!("{:?}", self.mode));
+        document["ruleset"] = value(format!("{:?}", self.ruleset));
+        document["randomizer"] = value(format!("{:?}", self.randomizer));
+        document["start_level"] = value(self.start_level as i64);
+        // Seeds use all 64 bits, more than a TOML integer holds
+        document["seed"] = value(self.seed.to_string());
+        document["board"] = value(self.board.as_str());
+        document["player"] = value(self.player.as_str());
+        document["score"] = value(self.score as i64);
+        document["lines"] = value(self.lines as i64);
+        document["duration_ms"] = value(self.duration.as_millis() as i64);
+        document["timestamp"] = value(self.timestamp as i64);
+        document["events"] = value(encode_events(&self.events));
+        document.to_string()
+    }
+
+    pub fn from_toml(text: &str) -> Result<Replay, String> {
+        let document = text.parse::<DocumentMut>().map_err(|error| error.to_string())?;
+        let text = |key| document.get(key).and_then(|item| item.as_str()).ok_or(format!("missing {}", key));
+        let number = |key| document.get(key).and_then(|item| item.as_integer()).unwrap_or(0);
+        let board = text("board")?.to_string();
+        board_from_ascii(&board)?;
+        Ok(Replay {
+            mode: find_by_name(&GameMode::ALL, text("mode")?).ok_or("unknown mode")?,
+            ruleset: find_by_name(&Ruleset::ALL, text("ruleset")?).ok_or("unknown ruleset")?,
+            randomizer: find_by_name(&Randomizer::ALL, text("randomizer")?).ok_or("unknown randomizer")?,
+            start_level: number("start_level") as u32,
+            seed: text("seed")?.parse().map_err(|_| "broken seed".to_string())?,
+            board,
+            player: text("player").unwrap_or_default().to_string(),
+            score: number("score") as i32,
+            lines: number("lines") as u32,
+            duration: Duration::from_millis(number("duration_ms") as u64),
+            timestamp: number("timestamp") as u64,
+            events: decode_events(text("events")?)?,
+        })
+    }
+}
+
+// Modes whose runs are just the player's board: no opponents, no random items or missions
+pub fn replayable(mode: GameMode) -> bool {
+    !matches!(
+        mode,
+        GameMode::Battle
+            | GameMode::Versus
+            | GameMode::Online
+            | GameMode::Royale
+            | GameMode::Daily
+            | GameMode::Mission
+            | GameMode::Party
+            | GameMode::Practice
+    )
+}
+
+fn best_replay_file(mode: GameMode) -> String {
+    format!("best_{}.toml", format!("{:?}", mode).to_lowercase())
+}
+
+fn save_replay(file: &str, replay: &Replay) -> io::Result<()> {
+    fs::create_dir_all(data_dir::path(REPLAYS_DIR))?;
+    data_dir::write_atomic(&format!("{}/{}", REPLAYS_DIR, file), &replay.to_toml())
+}
+
+pub fn load_replay(file: &str) -> Result<Replay, String> {
+    let text = fs::read_to_string(data_dir::path(REPLAYS_DIR).join(file)).map_err(|error| format!("{}: {}", file, error))?;
+    Replay::from_toml(&text).map_err(|error| format!("{}: {}", file, error))
+}
+
+// The replay files in the replays folder
+pub fn replay_files() -> Vec<String> {
+    let mut files: Vec<String> = fs::read_dir(data_dir::path(REPLAYS_DIR))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| {
+                    let name = entry.ok()?.file_name().to_string_lossy().into_owned();
+                    name.ends_with(".toml").then_some(name)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+impl CrowsTetris {
+    // Called for everything that changes the falling piece; does nothing while watching
+    pub(crate) fn record_replay(&mut self, event: ReplayEvent) {
+        // Whole milliseconds, as the file keeps them
+        let time = Duration::from_millis(self.play_time().as_millis() as u64);
+        // Nothing has happened to the board before the first event, so it's the starting board
+        if self.replay_log.as_ref().is_some_and(Vec::is_empty) {
+            self.replay_board = board_to_ascii(&self.grid);
+        }
+        if let Some(log) = &mut self.replay_log {
+            log.push((time, event));
+        }
+    }
+
+    // Whether the run beats the player's own best on its mode's table
+    fn personal_best(&self, result: &RunResult) -> bool {
+        let player = &self.active_profile().name;
+        let entries = leaderboard(&self.high_scores, result.mode);
+        let best = entries.iter().find(|entry| &entry.name == player);
+        match ranking(result.mode) {
+            Ranking::Score => result.score > best.map_or(0, |entry| entry.score),
+            Ranking::Time => result.completed && best.is_none_or(|entry| result.duration < entry.duration),
+        }
+    }
+
+    // Keeps the replay of the run that just ended, before its score goes on the table
+    pub(crate) fn save_run_replay(&mut self) {
+        self.last_replay = None;
+        let (Some(events), Some(result)) = (self.replay_log.take(), self.last_result.as_ref()) else {
+            return;
+        };
+        if events.is_empty() || !replayable(result.mode) {
+            return;
+        }
+        let replay = Replay {
+            mode: result.mode,
+            ruleset: self.ruleset,
+            randomizer: self.randomizer,
+            start_level: self.start_level,
+            seed: result.seed,
+            board: self.replay_board.clone(),
+            player: self.active_profile().name.clone(),
+            score: result.score,
+            lines: result.lines,
+            duration: Duration::from_millis(result.duration.as_millis() as u64),
+            timestamp: high_scores::now(),
+            events,
+        };
+        let best = self.personal_best(result).then(|| best_replay_file(result.mode));
+        self.last_replay = Some(replay);
+        self.save_last_replay(LAST_REPLAY.to_string());
+        if let Some(best) = best {
+            self.save_last_replay(best);
+        }
+    }
+
+    pub(crate) fn save_last_replay(&mut self, file: String) {
+        if let Some(replay) = &self.last_replay {
+            let saved = save_replay(&file, replay);
+            self.check_saved(saved, Retry::SaveReplay(file));
+        }
+    }
+
+    pub(crate) fn render_replay_list(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(self.tr("Replays")).show(ui, |ui| {
+            let files = replay_files();
+            if files.is_empty() {
+                ui.label(self.tr("No replays yet"));
+            }
+            for file in files {
+                ui.horizontal(|ui| {
+                    ui.label(file.trim_end_matches(".toml"));
+                    if ui.small_button(self.tr("Watch")).clicked() {
+                        match load_replay(&file) {
+                            Ok(replay) => {
+                                self.replay_error = None;
+                                self.watch_replay(replay);
+                            }
+                            Err(error) => self.replay_error = Some(error),
+                        }
+                    }
+                });
+            }
+            if let Some(error) = &self.replay_error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+    }
+}
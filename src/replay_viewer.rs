@@ -0,0 +1,216 @@
+// The replay viewer. A replay is watched on the game screen with a bar of
+// its own: play and pause, a playback speed, and a scrub bar. The board is
+// always rebuilt by playing the replay's events from its seed, so scrubbing
+// back starts over from the beginning and quietly plays up to the new spot.
+
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use crate::board_text::board_from_ascii;
+use crate::controls::Action;
+use crate::mission::Mission;
+use crate::randomizer::PieceGenerator;
+use crate::replay::{Replay, ReplayEvent};
+use crate::stats::PieceTally;
+use crate::{format_time, CrowsTetris, GameState};
+
+const SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+// A replay being watched: the next of its events to play and the viewer's own clock
+pub struct Playback {
+    replay: Replay,
+    next: usize,
+    position: Duration,
+    speed: f32,
+    paused: bool,
+    ticked: Instant,
+}
+
+impl Playback {
+    // Stands in for the play time while watching, so timers and time limits follow the scrub bar
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn watching_replay(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    pub(crate) fn watch_replay(&mut self, replay: Replay) {
+        if board_from_ascii(&replay.board).is_err() {
+            return;
+        }
+        self.playback = Some(Playback {
+            replay,
+            next: 0,
+            position: Duration::ZERO,
+            speed: 1.0,
+            paused: false,
+            ticked: Instant::now(),
+        });
+        self.rewind_replay();
+        self.start_countdown();
+    }
+
+    pub(crate) fn close_replay(&mut self) {
+        self.playback = None;
+        self.state = GameState::StartScreen;
+    }
+
+    // Puts the board back to how the replay starts
+    fn rewind_replay(&mut self) {
+        let Some(playback) = self.playback.take() else {
+            return;
+        };
+        let replay = &playback.replay;
+        self.mode = replay.mode;
+        self.ruleset = replay.ruleset;
+        self.randomizer = replay.randomizer;
+        self.start_level = replay.start_level;
+        self.reset_game();
+        self.countdown_until = None;
+        self.replay_log = None;
+        self.seed_run(replay.seed);
+        self.mission = Mission::random(None, &mut self.game_rng);
+        self.piece_generator = PieceGenerator::new(replay.randomizer);
+        self.next_queue.clear();
+        self.piece_tally = PieceTally::default();
+        self.grid = board_from_ascii(&replay.board).unwrap_or(self.grid);
+        self.active_block = Some(self.generate_random_block());
+        self.playback = Some(Playback {
+            next: 0,
+            position: Duration::ZERO,
+            ..playback
+        });
+    }
+
+    fn apply_replay_event(&mut self, event: ReplayEvent) {
+        match event {
+            ReplayEvent::Input(Action::MoveLeft) => {
+                self.shift_piece(-1);
+            }
+            ReplayEvent::Input(Action::MoveRight) => {
+                self.shift_piece(1);
+            }
+            ReplayEvent::Input(Action::Rotate) => self.rotate_block(true),
+            ReplayEvent::Input(Action::RotateCounterclockwise) => self.rotate_block(false),
+            ReplayEvent::Input(Action::Hold) => self.hold_piece(),
+            ReplayEvent::Input(Action::HardDrop) => self.hard_drop(),
+            ReplayEvent::Input(_) => {}
+            ReplayEvent::Gravity => self.move_block_down(),
+            ReplayEvent::Lock => self.lock_and_spawn(),
+        }
+    }
+
+    // Plays the events up to a point in the replay, in the order they were recorded
+    fn play_replay_until(&mut self, target: Duration) {
+        loop {
+            let Some(playback) = &mut self.playback else {
+                return;
+            };
+            match playback.replay.events.get(playback.next) {
+                Some(&(time, event)) if time <= target => {
+                    playback.next += 1;
+                    playback.position = time;
+                    self.apply_replay_event(event);
+                }
+                _ => {
+                    playback.position = target;
+                    // Stops on the last moment rather than leaving, so it can be scrubbed back
+                    if playback.next == playback.replay.events.len() && target >= playback.replay.duration {
+                        playback.paused = true;
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    // Moves the replay along by the time since the last frame, at the chosen speed
+    pub(crate) fn step_playback(&mut self) {
+        let counting_down = self.counting_down();
+        let Some(playback) = &mut self.playback else {
+            return;
+        };
+        let elapsed = playback.ticked.elapsed();
+        playback.ticked = Instant::now();
+        if playback.paused || counting_down {
+            return;
+        }
+        let target = (playback.position + elapsed.mul_f32(playback.speed)).min(playback.replay.duration);
+        self.play_replay_until(target);
+    }
+
+    pub(crate) fn seek_replay(&mut self, target: Duration) {
+        if self.playback.as_ref().is_some_and(|playback| target < playback.position) {
+            self.rewind_replay();
+        }
+        self.countdown_until = None;
+        self.audio.muted = true;
+        self.play_replay_until(target);
+        self.audio.muted = false;
+        // Whatever was animating belongs to the part that was skipped
+        self.line_clear = None;
+        self.effects.clear();
+        self.shake = None;
+        self.level_up = None;
+    }
+
+    pub(crate) fn toggle_replay_pause(&mut self) {
+        let Some(playback) = &mut self.playback else {
+            return;
+        };
+        playback.paused = !playback.paused;
+        // Playing again from the end starts over
+        if !playback.paused && playback.position >= playback.replay.duration {
+            self.seek_replay(Duration::ZERO);
+        }
+    }
+
+    pub(crate) fn render_replay_controls(&mut self, ui: &mut egui::Ui) {
+        let language = self.language;
+        let Some(playback) = &mut self.playback else {
+            return;
+        };
+        let replay = &playback.replay;
+        ui.strong(format!("{} · {} · {}", language.tr("Replay"), replay.player, language.tr(replay.mode.label())));
+
+        let mut toggled = false;
+        let mut seek = None;
+        let mut close = false;
+        ui.horizontal(|ui| {
+            toggled = ui.button(if playback.paused { "▶" } else { "⏸" }).clicked();
+            egui::ComboBox::from_id_salt("replay_speed")
+                .width(60.0)
+                .selected_text(format!("{}×", playback.speed))
+                .show_ui(ui, |ui| {
+                    for speed in SPEEDS {
+                        ui.selectable_value(&mut playback.speed, speed, format!("{}×", speed));
+                    }
+                });
+            let mut seconds = playback.position.as_secs_f32();
+            let scrub = egui::Slider::new(&mut seconds, 0.0..=playback.replay.duration.as_secs_f32()).show_value(false);
+            if ui.add(scrub).changed() {
+                seek = Some(Duration::from_secs_f32(seconds));
+            }
+            ui.monospace(format!("{} / {}", format_time(playback.position), format_time(playback.replay.duration)));
+            close = ui.button(language.tr("Close")).clicked();
+        });
+
+        if toggled {
+            self.toggle_replay_pause();
+        }
+        if let Some(target) = seek {
+            self.seek_replay(target);
+        }
+        if close {
+            self.close_replay();
+        }
+    }
+}
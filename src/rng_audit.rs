@@ -0,0 +1,128 @@
+// Hidden diagnostics screen (F12 on the start screen) that samples every
+// randomizer and charts how evenly the pieces come out.
+
+use eframe::egui;
+
+use crate::randomizer::{PieceGenerator, Randomizer};
+use crate::{BlockType, CrowsTetris, GameState};
+
+const AUDIT_PIECES: usize = 10_000;
+const MAX_CHARTED_INTERVAL: usize = 30;
+
+pub struct AuditReport {
+    randomizer: Randomizer,
+    counts: [usize; 7],
+    // interval_histogram[n] counts repeats of a piece type exactly n pieces apart;
+    // the last bucket collects everything longer.
+    interval_histogram: Vec<usize>,
+    longest_drought: [usize; 7],
+    mean_interval: f64,
+}
+
+impl AuditReport {
+    fn run(randomizer: Randomizer) -> Self {
+        let mut generator = PieceGenerator::new(randomizer);
+        let mut rng = rand::rng();
+        let mut counts = [0; 7];
+        let mut interval_histogram = vec![0; MAX_CHARTED_INTERVAL + 1];
+        let mut longest_drought = [0; 7];
+        let mut last_seen: [Option<usize>; 7] = [None; 7];
+        let mut interval_sum = 0;
+        let mut interval_count = 0;
+
+        for i in 0..AUDIT_PIECES {
+            let piece = generator.next(&mut rng) as usize;
+            counts[piece] += 1;
+
+            if let Some(previous) = last_seen[piece] {
+                let interval = i - previous;
+                interval_histogram[interval.min(MAX_CHARTED_INTERVAL)] += 1;
+                interval_sum += interval;
+                interval_count += 1;
+            }
+            last_seen[piece] = Some(i);
+
+            for (kind, seen) in last_seen.iter().enumerate() {
+                let drought = seen.map_or(i + 1, |seen| i - seen);
+                longest_drought[kind] = longest_drought[kind].max(drought);
+            }
+        }
+
+        Self {
+            randomizer,
+            counts,
+            interval_histogram,
+            longest_drought,
+            mean_interval: interval_sum as f64 / interval_count.max(1) as f64,
+        }
+    }
+}
+
+pub fn run_audit() -> Vec<AuditReport> {
+    Randomizer::ALL.iter().map(|&randomizer| AuditReport::run(randomizer)).collect()
+}
+
+fn draw_histogram(ui: &mut egui::Ui, buckets: &[usize]) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 80.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let max = buckets.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let bar_width = rect.width() / buckets.len() as f32;
+
+    painter.rect_filled(rect, 0.0, egui::Color32::from_gray(30));
+    for (i, &count) in buckets.iter().enumerate() {
+        let height = rect.height() * count as f32 / max;
+        let left = rect.left() + i as f32 * bar_width;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left, rect.bottom() - height),
+            egui::pos2(left + bar_width - 1.0, rect.bottom()),
+        );
+        painter.rect_filled(bar, 0.0, egui::Color32::LIGHT_BLUE);
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn render_rng_audit(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Randomizer audit");
+            ui.label(format!("{} pieces per randomizer", AUDIT_PIECES));
+            ui.horizontal(|ui| {
+                if ui.button("Run again").clicked() {
+                    self.rng_audit = run_audit();
+                }
+                if ui.button(self.tr("Back to Start")).clicked() {
+                    self.state = GameState::StartScreen;
+                }
+            });
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for report in &self.rng_audit {
+                    ui.strong(report.randomizer.label());
+
+                    let counts = BlockType::ALL
+                        .iter()
+                        .map(|block_type| format!("{:?}: {}", block_type, report.counts[*block_type as usize]))
+                        .collect::<Vec<_>>()
+                        .join("  ");
+                    ui.label(counts);
+
+                    let droughts = BlockType::ALL
+                        .iter()
+                        .map(|block_type| format!("{:?}: {}", block_type, report.longest_drought[*block_type as usize]))
+                        .collect::<Vec<_>>()
+                        .join("  ");
+                    ui.label(format!("Longest droughts: {}", droughts));
+                    ui.label(format!("Mean interval: {:.2} (ideal 7.00)", report.mean_interval));
+
+                    ui.label(format!("Interval distribution (1 to {}+):", MAX_CHARTED_INTERVAL));
+                    draw_histogram(ui, &report.interval_histogram[1..]);
+                    ui.add_space(15.0);
+                }
+            });
+        });
+
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.state = GameState::StartScreen;
+        }
+    }
+}
@@ -0,0 +1,212 @@
+// Versus and online matches are played in rounds, best of N, with the tally
+// and each round's stats shown between rounds. If the rounds split evenly the
+// match goes to sudden death: a cheese race where both boards start on the
+// same garbage, no garbage is traded, and whoever digs out first wins.
+
+use eframe::egui;
+use std::time::Duration;
+
+use crate::{format_time, CrowsTetris, GameMode, GameState};
+
+pub const MAX_BEST_OF: u32 = 9;
+const SUDDEN_DEATH_ROWS: usize = 8;
+
+pub struct RoundResult {
+    // Whether player 1 (or, online, this player) won it
+    pub won: bool,
+    pub lines: u32,
+    pub rival_lines: u32,
+    pub pieces: u32,
+    pub attack: u32,
+    pub duration: Duration,
+    pub sudden_death: bool,
+}
+
+impl RoundResult {
+    fn pieces_per_second(&self) -> f64 {
+        self.pieces as f64 / self.duration.as_secs_f64().max(1.0)
+    }
+}
+
+pub struct VersusMatch {
+    pub best_of: u32,
+    pub rounds: Vec<RoundResult>,
+}
+
+impl VersusMatch {
+    pub fn new(best_of: u32) -> Self {
+        Self {
+            best_of: best_of.clamp(1, MAX_BEST_OF),
+            rounds: Vec::new(),
+        }
+    }
+
+    // Rounds won by player 1 and by player 2
+    pub fn wins(&self) -> (u32, u32) {
+        let won = self.rounds.iter().filter(|round| round.won).count() as u32;
+        (won, self.rounds.len() as u32 - won)
+    }
+
+    // Whether player 1 won the match, once one side has more wins than the other can catch up
+    // on with the rounds left, or has won sudden death
+    pub fn winner(&self) -> Option<bool> {
+        if let Some(round) = self.rounds.last().filter(|round| round.sudden_death) {
+            return Some(round.won);
+        }
+        let (won, lost) = self.wins();
+        let left = self.best_of.saturating_sub(self.rounds.len() as u32);
+        if won > lost + left {
+            Some(true)
+        } else if lost > won + left {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    // Every round played and still level
+    pub fn in_sudden_death(&self) -> bool {
+        self.rounds.len() as u32 >= self.best_of && self.winner().is_none()
+    }
+}
+
+impl CrowsTetris {
+    // A fresh match for every new versus or online run; next_round keeps the one going
+    pub(crate) fn start_match(&mut self) {
+        self.versus_match = matches!(self.mode, GameMode::Versus | GameMode::Online).then(|| VersusMatch::new(self.best_of));
+    }
+
+    pub(crate) fn in_sudden_death(&self) -> bool {
+        self.versus_match.as_ref().is_some_and(VersusMatch::in_sudden_death)
+    }
+
+    // A round is over and the match isn't
+    pub(crate) fn between_rounds(&self) -> bool {
+        self.state == GameState::GameOver && self.versus_match.as_ref().is_some_and(|versus_match| versus_match.winner().is_none())
+    }
+
+    pub(crate) fn won_cheese_race(&self) -> bool {
+        self.in_sudden_death() && self.garbage_rows_left() == 0
+    }
+
+    pub(crate) fn rival_won_cheese_race(&self) -> bool {
+        self.in_sudden_death() && self.rivals.first().is_some_and(|rival| rival.dug_out())
+    }
+
+    pub(crate) fn record_round(&mut self, won: bool) {
+        let rival_lines = self.rivals.first().map_or(0, |rival| rival.lines);
+        let round = RoundResult {
+            won,
+            lines: self.lines,
+            rival_lines,
+            pieces: self.pieces_placed,
+            attack: self.attack,
+            duration: self.play_time(),
+            sudden_death: self.in_sudden_death(),
+        };
+        if let Some(versus_match) = &mut self.versus_match {
+            versus_match.rounds.push(round);
+        }
+    }
+
+    pub(crate) fn next_round(&mut self) {
+        let versus_match = self.versus_match.take();
+        self.reset_game();
+        self.versus_match = versus_match;
+
+        if self.in_sudden_death() {
+            // Online, both sides draw the same garbage from the round's seed
+            self.add_garbage_rows(SUDDEN_DEATH_ROWS);
+            for rival in &mut self.rivals {
+                rival.set_grid(self.grid);
+            }
+        }
+    }
+
+    fn side_labels(&self) -> (&'static str, &'static str) {
+        match self.mode {
+            GameMode::Online => ("You", "Opponent"),
+            _ => ("Player 1", "Player 2"),
+        }
+    }
+
+    // The tally and a row of stats for each round so far
+    pub(crate) fn render_match_summary(&self, ui: &mut egui::Ui) {
+        let Some(versus_match) = &self.versus_match else {
+            return;
+        };
+        let (player, rival) = self.side_labels();
+        let (won, lost) = versus_match.wins();
+        ui.strong(format!("{} {} - {} {}", self.tr(player), won, lost, self.tr(rival)));
+
+        egui::Grid::new("rounds").striped(true).show(ui, |ui| {
+            for heading in ["Round", "Winner", "Lines", "Opponent lines", "Attack", "PPS", "Time"] {
+                ui.strong(self.tr(heading));
+            }
+            ui.end_row();
+            for (i, round) in versus_match.rounds.iter().enumerate() {
+                if round.sudden_death {
+                    ui.label(self.tr("Sudden death"));
+                } else {
+                    ui.label((i + 1).to_string());
+                }
+                ui.label(self.tr(if round.won { player } else { rival }));
+                ui.label(round.lines.to_string());
+                ui.label(round.rival_lines.to_string());
+                ui.label(round.attack.to_string());
+                ui.label(format!("{:.2}", round.pieces_per_second()));
+                ui.label(egui::RichText::new(format_time(round.duration)).monospace());
+                ui.end_row();
+            }
+        });
+    }
+
+    pub(crate) fn render_intermission(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                let Some(versus_match) = &self.versus_match else {
+                    return;
+                };
+                let round_won = versus_match.rounds.last().is_some_and(|round| round.won);
+                ui.heading(format!("{} {}", self.tr("Round"), versus_match.rounds.len()));
+                ui.add_space(10.0);
+                ui.strong(self.tr(match (self.mode, round_won) {
+                    (GameMode::Online, true) => "You win the round",
+                    (GameMode::Online, false) => "Your opponent wins the round",
+                    (_, true) => "Player 1 wins the round",
+                    (_, false) => "Player 2 wins the round",
+                }));
+                ui.add_space(20.0);
+                self.render_match_summary(ui);
+
+                if self.in_sudden_death() {
+                    ui.add_space(20.0);
+                    ui.strong(self.tr("Sudden death: cheese race"));
+                    ui.label(self.tr("The rounds are split evenly. Both boards start on the same garbage, and the first to dig out wins."));
+                }
+
+                ui.add_space(33.0);
+                let online = self.mode == GameMode::Online;
+                let connected = self.online.as_ref().is_some_and(|session| session.is_connected());
+                let hosting = self.online.as_ref().is_some_and(|session| session.is_host);
+                if online && !connected {
+                    ui.label(self.tr("Not connected"));
+                } else if online && !hosting {
+                    ui.label(self.tr("Waiting for the host to start the next round..."));
+                } else if ui.button(self.tr("Next round")).clicked() {
+                    if online {
+                        self.start_next_online_round();
+                    } else {
+                        self.next_round();
+                    }
+                }
+
+                ui.add_space(33.0);
+                if ui.button(self.tr("Back to Start")).clicked() {
+                    self.versus_match = None;
+                    self.state = GameState::StartScreen;
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,1130 @@
+// Headless smoke test for packaging: `crows-tetris --self-test` plays a short
+// scripted game without opening a window, checks scoring, line clears and
+// game over, round-trips every file the game saves and every message sent
+// online, and exits non-zero if anything is off.
+
+use eframe::egui::{self, Color32};
+use rodio::Decoder;
+use std::collections::{HashSet, VecDeque};
+use std::env;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::audio::{Mixer, SoundEvent};
+use crate::autosave;
+use crate::board_text::{board_from_ascii, board_to_ascii};
+use crate::classic::Ruleset;
+use crate::confirm::Confirm;
+use crate::controls::{self, Action, Binding, Controls, InputDevice};
+use crate::daily::{self, DailyResult};
+use crate::data_dir;
+use crate::gamepad;
+use crate::handling::{AutoShift, Handling};
+use crate::high_scores::{self, HighScore};
+use crate::input_queue;
+use crate::items::Item;
+use crate::master;
+use crate::mouse;
+use crate::music::{self, MusicCue, MusicTrack};
+use crate::one_switch::ScanPhase;
+use crate::online::{MatchRules, Message};
+use crate::name_entry::{self, PickerKey};
+use crate::profile::{self, Accessory, CrowAvatar, CrowColor, Profile};
+use crate::randomizer::Randomizer;
+use crate::finesse::fewest_presses;
+use crate::file_errors::Retry;
+use crate::history;
+use crate::lifetime;
+use crate::replay;
+use crate::rounds::MAX_BEST_OF;
+use crate::config;
+use crate::skin;
+use crate::sound_pack;
+use crate::splits;
+use crate::sticky;
+use crate::theme::{CellStyle, Theme};
+use crate::touch;
+use crate::{
+    format_time, rotate_shape, Block, BlockType, CrowsTetris, GameMode, GameState, GRID_HEIGHT, GRID_WIDTH,
+};
+
+struct Checks {
+    failures: usize,
+}
+
+impl Checks {
+    fn check(&mut self, name: &str, passed: bool) {
+        if passed {
+            println!("ok   {}", name);
+        } else {
+            println!("FAIL {}", name);
+            self.failures += 1;
+        }
+    }
+}
+
+// Puts the piece at column `x` above the board and lets it fall all the way down.
+fn drop_piece(game: &mut CrowsTetris, mut block: Block, x: i32) {
+    block.position = (x, 0);
+    while !game.collides(&block.shape, (block.position.0, block.position.1 + 1)) {
+        block.position.1 += 1;
+    }
+    game.active_block = Some(block);
+    game.lock_and_spawn();
+}
+
+fn new_game(mode: GameMode) -> CrowsTetris {
+    let mut game = CrowsTetris::new(Mixer::silent());
+    game.mode = mode;
+    game.reset_game();
+    game
+}
+
+fn check_gameplay(checks: &mut Checks) {
+    // A horizontal I into a four-cell gap on the bottom row clears one line
+    let mut game = new_game(GameMode::Marathon);
+    game.grid[GRID_HEIGHT - 1] = [BlockType::O.cell(); GRID_WIDTH];
+    for x in 0..4 {
+        game.grid[GRID_HEIGHT - 1][x] = 0;
+    }
+    drop_piece(&mut game, Block::new(BlockType::I), 0);
+    checks.check("single line clear", game.lines == 1 && game.score == 100);
+    checks.check("cleared row is empty", game.grid[GRID_HEIGHT - 1].iter().all(|&cell| cell == 0));
+    checks.check("pieces counted", game.pieces_placed == 1);
+
+    // Sprint takes a split on reaching 10 lines
+    let mut game = new_game(GameMode::Sprint);
+    game.lines = 9;
+    game.grid[GRID_HEIGHT - 1] = [BlockType::O.cell(); GRID_WIDTH];
+    for x in 0..4 {
+        game.grid[GRID_HEIGHT - 1][x] = 0;
+    }
+    drop_piece(&mut game, Block::new(BlockType::I), 0);
+    checks.check("split at 10 lines", game.splits.len() == 1);
+
+    // Locked cells remember which piece they came from so they keep its color
+    let mut game = new_game(GameMode::Marathon);
+    drop_piece(&mut game, Block::new(BlockType::T), 0);
+    let cells: Vec<u8> = game.grid.iter().flatten().copied().filter(|&cell| cell != 0).collect();
+    checks.check(
+        "locked cells keep piece type",
+        cells.len() == 4 && cells.iter().all(|&cell| BlockType::from_cell(cell) == Some(BlockType::T)),
+    );
+
+    // A vertical I into a well four rows deep clears four lines
+    let mut game = new_game(GameMode::Marathon);
+    for row in &mut game.grid[GRID_HEIGHT - 4..] {
+        *row = [BlockType::L.cell(); GRID_WIDTH];
+        row[0] = 0;
+    }
+    let mut vertical = Block::new(BlockType::I);
+    vertical.shape = vec![vec![1]; 4];
+    drop_piece(&mut game, vertical, 0);
+    checks.check("four line clear", game.lines == 4 && game.score == 400);
+    checks.check("board empty after clear", game.grid.iter().flatten().all(|&cell| cell == 0));
+    checks.check("tetris counts as attack", game.attack == 4);
+
+    // Finesse: the fewest presses to a spot, and a fault for taking more
+    let spawn = Block::new(BlockType::T);
+    let mut wall = Block::new(BlockType::O);
+    wall.position.0 = 0;
+    let mut turned_left = Block::new(BlockType::T);
+    turned_left.shape = rotate_shape(&turned_left.shape);
+    turned_left.position.0 -= 1;
+    let mut game = new_game(GameMode::Marathon);
+    game.active_block = Some(Block::new(BlockType::T));
+    game.piece_presses = 3;
+    game.hard_drop();
+    let slow = game.finesse_faults;
+    game.active_block = Some(Block::new(BlockType::T));
+    game.hard_drop();
+    checks.check(
+        "finesse",
+        fewest_presses(Ruleset::Modern, BlockType::T, &spawn) == Some(0)
+            && fewest_presses(Ruleset::Modern, BlockType::O, &wall) == Some(1)
+            && fewest_presses(Ruleset::Modern, BlockType::T, &turned_left) == Some(2)
+            && slow == 1
+            && game.finesse_faults == 1,
+    );
+
+    // The same clear under Classic rules scores NES-style
+    let mut game = new_game(GameMode::Marathon);
+    game.ruleset = Ruleset::Classic;
+    for row in &mut game.grid[GRID_HEIGHT - 4..] {
+        *row = [BlockType::L.cell(); GRID_WIDTH];
+        row[0] = 0;
+    }
+    let mut vertical = Block::new(BlockType::I);
+    vertical.shape = vec![vec![1]; 4];
+    drop_piece(&mut game, vertical, 0);
+    checks.check("classic four line clear", game.lines == 4 && game.score == 1200);
+    checks.check("no level up banner below ten lines", !game.level_flash());
+
+    // Pressing soft drop late in a slow step sinks the piece a row, not the rest of the step at the fast rate
+    let mut game = new_game(GameMode::Marathon);
+    game.countdown_until = None;
+    let mut block = Block::new(BlockType::T);
+    block.position = (0, 0);
+    game.active_block = Some(block);
+    game.last_update = Instant::now() - game.drop_speed.mul_f32(0.9);
+    game.set_soft_dropping(true);
+    game.apply_gravity();
+    checks.check("soft drop keeps the step's progress", game.active_block.as_ref().is_some_and(|b| b.position.1 <= 1));
+
+    // Giving up mid-run asks first, and pauses while it does
+    let mut game = new_game(GameMode::Marathon);
+    game.ask(&egui::Context::default(), Confirm::Abandon);
+    checks.check(
+        "giving up asks first",
+        game.state == GameState::Playing && game.is_paused && game.confirming == Some((Confirm::Abandon, false)),
+    );
+
+    // Every piece has a cue of its own, and a sound pack file name of its own
+    let pieces = BlockType::ALL.map(SoundEvent::Piece);
+    let stems = SoundEvent::ALL.map(SoundEvent::file_stem);
+    checks.check(
+        "piece announcements are distinct",
+        pieces.iter().enumerate().all(|(i, a)| pieces[..i].iter().all(|b| a.notes() != b.notes()))
+            && stems.iter().collect::<HashSet<_>>().len() == stems.len(),
+    );
+
+    // The music follows the screen, and the built-in tunes loop for as long as they're needed
+    let mut game = new_game(GameMode::Marathon);
+    let playing = game.music_cue();
+    game.end_run();
+    let twenty_seconds = 48_000 * 20;
+    let looped = MusicCue::Loop(MusicTrack::Korobeiniki)
+        .source()
+        .is_some_and(|source| source.take(twenty_seconds).count() == twenty_seconds);
+    checks.check(
+        "music follows the screen",
+        playing == MusicCue::Loop(game.gameplay_music)
+            && game.music_cue() == MusicCue::GameOver
+            && MusicCue::Loop(MusicTrack::Off).source().is_none()
+            && looped,
+    );
+
+    // The bundled tracks are found from anywhere, and decode
+    let bundled = MusicTrack::ALL.iter().filter_map(|track| track.file()).chain(["game_over.ogg"]).all(|file| {
+        File::open(music::music_path(file)).is_ok_and(|file| Decoder::new(BufReader::new(file)).is_ok())
+    });
+    checks.check("bundled music decodes", bundled);
+
+    // In one-switch mode the column sweep moves the piece on its own, and gravity waits for the drop
+    let mut game = new_game(GameMode::Marathon);
+    game.countdown_until = None;
+    game.one_switch = true;
+    game.scan_interval = Duration::ZERO;
+    game.one_switch_state.phase = ScanPhase::Column;
+    let start = game.active_block.as_ref().map(|block| block.position);
+    game.update_one_switch(&egui::Context::default());
+    game.last_update = Instant::now() - Duration::from_secs(5);
+    game.apply_gravity();
+    let now = game.active_block.as_ref().map(|block| block.position);
+    checks.check(
+        "one-switch sweep",
+        matches!((start, now), (Some((x, y)), Some((new_x, new_y))) if new_x == x + 1 && new_y == y),
+    );
+
+    // Classic rules have no hard drop, from any input; one-switch lets the piece fall instead
+    let mut game = new_game(GameMode::Marathon);
+    game.countdown_until = None;
+    game.ruleset = Ruleset::Classic;
+    let start = game.active_block.as_ref().map(|block| block.position);
+    game.hard_drop();
+    let not_dropped = game.pieces_placed == 0 && game.active_block.as_ref().map(|block| block.position) == start;
+    game.one_switch = true;
+    game.one_switch_state.phase = ScanPhase::Column;
+    game.pointer_actions.push(Action::HardDrop);
+    game.update_one_switch(&egui::Context::default());
+    let falling = game.one_switch_state.phase == ScanPhase::Falling && !game.one_switch_scanning();
+    game.one_switch = false;
+    game.queue_input(&egui::Context::default());
+    checks.check(
+        "no hard drop under classic rules",
+        not_dropped && falling && game.pieces_placed == 0 && game.input_queue.is_empty(),
+    );
+
+    // Reaching ten lines speeds the game up and says so
+    let mut game = new_game(GameMode::Marathon);
+    game.ruleset = Ruleset::Classic;
+    game.lines = 8;
+    for row in &mut game.grid[GRID_HEIGHT - 4..] {
+        *row = [BlockType::L.cell(); GRID_WIDTH];
+        row[0] = 0;
+    }
+    let mut vertical = Block::new(BlockType::I);
+    vertical.shape = vec![vec![1]; 4];
+    drop_piece(&mut game, vertical, 0);
+    checks.check("level up banner", game.level == 1 && game.level_flash());
+
+    // Nowhere for the next piece to spawn ends the run
+    let mut game = new_game(GameMode::Marathon);
+    for row in &mut game.grid {
+        *row = [BlockType::T.cell(); GRID_WIDTH];
+        row[GRID_WIDTH - 1] = 0;
+    }
+    let mut block = Block::new(BlockType::O);
+    block.position = (0, -2);
+    game.active_block = Some(block);
+    game.lock_and_spawn();
+    checks.check("top out ends the run", game.state == GameState::GameOver);
+    checks.check("run result recorded", game.last_result.is_some());
+    checks.check("top out animates", game.topping_out());
+
+    // The same seed deals the same pieces
+    let pieces = |seed: &str| {
+        let mut game = CrowsTetris::new(Mixer::silent());
+        game.seed_text = seed.to_string();
+        game.reset_game();
+        (0..50).map(|_| game.generate_random_block().block_type).collect::<Vec<_>>()
+    };
+    checks.check("seeded runs repeat", pieces("crow") == pieces("crow") && pieces("1") != pieces("2"));
+
+    // A 7-bag deals each piece once per bag
+    let mut game = new_game(GameMode::Marathon);
+    game.randomizer = Randomizer::SevenBag;
+    game.reset_game();
+    for _ in 0..13 {
+        game.generate_random_block();
+    }
+    checks.check(
+        "piece tally counts dealt pieces",
+        BlockType::ALL.iter().all(|&block_type| game.piece_tally.count(block_type) == 2),
+    );
+
+    // Hold swaps in the next piece, then is locked out until the piece lands
+    let mut game = new_game(GameMode::Marathon);
+    let first = game.active_block.as_ref().map(|block| block.block_type);
+    let next = game.next_queue.front().copied();
+    game.hold_piece();
+    let swapped = game.active_block.as_ref().map(|block| block.block_type);
+    game.hold_piece();
+    checks.check(
+        "hold swaps once per piece",
+        game.held_piece == first
+            && swapped == next
+            && game.active_block.as_ref().map(|block| block.block_type) == swapped,
+    );
+
+    let mut game = new_game(GameMode::Marathon);
+    game.hard_drop();
+    checks.check(
+        "hard drop lands on the floor",
+        game.pieces_placed == 1 && game.grid[GRID_HEIGHT - 1].iter().any(|&cell| cell != 0) && !game.effects.is_empty(),
+    );
+
+    let mut game = new_game(GameMode::Marathon);
+    let safe = game.danger();
+    game.grid[1][0] = BlockType::O.cell();
+    checks.check("danger near top out", safe == 0.0 && game.danger() > 0.5);
+    let tense = game.music_tension();
+    game.grid[1][0] = 0;
+    checks.check("music calms down after digging out", tense > 0.5 && game.music_tension() == 0.0);
+
+    checks.check("timer shows milliseconds", format_time(Duration::from_millis(65_250)) == "1:05.250");
+
+    let mut game = new_game(GameMode::Dig);
+    game.dig_rows = 5;
+    game.reset_game();
+    checks.check("dig garbage rows", game.garbage_rows_left() == 5);
+
+    // The seed decides the garbage holes too
+    let dig = |seed: &str| {
+        let mut game = new_game(GameMode::Dig);
+        game.seed_text = seed.to_string();
+        game.reset_game();
+        game.grid
+    };
+    checks.check("seeded garbage repeats", dig("crow") == dig("crow") && dig("1") != dig("2"));
+
+    // Best of two: one round each goes to a cheese race, won by digging out first
+    let mut game = CrowsTetris::new(Mixer::silent());
+    game.mode = GameMode::Versus;
+    game.best_of = 2;
+    game.reset_game();
+    game.end_run();
+    let lost_first = game.between_rounds();
+    game.next_round();
+    game.rivals[0].topped_out = true;
+    game.end_run();
+    checks.check("versus rounds split to sudden death", lost_first && game.between_rounds() && game.in_sudden_death());
+    game.next_round();
+    let cheese = game.garbage_rows_left() > 0 && !game.rivals[0].dug_out();
+    game.exchange_garbage(4);
+    let traded = game.rivals[0].incoming_garbage > 0;
+    game.grid = [[0; GRID_WIDTH]; GRID_HEIGHT];
+    let won = game.goal_reached();
+    game.end_run();
+    let winner = game.versus_match.as_ref().and_then(|versus_match| versus_match.winner());
+    checks.check("cheese race decides the match", cheese && !traded && won && winner == Some(true) && !game.between_rounds());
+
+    // A sticky group left hanging falls to the floor, and its lock times come down with it
+    let mut grid = [[0; GRID_WIDTH]; GRID_HEIGHT];
+    let mut locked_at = [[None; GRID_WIDTH]; GRID_HEIGHT];
+    let locked = Instant::now() - Duration::from_secs(3);
+    for x in 2..4 {
+        grid[5][x] = BlockType::T.cell();
+        locked_at[5][x] = Some(locked);
+    }
+    let fell = sticky::settle(&mut grid, &mut locked_at);
+    checks.check(
+        "sticky groups settle with their lock times",
+        fell
+            && grid[GRID_HEIGHT - 1][2] == BlockType::T.cell()
+            && grid[5][2] == 0
+            && locked_at[GRID_HEIGHT - 1][3] == Some(locked)
+            && locked_at[5][3].is_none(),
+    );
+
+    // Party items act on the player's own board: slowing their fall, or shuffling their bottom
+    // rows without adding or taking away a cell
+    let mut game = new_game(GameMode::Party);
+    game.held_item = Some(Item::SlowFall);
+    game.use_item();
+    let slowed = game.held_item.is_none() && game.slowed_until.is_some();
+    for (i, row) in game.grid.iter_mut().rev().take(5).enumerate() {
+        *row = [BlockType::Z.cell(); GRID_WIDTH];
+        row[i] = 0;
+    }
+    let before = game.grid;
+    game.held_item = Some(Item::ShuffleRows);
+    game.use_item();
+    let filled = |row: &[u8; GRID_WIDTH]| row.iter().filter(|&&cell| cell != 0).count();
+    checks.check(
+        "party items act on your own board",
+        slowed
+            && game.grid[..GRID_HEIGHT - 4] == before[..GRID_HEIGHT - 4]
+            && game.grid.iter().zip(&before).all(|(row, was)| filled(row) == filled(was)),
+    );
+
+    // Master mode reaches 20G, only a clear moves on to the next section, and GM takes a full,
+    // fast and even run
+    let mut game = new_game(GameMode::Master);
+    game.level = 98;
+    game.advance_master_level(0);
+    game.advance_master_level(0);
+    let held_back = game.level == 99 && game.section_times.is_empty();
+    game.advance_master_level(1);
+    let fast = vec![Duration::from_secs(50); 10];
+    let uneven: Vec<_> = (1..=10).map(|minutes| Duration::from_secs(minutes * 60)).collect();
+    checks.check(
+        "master sections and grades",
+        held_back
+            && game.level == 100
+            && game.section_times.len() == 1
+            && master::drop_interval(500) <= Duration::from_secs_f64(1.0 / 60.0 / 20.0)
+            && master::grade(0, &[]) == "9"
+            && master::grade(master::MASTER_MAX_LEVEL, &fast) == "GM"
+            && master::grade(master::MASTER_MAX_LEVEL, &uneven) != "GM"
+            && game.master_grade().is_some(),
+    );
+
+    // Against the CPU a tetris sends four rows, a clear cancels what's coming first, and the
+    // CPU places its pieces and takes its garbage on its own
+    let ctx = egui::Context::default();
+    let mut game = new_game(GameMode::Battle);
+    game.exchange_garbage(4);
+    let sent = game.rivals[0].incoming_garbage == 4;
+    game.incoming_garbage = 3;
+    game.exchange_garbage(2);
+    let cancelled = game.incoming_garbage == 2;
+    game.exchange_garbage(0);
+    let received = game.incoming_garbage == 0 && game.garbage_rows_left() == 2;
+    game.countdown_until = None;
+    game.paused_at = None;
+    game.run_started = Instant::now() - Duration::from_secs(10);
+    game.update_rivals(&ctx);
+    checks.check(
+        "battle trades garbage with the cpu",
+        sent && cancelled && received && game.rivals[0].incoming_garbage == 0 && !game.rivals[0].topped_out,
+    );
+
+    // Received garbage never comes to more than the board holds
+    let mut game = new_game(GameMode::Online);
+    game.receive_garbage(usize::MAX);
+    game.receive_garbage(5);
+    checks.check("received garbage is clamped", game.incoming_garbage == GRID_HEIGHT);
+}
+
+fn check_persistence(checks: &mut Checks) {
+    let mut game = new_game(GameMode::Marathon);
+    for row in &mut game.grid[GRID_HEIGHT - 3..] {
+        *row = [BlockType::S.cell(); GRID_WIDTH];
+        row[3] = 0;
+    }
+    game.add_garbage_rows(2);
+    let text = board_to_ascii(&game.grid);
+    checks.check("board text round trip", board_from_ascii(&text) == Ok(game.grid));
+
+    // Scores from the old name,score file are imported once, as legacy scores
+    fs::write("high_scores.txt", "Crow,1200\nRaven,300\n").ok();
+    let old_scores = high_scores::load_high_scores();
+    let imported = high_scores::load_high_scores();
+    let high_scores = vec![HighScore {
+        name: "Crow".to_string(),
+        score: 1200,
+        lines: 42,
+        level: 4,
+        mode: GameMode::Sprint,
+        duration: Duration::from_millis(61_250),
+        timestamp: 1_760_000_000,
+        seed: u64::MAX - 7,
+        replay_digest: 0x0123_4567_89ab_cdef,
+        verified: true,
+        legacy: false,
+    }];
+    high_scores::save_high_scores(&high_scores).ok();
+    checks.check(
+        "high scores round trip",
+        old_scores.len() == 2
+            && old_scores[1].score == 300
+            && old_scores.iter().all(|entry| entry.legacy && entry.verified)
+            && imported == old_scores
+            && high_scores::load_high_scores() == high_scores,
+    );
+
+    // A score edited in the file no longer matches its signature
+    let saved = fs::read_to_string(data_dir::path(high_scores::HIGH_SCORE_FILE)).unwrap_or_default();
+    fs::write(data_dir::path(high_scores::HIGH_SCORE_FILE), saved.replace("score = 1200", "score = 9999")).ok();
+    let edited = high_scores::load_high_scores();
+    checks.check(
+        "edited high scores are unverified",
+        saved.contains("score = 1200") && edited.len() == 1 && edited[0].score == 9999 && !edited[0].verified,
+    );
+
+    // Each mode has its own table: Sprint ranks the faster time first, Marathon the higher score
+    let entry = |mode, score, seconds| HighScore {
+        name: "Crow".to_string(),
+        score,
+        lines: 40,
+        level: 1,
+        mode,
+        duration: Duration::from_secs(seconds),
+        timestamp: 1,
+        seed: 0,
+        replay_digest: 0,
+        verified: true,
+        legacy: false,
+    };
+    let mut game = new_game(GameMode::Marathon);
+    game.high_scores.clear();
+    fs::remove_file(data_dir::path(high_scores::HIGH_SCORE_FILE)).ok();
+    fs::remove_file(data_dir::path(high_scores::OLD_HIGH_SCORE_FILE)).ok();
+    for (mode, score, seconds) in [(GameMode::Sprint, 900, 70), (GameMode::Sprint, 100, 55), (GameMode::Marathon, 500, 30)] {
+        game.add_high_score(entry(mode, score, seconds));
+    }
+    for score in 0..12 {
+        game.add_high_score(entry(GameMode::Marathon, score, 10));
+    }
+    let sprint = high_scores::leaderboard(&game.high_scores, GameMode::Sprint);
+    let marathon = high_scores::leaderboard(&game.high_scores, GameMode::Marathon);
+    checks.check(
+        "per-mode leaderboards",
+        sprint.iter().map(|entry| entry.score).collect::<Vec<_>>() == [100, 900]
+            && marathon.len() == high_scores::HIGH_SCORE_COUNT
+            && marathon[0].score == 500
+            && game.high_scores.len() == high_scores::HIGH_SCORE_COUNT + 2,
+    );
+
+    // Two copies of the game adding scores keep each other's, and a shorter table saves cleanly
+    let mut other = new_game(GameMode::Marathon);
+    other.high_scores.clear();
+    other.add_high_score(entry(GameMode::Ultra, 777, 180));
+    game.add_high_score(entry(GameMode::Ultra, 555, 180));
+    let both = high_scores::load_high_scores();
+    high_scores::save_high_scores(&both[..1]).ok();
+    let leftovers = fs::read_dir(".").map_or(0, |entries| {
+        entries.filter_map(Result::ok).filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp")).count()
+    });
+    checks.check(
+        "high scores from other copies of the game are kept",
+        high_scores::leaderboard(&both, GameMode::Ultra).len() == 2
+            && high_scores::load_high_scores() == both[..1]
+            && leftovers == 0,
+    );
+
+    let profiles = vec![
+        Profile {
+            name: "Crow".to_string(),
+            avatar: CrowAvatar::default(),
+        },
+        Profile {
+            name: "Raven".to_string(),
+            avatar: CrowAvatar {
+                color: CrowColor::Blue,
+                accessory: Accessory::Monocle,
+            },
+        },
+    ];
+    profile::save_profiles(&profiles).ok();
+    let loaded = profile::load_profiles();
+    checks.check(
+        "profiles round trip",
+        loaded.len() == profiles.len()
+            && loaded
+                .iter()
+                .zip(&profiles)
+                .all(|(a, b)| a.name == b.name && a.avatar == b.avatar),
+    );
+
+    let results = vec![DailyResult {
+        day: 19723,
+        name: "Crow".to_string(),
+        score: 900,
+        lines: 9,
+    }];
+    daily::save_daily_results(&results).ok();
+    let loaded = daily::load_daily_results();
+    checks.check(
+        "daily results round trip",
+        loaded.len() == 1 && loaded[0].day == 19723 && loaded[0].name == "Crow" && loaded[0].score == 900,
+    );
+    checks.check("daily date label", daily::date_label(19723) == "2024-01-01");
+
+    let theme = Theme::from_toml("name = \"Test\"\nbackground = \"#102030\"\n[pieces]\nT = \"#ff000080\"", "test");
+    checks.check(
+        "theme file parses",
+        theme.as_ref().is_ok_and(|theme| {
+            theme.name == "Test"
+                && theme.background == Color32::from_rgb(0x10, 0x20, 0x30)
+                && theme.piece_color(BlockType::T) == Color32::from_rgba_unmultiplied(255, 0, 0, 128)
+                && theme.piece_color(BlockType::I) == Theme::default().piece_color(BlockType::I)
+        }),
+    );
+    let theme = Theme::from_toml("backgrounds = [\"a.png\", \"b.png\"]", "test");
+    checks.check(
+        "theme backgrounds cycle by level",
+        theme.as_ref().is_ok_and(|theme| {
+            theme.background_for_level(0) == Some("themes/a.png") && theme.background_for_level(3) == Some("themes/b.png")
+        }),
+    );
+    checks.check("bad theme color rejected", Theme::from_toml("board = \"red\"", "test").is_err());
+
+    // Skins need eight square tiles in a row
+    fs::create_dir_all("skins").ok();
+    image::RgbaImage::new(64, 8).save("skins/good.png").ok();
+    image::RgbaImage::new(64, 16).save("skins/bad.png").ok();
+    let ctx = egui::Context::default();
+    checks.check(
+        "skin tile sheet loads",
+        skin::skin_names() == ["bad", "good"]
+            && skin::load_skin(&ctx, "good").is_ok()
+            && skin::load_skin(&ctx, "bad").is_err(),
+    );
+
+    // A pack replaces the sounds it has files for; a broken or empty one doesn't load
+    let mut wav = b"RIFF\0\0\0\0WAVEfmt \x10\0\0\0\x01\0\x01\0\x80\xbb\0\0\0\x77\x01\0\x02\0\x10\0data\x08\0\0\0".to_vec();
+    wav.extend([1, 0, 2, 0, 3, 0, 4, 0]);
+    for pack in ["crows", "broken", "empty"] {
+        fs::create_dir_all(format!("soundpacks/{}", pack)).ok();
+    }
+    fs::write("soundpacks/crows/clear.wav", &wav).ok();
+    fs::write("soundpacks/broken/move.wav", b"not a wav").ok();
+    let pack = sound_pack::load_sound_pack("crows");
+    checks.check(
+        "sound pack loads",
+        sound_pack::sound_pack_names() == ["broken", "crows", "empty"]
+            && pack.as_ref().is_ok_and(|pack| {
+                pack.sound(SoundEvent::LineClear).is_some_and(|sound| sound.clone().collect::<Vec<_>>() == [1, 2, 3, 4])
+                    && pack.sound(SoundEvent::Move).is_none()
+            })
+            && sound_pack::load_sound_pack("broken").is_err()
+            && sound_pack::load_sound_pack("empty").is_err(),
+    );
+
+    // Packs of OGG files load too, like the move.ogg, clear.ogg, tetris.ogg ones players make
+    fs::create_dir_all("soundpacks/ogg").ok();
+    for file in ["move.ogg", "clear.ogg", "tetris.ogg"] {
+        fs::copy(music::music_path("game_over.ogg"), format!("soundpacks/ogg/{}", file)).ok();
+    }
+    let pack = sound_pack::load_sound_pack("ogg");
+    checks.check(
+        "ogg sound pack loads",
+        pack.is_ok_and(|pack| {
+            [SoundEvent::Move, SoundEvent::LineClear, SoundEvent::Tetris]
+                .into_iter()
+                .all(|event| pack.sound(event).is_some_and(|sound| sound.clone().any(|sample| sample != 0)))
+        }),
+    );
+
+    let mut game = new_game(GameMode::Marathon);
+    game.drop_speed = Duration::from_millis(300);
+    game.screen_shake = false;
+    game.audio.music_volume = 0.25;
+    game.audio.master_volume = 0.5;
+    game.ui_scale = 1.5;
+    game.cell_style = CellStyle::Beveled;
+    game.gamepads.deadzone = 0.3;
+    game.handling.das = Duration::from_millis(120);
+    game.gameplay_music = MusicTrack::Off;
+    game.window_size = egui::vec2(640.0, 720.0);
+    game.window_position = Some(egui::pos2(-200.0, 64.0));
+    game.fullscreen = true;
+    game.mode = GameMode::Sprint;
+    game.save_settings();
+    let mut loaded = new_game(GameMode::Marathon);
+    loaded.load_settings(&ctx);
+    checks.check(
+        "settings round trip",
+        loaded.drop_speed == game.drop_speed
+            && !loaded.screen_shake
+            && loaded.audio.music_volume == 0.25
+            && loaded.audio.master_volume == 0.5
+            && loaded.ui_scale == 1.5
+            && loaded.cell_style == CellStyle::Beveled
+            && loaded.gamepads.deadzone == 0.3
+            && loaded.handling.das == Duration::from_millis(120)
+            && loaded.gameplay_music == MusicTrack::Off
+            && loaded.window_size == egui::vec2(640.0, 720.0)
+            && loaded.window_position == Some(egui::pos2(-200.0, 64.0))
+            && loaded.fullscreen
+            && loaded.mode == GameMode::Sprint,
+    );
+
+    // Files edited while the game runs are read again, apart from what only counts at launch
+    let mut running = new_game(GameMode::Marathon);
+    let themes_before = running.themes.len();
+    let config = fs::read_to_string(data_dir::path(config::CONFIG_FILE)).unwrap_or_default();
+    let edited = config.replace("drop_speed_ms = 300", "drop_speed_ms = 450").replace("\"Sprint\"", "\"Zen\"");
+    fs::write(data_dir::path(config::CONFIG_FILE), edited).ok();
+    fs::create_dir_all("themes").ok();
+    fs::write("themes/hot.toml", "name = \"Hot\"\n").ok();
+    running.reload_changed_files(&ctx);
+    checks.check(
+        "edited files are reloaded",
+        running.drop_speed == Duration::from_millis(450)
+            && running.mode == GameMode::Marathon
+            && running.themes.len() == themes_before + 1
+            && running.themes.iter().any(|theme| theme.name == "Hot"),
+    );
+    fs::remove_file("themes/hot.toml").ok();
+
+    // Files that can't be read or written are reported, not given up on quietly, and can be retried
+    let config = fs::read_to_string(data_dir::path(config::CONFIG_FILE)).unwrap_or_default();
+    fs::write(data_dir::path(config::CONFIG_FILE), "[gameplay\n").ok();
+    running.load_settings(&ctx);
+    let bad_config = running.file_errors.len() == 1 && running.drop_speed == Duration::from_millis(450);
+    fs::write(data_dir::path(config::CONFIG_FILE), config).ok();
+    fs::remove_file(data_dir::path(lifetime::LIFETIME_STATS_FILE)).ok();
+    fs::create_dir_all(data_dir::path(lifetime::LIFETIME_STATS_FILE)).ok();
+    running.save_lifetime();
+    let unwritable = running.file_errors.len() == 2;
+    fs::remove_dir(data_dir::path(lifetime::LIFETIME_STATS_FILE)).ok();
+    running.retry(&ctx, Retry::LoadSettings);
+    running.retry(&ctx, Retry::SaveLifetimeStats);
+    checks.check(
+        "file errors are reported and retried",
+        bad_config
+            && unwritable
+            && running.file_errors.is_empty()
+            && lifetime::load_lifetime_stats() == running.lifetime_stats,
+    );
+
+    // The controls save themselves, so their failures come through the game, and a missing sound
+    // pack can be looked for again
+    let config_path = data_dir::path(config::CONFIG_FILE);
+    let config = fs::read_to_string(&config_path).unwrap_or_default();
+    fs::remove_file(&config_path).ok();
+    fs::create_dir_all(&config_path).ok();
+    running.controls.rebind(Action::Hold, Binding::Key(egui::Key::H));
+    running.report_control_save_errors();
+    running.load_sound_pack_setting("missing".to_string());
+    let reported: Vec<_> = running.file_errors.iter().filter_map(|error| error.retry.clone()).collect();
+    fs::remove_dir(&config_path).ok();
+    fs::write(&config_path, config).ok();
+    running.retry(&ctx, Retry::SaveBindings);
+    checks.check(
+        "control and sound pack failures are reported",
+        reported == [Retry::SaveBindings, Retry::LoadSoundPack("missing".to_string())]
+            && running.file_errors.len() == 1
+            && Controls::load().bindings().get(&Action::Hold) == Some(&Binding::Key(egui::Key::H)),
+    );
+
+    // The first launch with a data directory copies the old files over, replays included, but never
+    // overwrites newer ones or touches the originals; later launches leave both alone
+    for dir in ["old/replays", "new"] {
+        fs::create_dir_all(dir).ok();
+    }
+    fs::write("old/profiles.txt", "old").ok();
+    fs::write("old/splits.txt", "old").ok();
+    fs::write("old/replays/last.toml", "old").ok();
+    fs::write("new/splits.txt", "new").ok();
+    // Started from somewhere other than the game's folder, as `cargo run` does
+    fs::create_dir_all("started_from").ok();
+    fs::write("started_from/high_scores.txt", "Crow,100\n").ok();
+    fs::write("started_from/profiles.txt", "started").ok();
+    let from = [PathBuf::from("started_from"), PathBuf::from("old")];
+    data_dir::migrate_once(&from, Path::new("new"));
+    fs::write("old/config.toml", "old").ok();
+    data_dir::migrate_once(&from, Path::new("new"));
+    let looks_in_working_dir = env::current_dir().is_ok_and(|dir| data_dir::old_dirs().first() == Some(&dir));
+    checks.check(
+        "saved files are copied to the data directory once",
+        looks_in_working_dir
+            && fs::read_to_string("new/high_scores.txt").is_ok_and(|text| text == "Crow,100\n")
+            && fs::read_to_string("new/profiles.txt").is_ok_and(|text| text == "started")
+            && fs::read_to_string("new/splits.txt").is_ok_and(|text| text == "new")
+            && fs::read_to_string("new/replays/last.toml").is_ok_and(|text| text == "old")
+            && Path::new("old/profiles.txt").exists()
+            && !Path::new("new/config.toml").exists(),
+    );
+
+    // Out of range values are pulled back in, and an old settings.txt is still read
+    fs::write("config.toml", "[gameplay]\ndrop_speed_ms = 5\n[display]\nui_scale = 9.0\n").ok();
+    let mut clamped = new_game(GameMode::Marathon);
+    clamped.load_settings(&ctx);
+    fs::remove_file("config.toml").ok();
+    fs::write("settings.txt", "drop_speed_ms=400\n").ok();
+    let mut migrated = new_game(GameMode::Marathon);
+    migrated.load_settings(&ctx);
+    checks.check(
+        "config values are validated",
+        clamped.drop_speed == Duration::from_millis(50)
+            && clamped.ui_scale == 2.0
+            && migrated.drop_speed == Duration::from_millis(400),
+    );
+
+    // A recorded run plays back to the same board, from memory or from its file, and
+    // scrubbing back rebuilds the board as it was then. A piece drops every second.
+    let mut recorded = new_game(GameMode::Marathon);
+    recorded.countdown_until = None;
+    recorded.paused_at = None;
+    recorded.last_update = Instant::now() - recorded.drop_speed * 3;
+    recorded.apply_gravity();
+    recorded.shift_piece(-1);
+    recorded.rotate_block(true);
+    recorded.hard_drop();
+    recorded.hold_piece();
+    recorded.shift_piece(1);
+    let mut halfway = None;
+    for second in 1..=6 {
+        recorded.run_started -= Duration::from_secs(1);
+        recorded.hard_drop();
+        if second == 3 {
+            halfway = Some(recorded.grid);
+        }
+    }
+    let (grid, active_block, held_piece) = (recorded.grid, recorded.active_block.clone(), recorded.held_piece);
+    recorded.end_run();
+    let mut watched = new_game(GameMode::Sprint);
+    let duration = recorded.last_replay.as_ref().map_or(Duration::ZERO, |replay| replay.duration);
+    if let Some(replay) = recorded.last_replay.clone() {
+        watched.watch_replay(replay);
+    }
+    watched.seek_replay(duration);
+    let played_back = watched.grid == grid
+        && watched.active_block == active_block
+        && watched.held_piece == held_piece
+        && watched.state == GameState::Playing;
+    watched.seek_replay(Duration::from_millis(3500));
+    checks.check(
+        "replays play back the run",
+        replay::load_replay("last.toml").ok() == recorded.last_replay
+            && played_back
+            && Some(watched.grid) == halfway
+            && watched.play_time() == Duration::from_millis(3500),
+    );
+
+    // Every game that ends adds to the lifetime totals, which are saved as they go
+    let mut game = new_game(GameMode::Marathon);
+    let before = game.lifetime_stats.clone();
+    for row in &mut game.grid[GRID_HEIGHT - 4..] {
+        *row = [BlockType::L.cell(); GRID_WIDTH];
+        row[0] = 0;
+    }
+    let mut vertical = Block::new(BlockType::I);
+    vertical.shape = vec![vec![1]; 4];
+    drop_piece(&mut game, vertical, 0);
+    game.end_run();
+    let after = lifetime::load_lifetime_stats();
+    checks.check(
+        "lifetime stats add up",
+        after.games == before.games + 1
+            && after.tetrises == before.tetrises + 1
+            && after.lines == before.lines + 4
+            && after.pieces == before.pieces + 1,
+    );
+
+    // Every game goes into the history, and the export has a row per game
+    let games_before = history::load_game_history().len();
+    let mut game = new_game(GameMode::Sprint);
+    game.end_run();
+    let exported = history::export_stats().ok().and_then(|path| fs::read_to_string(path).ok()).unwrap_or_default();
+    let rows: Vec<&str> = exported.lines().collect();
+    checks.check(
+        "stats export has a row per game",
+        history::load_game_history().len() == games_before + 1
+            && rows.len() == games_before + 2
+            && rows[0] == "date,mode,score,lines,duration_s,pps,finesse_faults"
+            && rows.last().is_some_and(|row| row.split(',').nth(1) == Some("Sprint") && row.split(',').count() == 7),
+    );
+
+    // A suspended run comes back as it was left, pieces still to come included
+    let mut suspended = new_game(GameMode::Sprint);
+    for _ in 0..9 {
+        suspended.hard_drop();
+    }
+    suspended.hold_piece();
+    suspended.score = 1234;
+    suspended.suspend_run();
+    let mut continued = new_game(GameMode::Marathon);
+    let restored = continued.continue_suspended_run();
+    let upcoming = |game: &mut CrowsTetris| (0..10).map(|_| game.generate_random_block().block_type).collect::<Vec<_>>();
+    checks.check(
+        "suspended runs continue where they left off",
+        restored
+            && continued.mode == GameMode::Sprint
+            && continued.grid == suspended.grid
+            && continued.active_block == suspended.active_block
+            && continued.held_piece == suspended.held_piece
+            && continued.hold_used
+            && continued.score == 1234
+            && continued.pieces_placed == suspended.pieces_placed
+            && continued.piece_tally == suspended.piece_tally
+            && continued.play_time().abs_diff(suspended.play_time()) < Duration::from_millis(50)
+            && upcoming(&mut continued) == upcoming(&mut suspended)
+            && !continued.has_suspended_run
+            && !continued.continue_suspended_run(),
+    );
+
+    // An autosave is there while a run goes on, comes back at the next launch, and is gone once the run ends
+    let mut crashed = new_game(GameMode::Sprint);
+    crashed.countdown_until = None;
+    for _ in 0..5 {
+        crashed.hard_drop();
+    }
+    crashed.autosave();
+    let mut relaunched = new_game(GameMode::Marathon);
+    relaunched.state = GameState::StartScreen;
+    relaunched.has_interrupted_run = autosave::has_interrupted_run();
+    let restored = relaunched.has_interrupted_run && relaunched.restore_interrupted_run();
+    relaunched.countdown_until = None;
+    relaunched.autosave();
+    relaunched.state = GameState::GameOver;
+    relaunched.autosave();
+    checks.check(
+        "interrupted runs can be restored",
+        restored
+            && relaunched.mode == GameMode::Sprint
+            && relaunched.grid == crashed.grid
+            && relaunched.pieces_placed == crashed.pieces_placed
+            && !autosave::has_interrupted_run(),
+    );
+
+    let best = vec![(GameMode::Sprint, vec![Duration::from_millis(31_250), Duration::from_millis(64_100)])];
+    splits::save_best_splits(&best).ok();
+    checks.check("best splits round trip", splits::load_best_splits() == best);
+
+    // The bindings go in config.toml with the settings, and an old keybindings.txt is still read
+    let mut controls = Controls::load();
+    controls.rebind(Action::Pause, Binding::Key(egui::Key::F1));
+    let in_config = fs::read_to_string(data_dir::path(config::CONFIG_FILE))
+        .is_ok_and(|text| text.contains("[keybindings.keyboard]") && text.contains("Pause = \"key:F1\""));
+    checks.check("key bindings round trip", in_config && Controls::load().bindings() == controls.bindings());
+    let config = fs::read_to_string(data_dir::path(config::CONFIG_FILE)).unwrap_or_default();
+    fs::remove_file(data_dir::path(config::CONFIG_FILE)).ok();
+    fs::write(data_dir::path(controls::OLD_KEYBINDINGS_FILE), "keyboard,Hold,key:F2\n").ok();
+    let old = Controls::load().bindings().get(&Action::Hold) == Some(&Binding::Key(egui::Key::F2));
+    fs::write(data_dir::path(config::CONFIG_FILE), config).ok();
+    fs::remove_file(data_dir::path(controls::OLD_KEYBINDINGS_FILE)).ok();
+    checks.check("old key bindings file is read", old);
+}
+
+// Every message the two players send each other reads back the same, and what can't be
+// trusted is turned away or pulled into range
+fn check_online(checks: &mut Checks) {
+    let rules = MatchRules { ruleset: Ruleset::Classic, best_of: 5 };
+    let mut grid = [[0; GRID_WIDTH]; GRID_HEIGHT];
+    grid[GRID_HEIGHT - 1][3] = BlockType::L.cell();
+    let messages = [
+        Message::Seed { seed: u64::MAX, rules },
+        Message::NextRound(42),
+        Message::Garbage(3),
+        Message::Board(Box::new(grid), 7),
+        Message::Lost,
+        Message::DugOut,
+        Message::Rematch { seed: 9, rules },
+        Message::RematchAccepted,
+        Message::RematchDeclined,
+        Message::Hello(Profile {
+            name: "Crow Jr. the 2nd".to_string(),
+            avatar: CrowAvatar { color: CrowColor::Green, accessory: Accessory::TopHat },
+        }),
+    ];
+    checks.check(
+        "online messages round trip",
+        messages.iter().all(|message| Message::decode(&message.encode()).as_ref() == Some(message)),
+    );
+    let clamped = Message::decode("SEED 1 MODERN 99");
+    let long_name = Message::decode(&format!("HELLO Black Crown {}", "a".repeat(100)));
+    checks.check(
+        "bad online messages are turned away",
+        clamped == Some(Message::Seed { seed: 1, rules: MatchRules { ruleset: Ruleset::Modern, best_of: MAX_BEST_OF } })
+            && Message::decode("GARBAGE -1").is_none()
+            && Message::decode("SEED 1 TURBO 3").is_none()
+            && Message::decode("BOARD 7 nonsense").is_none()
+            && Message::decode("HELLO Black None  ").is_none()
+            && Message::decode("HELLO Purple None Crow").is_none()
+            && matches!(long_name, Some(Message::Hello(profile)) if profile.name.len() < 100)
+            && Message::decode("HOWDY").is_none(),
+    );
+}
+
+fn check_controls(checks: &mut Checks) {
+    // Taking another action's key hands that action the old key
+    let mut controls = Controls::load();
+    let rotate_key = controls.bindings()[&Action::Rotate].clone();
+    let hold_key = controls.bindings()[&Action::Hold].clone();
+    let swapped = controls.rebind(Action::Hold, rotate_key.clone());
+    checks.check(
+        "rebinding swaps conflicting keys",
+        swapped == Some(Action::Rotate)
+            && controls.bindings()[&Action::Hold] == rotate_key
+            && controls.bindings()[&Action::Rotate] == hold_key,
+    );
+
+    // A quick tap rotates, a long swipe down drops and a sideways drag does neither on release
+    let quick = Duration::from_millis(100);
+    checks.check(
+        "touch gestures",
+        touch::released_action(egui::vec2(2.0, 3.0), quick, false, 20.0) == Some(Action::Rotate)
+            && touch::released_action(egui::vec2(5.0, 120.0), quick, false, 20.0) == Some(Action::HardDrop)
+            && touch::released_action(egui::vec2(5.0, -120.0), quick, false, 20.0) == Some(Action::Hold)
+            && touch::released_action(egui::vec2(90.0, 10.0), quick, true, 20.0).is_none(),
+    );
+
+    // Holding right moves once, waits out the DAS, then repeats every ARR
+    let handling = Handling::default();
+    let mut shift = AutoShift::default();
+    let start = Instant::now();
+    let held = |pressed| ((false, false), (pressed, true));
+    let at = |ms| start + Duration::from_millis(ms);
+    checks.check(
+        "auto shift repeats after the delay",
+        shift.update(held(true), &handling, start) == 1
+            && shift.update(held(false), &handling, at(100)) == 0
+            && shift.update(held(false), &handling, at(170)) == 1
+            && shift.update(held(false), &handling, at(270)) == 2
+            && shift.update(((false, false), (false, false)), &handling, at(280)) == 0,
+    );
+
+    // Between pieces a rotation waits for the next one, but a hard drop is dropped
+    let mut queue = VecDeque::new();
+    input_queue::push_input(&mut queue, Action::Rotate, true);
+    input_queue::push_input(&mut queue, Action::HardDrop, true);
+    input_queue::push_input(&mut queue, Action::HardDrop, false);
+    checks.check("inputs buffer between pieces", queue == [Action::Rotate, Action::HardDrop]);
+
+    // In mouse mode the piece centres itself under the cursor without leaving the board
+    let board = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(GRID_WIDTH as f32 * 10.0, 210.0));
+    checks.check(
+        "mouse column targeting",
+        mouse::target_column(105.0, board, 3) == 9
+            && mouse::target_column(1.0, board, 4) == 0
+            && mouse::target_column(399.0, board, 4) == GRID_WIDTH as i32 - 4,
+    );
+
+    // A saved profile brings its keys back after they're changed, and survives a reload
+    let mut controls = Controls::load();
+    let saved_keys = controls.bindings().clone();
+    controls.save_profile(" Crow, Jr. ");
+    controls.apply_profile("Left-handed");
+    let left_handed = controls.bindings()[&Action::MoveLeft] == Binding::Key(egui::Key::A);
+    let mut reloaded = Controls::load();
+    reloaded.apply_profile("Crow, Jr.");
+    let in_config = fs::read_to_string(data_dir::path(config::CONFIG_FILE))
+        .is_ok_and(|text| text.contains("[[control_profiles]]") && text.contains("name = \"Crow, Jr.\""));
+    checks.check(
+        "control profiles",
+        left_handed && in_config && *reloaded.bindings() == saved_keys && reloaded.saved_profiles().eq(["Crow, Jr."]),
+    );
+
+    // Profiles go through the config's checks, and an old control_profiles.txt is read until
+    // config.toml has profiles of its own
+    let text = fs::read_to_string(data_dir::path(config::CONFIG_FILE)).unwrap_or_default();
+    let mut edited = config::Config::load().unwrap_or_default();
+    let mut profiles = edited.control_profiles.clone().unwrap_or_default();
+    let mut copy = config::ControlProfile { name: "Crow copy".to_string(), ..profiles[0].clone() };
+    copy.keybindings.entry("keyboard".to_string()).or_default().insert(Action::Hold, "key:NoSuchKey".to_string());
+    profiles.push(copy.clone());
+    for name in ["Crow copy", "Vim", "  "] {
+        profiles.push(config::ControlProfile { name: name.to_string(), ..copy.clone() });
+    }
+    edited.control_profiles = Some(profiles);
+    edited.validate();
+    let validated = edited.control_profiles.as_ref().is_some_and(|profiles| {
+        profiles.len() == 2 && profiles[1].keybindings.get("keyboard").is_some_and(|keys| !keys.contains_key(&Action::Hold))
+    });
+    edited.control_profiles = None;
+    edited.save().ok();
+    fs::write(data_dir::path(controls::OLD_CONTROL_PROFILES_FILE), "Old crow,keyboard,Hold,key:F3\n").ok();
+    let old = Controls::load().saved_profiles().eq(["Old crow"]);
+    fs::remove_file(data_dir::path(controls::OLD_CONTROL_PROFILES_FILE)).ok();
+    fs::write(data_dir::path(config::CONFIG_FILE), text).ok();
+    checks.check("control profiles are validated and brought over", validated && old);
+
+    // Every preset binds every action, each to its own key
+    let presets_complete = controls::PRESETS.iter().all(|&preset| {
+        let mut controls = Controls::load();
+        controls.apply_profile(preset);
+        let keys: HashSet<String> = controls.bindings().values().map(Binding::label).collect();
+        controls.bindings().len() == Action::ALL.len() && keys.len() == Action::ALL.len()
+    });
+    checks.check("control presets are complete", presets_complete);
+
+    // The on-screen keyboard wraps around its edges and ends on delete and done
+    checks.check(
+        "on-screen keyboard navigation",
+        name_entry::picker_key(name_entry::move_cursor((0, 0), -1, 0)) == PickerKey::Char('J')
+            && name_entry::picker_key(name_entry::move_cursor((7, 3), 0, 1)) == PickerKey::Done
+            && name_entry::picker_key(name_entry::move_cursor((7, 4), 1, 0)) == PickerKey::Delete
+            && name_entry::move_cursor((2, 0), 0, -1).1 == 4,
+    );
+
+    // Bigger clears rumble harder
+    let rumble = |lines| gamepad::clear_rumble(lines).0;
+    checks.check("rumble scales with the clear", rumble(0) == 0.0 && rumble(1) < rumble(3) && rumble(3) < rumble(4));
+
+    // A controller gets its own default bindings, and the stick only counts past the deadzone
+    controls.set_active_device(InputDevice::Gamepad("self-test".to_string()));
+    controls.set_buttons(HashSet::from(["South".to_string()]), HashSet::from(["South".to_string()]));
+    let input = egui::InputState::default();
+    checks.check(
+        "gamepad buttons trigger actions",
+        controls.pressed(&input, Action::Rotate)
+            && !controls.pressed(&input, Action::Hold)
+            && gamepad::stick_directions(0.3, 0.0, 0.5).is_empty()
+            && gamepad::stick_directions(-0.8, 0.0, 0.5) == ["DPadLeft"],
+    );
+}
+
+pub fn run() -> bool {
+    // Work in a scratch directory so the player's saved files are never touched
+    let scratch = env::temp_dir().join(format!("crows-tetris-self-test-{}", std::process::id()));
+    let original = env::current_dir();
+    if fs::create_dir_all(&scratch).is_err() || env::set_current_dir(&scratch).is_err() {
+        println!("FAIL could not set up {}", scratch.display());
+        return false;
+    }
+
+    let mut checks = Checks { failures: 0 };
+    check_gameplay(&mut checks);
+    check_persistence(&mut checks);
+    check_online(&mut checks);
+    check_controls(&mut checks);
+
+    if let Ok(original) = original {
+        env::set_current_dir(original).ok();
+    }
+    fs::remove_dir_all(&scratch).ok();
+
+    if checks.failures == 0 {
+        println!("self-test passed");
+    } else {
+        println!("self-test failed: {} check(s)", checks.failures);
+    }
+    checks.failures == 0
+}
@@ -0,0 +1,461 @@
+// The settings screen. Settings are saved to config.toml (see config.rs)
+// whenever the player leaves the screen or closes the game.
+
+use eframe::egui;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::controls::{self, Action, Binding};
+use crate::file_errors::Retry;
+use crate::gamepad::{MAX_DEADZONE, MIN_DEADZONE};
+use crate::i18n::Language;
+use crate::one_switch::{MAX_SCAN_INTERVAL_MS, MIN_SCAN_INTERVAL_MS};
+use crate::theme::CellStyle;
+use crate::{skin, sound_pack, BackgroundBehavior, CrowsTetris, GameState};
+
+pub const MIN_DROP_SPEED_MS: u64 = 50;
+pub const MAX_DROP_SPEED_MS: u64 = 1000;
+pub const MIN_UI_SCALE: f32 = 0.75;
+pub const MAX_UI_SCALE: f32 = 2.0;
+
+pub(crate) fn find_by_name<T: std::fmt::Debug + Copy>(all: &[T], name: &str) -> Option<T> {
+    all.iter().copied().find(|item| format!("{:?}", item) == name)
+}
+
+// The window as it was left last time, read before the game starts to open the window that way
+pub fn with_saved_window(viewport: egui::ViewportBuilder) -> egui::ViewportBuilder {
+    let window = Config::load().unwrap_or_default().window;
+    let mut viewport = viewport.with_inner_size(egui::vec2(window.width as f32, window.height as f32));
+    if let Some((x, y)) = window.x.zip(window.y) {
+        viewport = viewport.with_position(egui::pos2(x as f32, y as f32));
+    }
+    viewport.with_fullscreen(window.fullscreen)
+}
+
+impl CrowsTetris {
+    // Everything the settings screen sets, and the key bindings
+    fn config(&self) -> Config {
+        let mut config = Config {
+            language: self.language,
+            keybindings: self.controls.keybindings(),
+            control_profiles: Some(self.controls.control_profiles()),
+            ..Config::default()
+        };
+
+        let gameplay = &mut config.gameplay;
+        gameplay.mode = self.mode;
+        gameplay.drop_speed_ms = self.drop_speed.as_millis() as u64;
+        gameplay.show_trajectory = self.show_trajectory;
+        gameplay.show_live_stats = self.show_live_stats;
+        gameplay.focus_mode = self.focus_mode;
+        gameplay.background_behavior = self.background_behavior;
+
+        let controls = &mut config.controls;
+        controls.control_profile = self.controls.profile_name.clone();
+        controls.das_ms = self.handling.das.as_millis() as u64;
+        controls.arr_ms = self.handling.arr.as_millis() as u64;
+        controls.soft_drop_factor = self.handling.soft_drop_factor;
+        controls.gamepad_deadzone = self.gamepads.deadzone;
+        controls.rumble = self.gamepads.rumble;
+        controls.touch_controls = self.touch_controls;
+        controls.mouse_controls = self.mouse_controls;
+        controls.one_switch = self.one_switch;
+        controls.scan_interval_ms = self.scan_interval.as_millis() as u64;
+        controls.show_input_display = self.show_input_display;
+
+        let audio = &mut config.audio;
+        audio.master_volume = self.audio.master_volume;
+        audio.effects_volume = self.audio.effects_volume;
+        audio.music_volume = self.audio.music_volume;
+        audio.announce_pieces = self.announce_pieces;
+        audio.sound_pack = self.audio.sound_pack.as_ref().map(|pack| pack.name.clone());
+        audio.menu_music = self.menu_music;
+        audio.gameplay_music = self.gameplay_music;
+
+        let display = &mut config.display;
+        display.theme = Some(self.theme().name.clone());
+        display.skin = self.skin.as_ref().map(|skin| skin.name.clone());
+        display.cell_style = self.cell_style;
+        display.show_grid_lines = self.show_grid_lines;
+        display.screen_shake = self.screen_shake;
+        display.fancy_graphics = self.fancy_graphics;
+        display.ui_scale = self.ui_scale;
+
+        let window = &mut config.window;
+        window.width = self.window_size.x.round() as u32;
+        window.height = self.window_size.y.round() as u32;
+        window.x = self.window_position.map(|position| position.x.round() as i32);
+        window.y = self.window_position.map(|position| position.y.round() as i32);
+        window.fullscreen = self.fullscreen;
+
+        config
+    }
+
+    pub(crate) fn save_settings(&mut self) {
+        let saved = self.config().save();
+        self.check_saved(saved, Retry::SaveSettings);
+    }
+
+    // A config file that can't be read leaves the settings as they are
+    fn read_config_or_report(&mut self) -> Option<Config> {
+        match Config::load() {
+            Ok(config) => {
+                self.clear_file_error(&Retry::LoadSettings);
+                Some(config)
+            }
+            Err(error) => {
+                self.report_failure(Retry::LoadSettings, error);
+                None
+            }
+        }
+    }
+
+    // Needs the egui context to load the saved skin's texture
+    pub(crate) fn load_settings(&mut self, ctx: &egui::Context) {
+        if let Some(config) = self.read_config_or_report() {
+            self.apply_config(ctx, &config, true);
+        }
+    }
+
+    // The config file changed while the game was running: everything but what's only read at launch
+    pub(crate) fn reload_settings(&mut self, ctx: &egui::Context) {
+        if let Some(config) = self.read_config_or_report() {
+            self.apply_config(ctx, &config, false);
+            self.controls.reload_bindings(&config.keybindings);
+            self.controls.reload_profiles(config.control_profiles.as_deref());
+        }
+    }
+
+    // The mode and the window are only read at launch; changed mid-game they'd move the window
+    // or switch the mode under the player
+    fn apply_config(&mut self, ctx: &egui::Context, config: &Config, at_launch: bool) {
+        self.language = config.language;
+
+        let gameplay = &config.gameplay;
+        if at_launch {
+            self.mode = gameplay.mode;
+        }
+        self.drop_speed = Duration::from_millis(gameplay.drop_speed_ms);
+        self.show_trajectory = gameplay.show_trajectory;
+        self.show_live_stats = gameplay.show_live_stats;
+        self.focus_mode = gameplay.focus_mode;
+        self.background_behavior = gameplay.background_behavior;
+
+        let controls = &config.controls;
+        self.controls.profile_name = controls.control_profile.clone();
+        self.handling.das = Duration::from_millis(controls.das_ms);
+        self.handling.arr = Duration::from_millis(controls.arr_ms);
+        self.handling.soft_drop_factor = controls.soft_drop_factor;
+        self.gamepads.deadzone = controls.gamepad_deadzone;
+        self.gamepads.rumble = controls.rumble;
+        self.touch_controls = controls.touch_controls;
+        self.mouse_controls = controls.mouse_controls;
+        self.one_switch = controls.one_switch;
+        self.scan_interval = Duration::from_millis(controls.scan_interval_ms);
+        self.show_input_display = controls.show_input_display;
+
+        let audio = &config.audio;
+        self.audio.master_volume = audio.master_volume;
+        self.audio.effects_volume = audio.effects_volume;
+        self.audio.music_volume = audio.music_volume;
+        self.announce_pieces = audio.announce_pieces;
+        match &audio.sound_pack {
+            Some(name) => self.load_sound_pack_setting(name.clone()),
+            None => self.audio.sound_pack = None,
+        }
+        self.menu_music = audio.menu_music;
+        self.gameplay_music = audio.gameplay_music;
+
+        let display = &config.display;
+        if let Some(name) = &display.theme {
+            match self.themes.iter().position(|theme| theme.name == *name) {
+                Some(theme) => self.theme = theme,
+                None => self.report_file_error(format!("{} {}", self.tr("Theme not found:"), name), None),
+            }
+        }
+        self.skin = None;
+        if let Some(name) = &display.skin {
+            match skin::load_skin(ctx, name) {
+                Ok(skin) => self.skin = Some(skin),
+                Err(error) => self.report_file_error(error, None),
+            }
+        }
+        self.cell_style = display.cell_style;
+        self.show_grid_lines = display.show_grid_lines;
+        self.screen_shake = display.screen_shake;
+        self.fancy_graphics = display.fancy_graphics;
+        self.ui_scale = display.ui_scale;
+
+        if at_launch {
+            let window = &config.window;
+            self.window_size = egui::vec2(window.width as f32, window.height as f32);
+            self.window_position = window.x.zip(window.y).map(|(x, y)| egui::pos2(x as f32, y as f32));
+            self.fullscreen = window.fullscreen;
+        }
+    }
+
+    pub(crate) fn load_sound_pack_setting(&mut self, name: String) {
+        match sound_pack::load_sound_pack(&name) {
+            Ok(pack) => self.audio.sound_pack = Some(pack),
+            Err(error) => {
+                self.audio.sound_pack = None;
+                self.report_failure(Retry::LoadSoundPack(name), error);
+            }
+        }
+    }
+
+    // Opens the settings screen; leaving it goes back to the screen it was opened from
+    pub(crate) fn open_settings(&mut self) {
+        self.settings_return = self.state;
+        self.rebinding = None;
+        self.rebind_swap = None;
+        self.state = GameState::Settings;
+    }
+
+    fn close_settings(&mut self) {
+        self.save_settings();
+        self.rebinding = None;
+        self.state = self.settings_return;
+    }
+
+    pub(crate) fn render_settings_screen(&mut self, ctx: &egui::Context) {
+        if self.rebinding.is_some() {
+            self.capture_rebinding(ctx);
+        } else if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.close_settings();
+            return;
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.vertical_centered(|ui| ui.heading(self.tr("Settings")));
+                ui.add_space(10.0);
+
+                ui.strong(self.tr("Gameplay"));
+                self.render_gameplay_settings(ui);
+                ui.add_space(10.0);
+
+                ui.strong(self.tr("Controls"));
+                self.render_control_settings(ui);
+                ui.add_space(10.0);
+
+                ui.strong(self.tr("Handling"));
+                self.render_handling_settings(ui);
+                ui.add_space(10.0);
+
+                ui.strong(self.tr("Audio"));
+                let label = self.tr("Master volume");
+                ui.add(egui::Slider::new(&mut self.audio.master_volume, 0.0..=1.0).text(label));
+                let label = self.tr("Effects volume");
+                ui.add(egui::Slider::new(&mut self.audio.effects_volume, 0.0..=1.0).text(label));
+                let label = self.tr("Music volume");
+                ui.add(egui::Slider::new(&mut self.audio.music_volume, 0.0..=1.0).text(label));
+                ui.checkbox(&mut self.announce_pieces, self.language.tr("Announce each piece and the next with a sound"));
+                self.render_sound_pack_picker(ui);
+                self.render_music_settings(ui);
+                ui.add_space(10.0);
+
+                ui.strong(self.tr("Display"));
+                self.render_display_settings(ui);
+                ui.add_space(20.0);
+
+                ui.vertical_centered(|ui| {
+                    if ui.button(self.tr("Back")).clicked() {
+                        self.close_settings();
+                    }
+                });
+            });
+        });
+    }
+
+    fn render_gameplay_settings(&mut self, ui: &mut egui::Ui) {
+        let mut drop_speed_ms = self.drop_speed.as_millis() as u64;
+        let label = self.tr("Drop interval (ms)");
+        if ui
+            .add(egui::Slider::new(&mut drop_speed_ms, MIN_DROP_SPEED_MS..=MAX_DROP_SPEED_MS).text(label))
+            .changed()
+        {
+            self.drop_speed = Duration::from_millis(drop_speed_ms);
+        }
+
+        ui.checkbox(&mut self.show_trajectory, self.language.tr("Beginner column hints"));
+        ui.checkbox(&mut self.show_live_stats, self.language.tr("Live stats (PPS, APM, KPP)"));
+        ui.checkbox(&mut self.focus_mode, self.language.tr("Focus mode (hide score and labels)"));
+        ui.checkbox(&mut self.one_switch, self.language.tr("One-switch mode (the drop key places pieces)"))
+            .on_hover_text(self.language.tr("Rotation, then column, cycle by themselves; press to pick each"));
+        ui.add_enabled_ui(self.one_switch, |ui| {
+            let mut scan_ms = self.scan_interval.as_millis() as u64;
+            let label = self.tr("Scan step (ms)");
+            if ui
+                .add(egui::Slider::new(&mut scan_ms, MIN_SCAN_INTERVAL_MS..=MAX_SCAN_INTERVAL_MS).text(label))
+                .changed()
+            {
+                self.scan_interval = Duration::from_millis(scan_ms);
+            }
+        });
+        ui.checkbox(&mut self.touch_controls, self.language.tr("Touch controls (swipe and tap the board)"));
+        ui.checkbox(&mut self.mouse_controls, self.language.tr("Mouse controls (piece follows the cursor)"))
+            .on_hover_text(self.language.tr("Left click rotates, the wheel rotates the other way, right click drops"));
+        ui.checkbox(&mut self.show_input_display, self.language.tr("Show pressed keys (for streaming)"));
+
+        egui::ComboBox::from_label(self.tr("When unfocused"))
+            .selected_text(self.tr(self.background_behavior.label()))
+            .show_ui(ui, |ui| {
+                for behavior in BackgroundBehavior::ALL {
+                    ui.selectable_value(&mut self.background_behavior, behavior, self.language.tr(behavior.label()));
+                }
+            });
+    }
+
+    fn render_control_settings(&mut self, ui: &mut egui::Ui) {
+        self.render_control_profiles(ui);
+        // Each device has its own bindings; these belong to whichever was used last
+        ui.label(format!("{} {}", self.tr("Device:"), self.device_label()));
+        egui::Grid::new("bindings").striped(true).show(ui, |ui| {
+            for action in Action::ALL {
+                ui.label(self.tr(action.label()));
+                let binding = if self.rebinding == Some(action) {
+                    self.tr("Press a key or button...").to_string()
+                } else {
+                    self.controls.bindings().get(&action).map_or("-".to_string(), Binding::label)
+                };
+                if ui.button(binding).clicked() {
+                    self.rebinding = Some(action);
+                }
+                ui.end_row();
+            }
+        });
+        let label = self.tr("Stick deadzone");
+        ui.add(egui::Slider::new(&mut self.gamepads.deadzone, MIN_DEADZONE..=MAX_DEADZONE).text(label));
+        ui.checkbox(&mut self.gamepads.rumble, self.language.tr("Controller rumble"));
+        if let Some((action, other)) = self.rebind_swap {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("{} {} → {}", self.tr("Key was taken; swapped:"), self.tr(action.label()), self.tr(other.label())),
+            );
+        }
+    }
+
+    fn render_control_profiles(&mut self, ui: &mut egui::Ui) {
+        let preset = controls::PRESETS.into_iter().find(|&preset| self.controls.profile_name.as_deref() == Some(preset));
+        let current = match (preset, &self.controls.profile_name) {
+            (Some(preset), _) => self.tr(preset).to_string(),
+            (None, Some(name)) => name.clone(),
+            (None, None) => self.tr("Custom").to_string(),
+        };
+        let mut chosen = None;
+        egui::ComboBox::from_label(self.tr("Control profile"))
+            .selected_text(current)
+            .show_ui(ui, |ui| {
+                for preset in controls::PRESETS {
+                    if ui.selectable_label(false, self.language.tr(preset)).clicked() {
+                        chosen = Some(preset.to_string());
+                    }
+                }
+                for name in self.controls.saved_profiles() {
+                    if ui.selectable_label(false, name).clicked() {
+                        chosen = Some(name.to_string());
+                    }
+                }
+            });
+        if let Some(name) = chosen {
+            self.controls.apply_profile(&name);
+        }
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.new_control_profile_name);
+            if ui.button(self.tr("Save profile")).clicked() {
+                self.controls.save_profile(&self.new_control_profile_name);
+                self.new_control_profile_name.clear();
+            }
+            if let (None, Some(name)) = (preset, self.controls.profile_name.clone()) {
+                if ui.button(self.tr("Delete profile")).clicked() {
+                    self.controls.delete_profile(&name);
+                }
+            }
+        });
+    }
+
+    // The next key pressed becomes the binding being changed; Escape cancels
+    fn capture_rebinding(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.rebinding else {
+            return;
+        };
+        let pressed = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key { key, pressed: true, .. } => Some(*key),
+                _ => None,
+            })
+        });
+        match pressed {
+            Some(egui::Key::Escape) => self.rebinding = None,
+            Some(key) => {
+                self.rebind_swap = self.controls.rebind(action, Binding::Key(key)).map(|other| (action, other));
+                self.rebinding = None;
+            }
+            None => {}
+        }
+    }
+
+    fn render_display_settings(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label(self.tr("Language"))
+            .selected_text(self.language.native_name())
+            .show_ui(ui, |ui| {
+                for language in Language::ALL {
+                    ui.selectable_value(&mut self.language, language, language.native_name());
+                }
+            });
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label(self.language.tr("Theme"))
+                .selected_text(&self.themes[self.theme].name)
+                .show_ui(ui, |ui| {
+                    for (i, theme) in self.themes.iter().enumerate() {
+                        ui.selectable_value(&mut self.theme, i, &theme.name);
+                    }
+                });
+            if ui.small_button(self.language.tr("Reload")).clicked() {
+                self.reload_themes();
+            }
+        });
+
+        self.render_skin_picker(ui);
+        ui.add_enabled_ui(self.skin.is_none(), |ui| {
+            egui::ComboBox::from_label(self.language.tr("Cell style"))
+                .selected_text(self.language.tr(self.cell_style.label()))
+                .show_ui(ui, |ui| {
+                    for style in CellStyle::ALL {
+                        ui.selectable_value(&mut self.cell_style, style, self.language.tr(style.label()));
+                    }
+                });
+        });
+
+        let mut fullscreen = ui.ctx().input(|i| i.viewport().fullscreen).unwrap_or(false);
+        if ui.checkbox(&mut fullscreen, self.language.tr("Fullscreen (F11)")).changed() {
+            ui.ctx().send_viewport_cmd(egui::ViewportCommand::Fullscreen(fullscreen));
+        }
+
+        // Applied when the slider is let go; rescaling mid-drag would move it out from under the pointer
+        let id = ui.id().with("ui_scale");
+        let mut ui_scale = ui.data(|data| data.get_temp(id)).unwrap_or(self.ui_scale);
+        let response = ui.add(
+            egui::Slider::new(&mut ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE)
+                .step_by(0.05)
+                .custom_formatter(|value, _| format!("{:.0}%", value * 100.0))
+                .text(self.tr("UI scale")),
+        );
+        if response.dragged() {
+            ui.data_mut(|data| data.insert_temp(id, ui_scale));
+        } else {
+            ui.data_mut(|data| data.remove::<f32>(id));
+            self.ui_scale = ui_scale;
+        }
+
+        ui.checkbox(&mut self.show_grid_lines, self.language.tr("Grid lines and border"));
+        ui.checkbox(&mut self.screen_shake, self.language.tr("Screen shake"));
+        ui.add_enabled_ui(self.fancy_graphics_available(), |ui| {
+            ui.checkbox(&mut self.fancy_graphics, self.language.tr("Fancy graphics (glow)"))
+                .on_disabled_hover_text(self.language.tr("Needs the OpenGL renderer"));
+        });
+    }
+}
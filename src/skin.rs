@@ -0,0 +1,68 @@
+// Textured cell skins. A skin is a PNG in the skins directory holding eight
+// square tiles side by side: I, O, T, S, Z, J, L, then garbage. Without a
+// skin (or if it fails to load) cells are drawn as flat theme colors.
+
+use eframe::egui;
+use std::fs;
+
+const SKINS_DIR: &str = "skins";
+const TILES: usize = 8;
+
+pub struct Skin {
+    pub name: String,
+    texture: egui::TextureHandle,
+}
+
+// File names (without .png) of every skin in the skins directory
+pub fn skin_names() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(SKINS_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| {
+                    let path = entry.ok()?.path();
+                    if path.extension()? != "png" {
+                        return None;
+                    }
+                    Some(path.file_stem()?.to_string_lossy().into_owned())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+pub fn load_image(path: &str) -> Result<egui::ColorImage, String> {
+    let image = image::open(path).map_err(|error| format!("{}: {}", path, error))?.to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    Ok(egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
+
+pub fn skin_path(name: &str) -> String {
+    format!("{}/{}.png", SKINS_DIR, name)
+}
+
+pub fn load_skin(ctx: &egui::Context, name: &str) -> Result<Skin, String> {
+    let path = skin_path(name);
+    let image = load_image(&path)?;
+    if image.width() != image.height() * TILES {
+        return Err(format!("{}: expected {} square tiles in a row", path, TILES));
+    }
+
+    Ok(Skin {
+        name: name.to_string(),
+        texture: ctx.load_texture(format!("skin-{}", name), image, egui::TextureOptions::NEAREST),
+    })
+}
+
+impl Skin {
+    // Draws a filled grid cell (block cell or garbage), faded by `visibility`
+    pub fn paint_cell(&self, painter: &egui::Painter, rect: egui::Rect, cell: u8, visibility: f32) {
+        let tile = (cell as usize).clamp(1, TILES) - 1;
+        let uv = egui::Rect::from_min_max(
+            egui::pos2(tile as f32 / TILES as f32, 0.0),
+            egui::pos2((tile + 1) as f32 / TILES as f32, 1.0),
+        );
+        painter.image(self.texture.id(), rect, uv, egui::Color32::WHITE.gamma_multiply(visibility));
+    }
+}
@@ -0,0 +1,171 @@
+// Sound packs. A pack is a folder in the soundpacks directory with a file
+// per sound (move, rotate, lock, clear, item, tetris, game_over, and
+// piece_i to piece_l for the piece announcements), each a .wav or .ogg, that
+// plays instead of the built-in beep. Sounds a pack leaves out keep the
+// built-in one. WAV files are read here; OGG goes through the audio
+// backend's decoder.
+
+use eframe::egui;
+use rodio::buffer::SamplesBuffer;
+use rodio::{Decoder, Source};
+use std::fs::{self, File};
+use std::io::BufReader;
+
+use crate::audio::SoundEvent;
+use crate::CrowsTetris;
+
+const SOUND_PACKS_DIR: &str = "soundpacks";
+const WAV_PCM: u16 = 1;
+
+pub struct SoundPack {
+    pub name: String,
+    sounds: Vec<(SoundEvent, SamplesBuffer<i16>)>,
+}
+
+impl SoundPack {
+    pub fn sound(&self, event: SoundEvent) -> Option<&SamplesBuffer<i16>> {
+        self.sounds.iter().find(|(e, _)| *e == event).map(|(_, sound)| sound)
+    }
+}
+
+// Folder names of every pack in the soundpacks directory
+pub fn sound_pack_names() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(SOUND_PACKS_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| {
+                    let path = entry.ok()?.path();
+                    if !path.is_dir() {
+                        return None;
+                    }
+                    Some(path.file_name()?.to_string_lossy().into_owned())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+// 16-bit PCM only, which is what most editors export by default
+pub fn read_wav(bytes: &[u8]) -> Result<SamplesBuffer<i16>, String> {
+    if bytes.get(0..4) != Some(b"RIFF") || bytes.get(8..12) != Some(b"WAVE") {
+        return Err("not a WAV file".to_string());
+    }
+    let mut format = None;
+    let mut at = 12;
+    while let (Some(id), Some(size)) = (bytes.get(at..at + 4), u32_at(bytes, at + 4)) {
+        let body = at + 8;
+        let size = size as usize;
+        match id {
+            b"fmt " => {
+                let (Some(encoding), Some(channels), Some(rate), Some(bits)) =
+                    (u16_at(bytes, body), u16_at(bytes, body + 2), u32_at(bytes, body + 4), u16_at(bytes, body + 14))
+                else {
+                    return Err("broken format chunk".to_string());
+                };
+                if encoding != WAV_PCM || bits != 16 || channels == 0 || rate == 0 {
+                    return Err("only 16-bit PCM WAV files are supported".to_string());
+                }
+                format = Some((channels, rate));
+            }
+            b"data" => {
+                let Some((channels, rate)) = format else {
+                    return Err("data before the format chunk".to_string());
+                };
+                // Some writers leave the size at zero or too big; take what's there
+                let end = if size == 0 { bytes.len() } else { (body + size).min(bytes.len()) };
+                let data = &bytes[body.min(end)..end];
+                let samples = data.chunks_exact(2).map(|pair| i16::from_le_bytes([pair[0], pair[1]])).collect::<Vec<_>>();
+                return Ok(SamplesBuffer::new(channels, rate, samples));
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even length
+        at = body + size + size % 2;
+    }
+    Err("no sound data".to_string())
+}
+
+fn read_ogg(path: &str) -> Result<SamplesBuffer<i16>, String> {
+    let file = File::open(path).map_err(|error| error.to_string())?;
+    let decoder = Decoder::new(BufReader::new(file)).map_err(|error| error.to_string())?;
+    let (channels, rate) = (decoder.channels(), decoder.sample_rate());
+    Ok(SamplesBuffer::new(channels, rate, decoder.collect::<Vec<_>>()))
+}
+
+fn load_sound(stem: &str) -> Option<Result<SamplesBuffer<i16>, String>> {
+    let wav = format!("{}.wav", stem);
+    let ogg = format!("{}.ogg", stem);
+    if let Ok(bytes) = fs::read(&wav) {
+        Some(read_wav(&bytes).map_err(|error| format!("{}: {}", wav, error)))
+    } else if fs::metadata(&ogg).is_ok() {
+        Some(read_ogg(&ogg).map_err(|error| format!("{}: {}", ogg, error)))
+    } else {
+        None
+    }
+}
+
+// A pack has to have at least one sound, and every file in it has to load
+pub fn load_sound_pack(name: &str) -> Result<SoundPack, String> {
+    let mut sounds = Vec::new();
+    for event in SoundEvent::ALL {
+        if let Some(sound) = load_sound(&format!("{}/{}/{}", SOUND_PACKS_DIR, name, event.file_stem())) {
+            sounds.push((event, sound?));
+        }
+    }
+    if sounds.is_empty() {
+        return Err(format!("{}/{}: no sounds found", SOUND_PACKS_DIR, name));
+    }
+    Ok(SoundPack { name: name.to_string(), sounds })
+}
+
+impl CrowsTetris {
+    pub(crate) fn render_sound_pack_picker(&mut self, ui: &mut egui::Ui) {
+        let built_in = self.tr("Built-in sounds");
+        let current = self.audio.sound_pack.as_ref().map(|pack| pack.name.clone());
+        let mut picked = None;
+        egui::ComboBox::from_label(self.tr("Sound pack"))
+            .selected_text(current.as_deref().unwrap_or(built_in))
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(current.is_none(), built_in).clicked() {
+                    picked = Some(None);
+                }
+                for name in sound_pack_names() {
+                    if ui.selectable_label(current.as_ref() == Some(&name), &name).clicked() {
+                        picked = Some(Some(name));
+                    }
+                }
+            });
+
+        // Like skins, packs are read when picked, so edited files are picked up by picking the pack again
+        match picked {
+            Some(None) => {
+                self.audio.sound_pack = None;
+                self.sound_pack_error = None;
+            }
+            Some(Some(name)) => match load_sound_pack(&name) {
+                Ok(pack) => {
+                    self.audio.sound_pack = Some(pack);
+                    self.sound_pack_error = None;
+                }
+                Err(error) => {
+                    self.audio.sound_pack = None;
+                    self.sound_pack_error = Some(error);
+                }
+            },
+            None => {}
+        }
+        if let Some(error) = &self.sound_pack_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// Split timer for Sprint and Marathon: the run time is taken every 10 lines
+// and compared, live and on the results screen, with the player's best run in
+// that mode. Best splits are kept in a text file, one mode per line:
+//
+//     Sprint=31250,64100,98020,130400
+//
+// with each split in milliseconds since the run started.
+
+use eframe::egui;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::time::Duration;
+
+use crate::file_errors::Retry;
+use crate::{data_dir, format_time, CrowsTetris, GameMode, RunResult};
+
+pub const SPLITS_FILE: &str = "splits.txt";
+// Lines per split
+pub const SPLIT_LINES: u32 = 10;
+const AHEAD_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 220, 110);
+const BEHIND_COLOR: egui::Color32 = egui::Color32::from_rgb(240, 90, 80);
+
+pub type BestSplits = Vec<(GameMode, Vec<Duration>)>;
+
+pub fn load_best_splits() -> BestSplits {
+    let Ok(text) = fs::read_to_string(data_dir::path(SPLITS_FILE)) else {
+        return Vec::new();
+    };
+    text.lines()
+        .filter_map(|line| {
+            let (mode, splits) = line.split_once('=')?;
+            let mode = GameMode::ALL.into_iter().find(|candidate| format!("{:?}", candidate) == mode)?;
+            let splits = splits
+                .split(',')
+                .map(|ms| ms.parse().ok().map(Duration::from_millis))
+                .collect::<Option<Vec<_>>>()?;
+            Some((mode, splits))
+        })
+        .collect()
+}
+
+pub fn save_best_splits(best: &BestSplits) -> io::Result<()> {
+    let mut text = String::new();
+    for (mode, splits) in best {
+        let splits: Vec<String> = splits.iter().map(|split| split.as_millis().to_string()).collect();
+        writeln!(text, "{:?}={}", mode, splits.join(",")).ok();
+    }
+    data_dir::write_atomic(SPLITS_FILE, &text)
+}
+
+// Getting further beats getting there faster
+fn is_better(splits: &[Duration], best: &[Duration]) -> bool {
+    splits.len() > best.len() || (splits.len() == best.len() && splits.last() < best.last())
+}
+
+// "+1.250" behind or "-0.400" ahead of the best split
+fn split_difference(split: Duration, best: Duration) -> egui::RichText {
+    let (sign, difference, color) = if split > best {
+        ('+', split - best, BEHIND_COLOR)
+    } else {
+        ('-', best - split, AHEAD_COLOR)
+    };
+    egui::RichText::new(format!("{}{}.{:03}", sign, difference.as_secs(), difference.subsec_millis()))
+        .monospace()
+        .color(color)
+}
+
+impl CrowsTetris {
+    pub(crate) fn splits_tracked(&self) -> bool {
+        matches!(self.mode, GameMode::Sprint | GameMode::Marathon)
+    }
+
+    pub(crate) fn best_splits(&self, mode: GameMode) -> &[Duration] {
+        self.best_splits
+            .iter()
+            .find(|(best_mode, _)| *best_mode == mode)
+            .map_or(&[], |(_, splits)| splits.as_slice())
+    }
+
+    // Takes a split for every 10 lines passed since the last one
+    pub(crate) fn record_splits(&mut self) {
+        if !self.splits_tracked() {
+            return;
+        }
+        while self.lines >= (self.splits.len() as u32 + 1) * SPLIT_LINES {
+            self.splits.push(self.play_time());
+        }
+    }
+
+    // At the end of a run; returns whether it set new best splits
+    pub(crate) fn save_splits_if_best(&mut self) -> bool {
+        if !self.splits_tracked() || self.splits.is_empty() || !is_better(&self.splits, self.best_splits(self.mode)) {
+            return false;
+        }
+        let mode = self.mode;
+        self.best_splits.retain(|(best_mode, _)| *best_mode != mode);
+        self.best_splits.push((mode, self.splits.clone()));
+        self.check_saved(save_best_splits(&self.best_splits), Retry::SaveSplits);
+        true
+    }
+
+    // The latest split during play, against the same split of the best run
+    pub(crate) fn render_live_split(&self, ui: &mut egui::Ui) {
+        let Some(&split) = self.splits.last().filter(|_| self.splits_tracked()) else {
+            return;
+        };
+        let lines = self.splits.len() as u32 * SPLIT_LINES;
+        ui.horizontal(|ui| {
+            ui.label(format!("{} {}: {}", lines, self.tr("lines"), format_time(split)));
+            if let Some(&best) = self.best_splits(self.mode).get(self.splits.len() - 1) {
+                ui.label(split_difference(split, best));
+            }
+        });
+    }
+
+    pub(crate) fn render_split_results(&self, ui: &mut egui::Ui, result: &RunResult) {
+        if result.splits.is_empty() {
+            return;
+        }
+        ui.add_space(10.0);
+        ui.strong(self.tr("Splits"));
+        if result.new_best_splits {
+            ui.label(self.tr("New best splits!"));
+        }
+        egui::Grid::new("splits").show(ui, |ui| {
+            for (i, &split) in result.splits.iter().enumerate() {
+                ui.label(format!("{} {}", (i as u32 + 1) * SPLIT_LINES, self.tr("lines")));
+                ui.label(egui::RichText::new(format_time(split)).monospace());
+                if let Some(&best) = result.previous_best_splits.get(i) {
+                    ui.label(split_difference(split, best));
+                }
+                ui.end_row();
+            }
+        });
+    }
+}
@@ -0,0 +1,146 @@
+// Live pace stats for competitive players: pieces per second, attack (garbage
+// lines a clear would send) per minute, and key presses per piece. Also the
+// stack height over the run, as a sparkline in play and a graph afterwards,
+// and a tally of the pieces dealt for judging randomizers.
+
+use eframe::egui;
+use std::time::Duration;
+
+use crate::battle::garbage_for_lines;
+use crate::controls::Action;
+use crate::effects::DANGER_ROWS;
+use crate::{BlockType, CrowsTetris, GRID_HEIGHT};
+
+// Pieces shown in the live sparkline
+const SPARKLINE_SAMPLES: usize = 40;
+const GRAPH_COLOR: egui::Color32 = egui::Color32::from_rgb(120, 200, 255);
+// Pieces without an I before the drought counter turns red
+const LONG_I_DROUGHT: u32 = 12;
+
+// Presses that move the piece; pausing and menu keys don't count
+const COUNTED_ACTIONS: [Action; 7] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Rotate,
+    Action::RotateCounterclockwise,
+    Action::SoftDrop,
+    Action::HardDrop,
+    Action::Hold,
+];
+
+// How many of each piece this run has dealt, and how long the player has gone without an I
+#[derive(Debug, Default, PartialEq)]
+pub struct PieceTally {
+    counts: [u32; 7],
+    pub since_i: u32,
+    longest_i_drought: u32,
+}
+
+impl PieceTally {
+    fn deal(&mut self, block_type: BlockType) {
+        self.counts[block_type as usize] += 1;
+        if block_type == BlockType::I {
+            self.since_i = 0;
+        } else {
+            self.since_i += 1;
+            self.longest_i_drought = self.longest_i_drought.max(self.since_i);
+        }
+    }
+
+    pub fn count(&self, block_type: BlockType) -> u32 {
+        self.counts[block_type as usize]
+    }
+}
+
+impl CrowsTetris {
+    pub(crate) fn count_dealt(&mut self, block_type: BlockType) {
+        self.piece_tally.deal(block_type);
+    }
+
+    // Pieces since the last I, so the player can judge whether to wait for a Tetris
+    pub(crate) fn render_i_drought(&self, ui: &mut egui::Ui) {
+        let since_i = self.piece_tally.since_i;
+        let text = egui::RichText::new(format!("{} {}", self.tr("No I for"), since_i));
+        ui.label(if since_i >= LONG_I_DROUGHT { text.strong().color(egui::Color32::RED) } else { text })
+            .on_hover_text(self.tr("Pieces dealt since the last I piece"));
+    }
+
+    pub(crate) fn render_piece_tally(&self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(self.tr("Pieces")).show(ui, |ui| {
+            let total = BlockType::ALL.iter().map(|&block_type| self.piece_tally.count(block_type)).sum::<u32>().max(1);
+            for block_type in BlockType::ALL {
+                let count = self.piece_tally.count(block_type);
+                ui.horizontal(|ui| {
+                    ui.colored_label(self.theme().piece_color(block_type), block_type.letter().to_string());
+                    ui.label(format!("{} ({}%)", count, count * 100 / total));
+                });
+            }
+            ui.label(format!(
+                "{} {} / {}",
+                self.tr("I drought:"),
+                self.piece_tally.since_i,
+                self.piece_tally.longest_i_drought
+            ));
+        });
+    }
+
+    pub(crate) fn count_keys(&mut self, ctx: &egui::Context) {
+        let pressed = COUNTED_ACTIONS
+            .iter()
+            .filter(|&&action| self.action_pressed(ctx, action))
+            .count();
+        self.keys_pressed += pressed as u32;
+    }
+
+    pub(crate) fn count_attack(&mut self, lines_cleared: usize) {
+        self.attack += garbage_for_lines(lines_cleared) as u32;
+    }
+
+    // Taken after every piece locks
+    pub(crate) fn record_stack_height(&mut self) {
+        let top = self.grid.iter().position(|row| row.iter().any(|&cell| cell != 0));
+        let height = GRID_HEIGHT - top.unwrap_or(GRID_HEIGHT);
+        self.stack_heights.push((self.play_time(), height));
+    }
+
+    pub(crate) fn render_stack_sparkline(&self, ui: &mut egui::Ui) {
+        let recent = &self.stack_heights[self.stack_heights.len().saturating_sub(SPARKLINE_SAMPLES)..];
+        let width = ui.available_width();
+        draw_stack_graph(ui, recent, egui::vec2(width, 24.0));
+    }
+
+    pub(crate) fn render_live_stats(&self, ui: &mut egui::Ui) {
+        if !self.show_live_stats {
+            return;
+        }
+        let seconds = self.play_time().as_secs_f64().max(1.0);
+        let pieces = self.pieces_placed.max(1) as f64;
+        ui.label(format!("PPS {:.2}", self.pieces_placed as f64 / seconds));
+        ui.label(format!("APM {:.1}", self.attack as f64 * 60.0 / seconds));
+        ui.label(format!("KPP {:.1}", self.keys_pressed as f64 / pieces));
+    }
+}
+
+// Stack height against run time, with the rows near the top shaded as the danger zone
+pub fn draw_stack_graph(ui: &mut egui::Ui, heights: &[(Duration, usize)], size: egui::Vec2) {
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, egui::Color32::from_black_alpha(120));
+    let danger_height = rect.height() * DANGER_ROWS as f32 / GRID_HEIGHT as f32;
+    let danger_zone = rect.with_max_y(rect.min.y + danger_height);
+    painter.rect_filled(danger_zone, 2.0, egui::Color32::from_rgba_unmultiplied(220, 30, 30, 50));
+
+    let (Some(&(first, _)), Some(&(last, _))) = (heights.first(), heights.last()) else {
+        return;
+    };
+    let span = (last - first).as_secs_f32().max(f32::EPSILON);
+    let points: Vec<egui::Pos2> = heights
+        .iter()
+        .map(|&(time, height)| {
+            let x = rect.min.x + (time - first).as_secs_f32() / span * rect.width();
+            let y = rect.max.y - height as f32 / GRID_HEIGHT as f32 * rect.height();
+            egui::pos2(x, y)
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, GRAPH_COLOR)));
+}
@@ -0,0 +1,110 @@
+// Sticky gravity: after a clear, every connected group of same-colored cells
+// falls as one piece until it lands, which can set off further clears.
+
+use crate::{AgeGrid, CrowsTetris, Grid, GRID_HEIGHT, GRID_WIDTH};
+
+fn find_groups(grid: &Grid) -> Vec<Vec<(usize, usize)>> {
+    let mut visited = [[false; GRID_WIDTH]; GRID_HEIGHT];
+    let mut groups = Vec::new();
+
+    for y in 0..GRID_HEIGHT {
+        for x in 0..GRID_WIDTH {
+            if grid[y][x] == 0 || visited[y][x] {
+                continue;
+            }
+
+            let color = grid[y][x];
+            let mut group = Vec::new();
+            let mut stack = vec![(y, x)];
+            visited[y][x] = true;
+
+            while let Some((cy, cx)) = stack.pop() {
+                group.push((cy, cx));
+
+                let mut neighbours = Vec::with_capacity(4);
+                if cy > 0 {
+                    neighbours.push((cy - 1, cx));
+                }
+                if cy + 1 < GRID_HEIGHT {
+                    neighbours.push((cy + 1, cx));
+                }
+                if cx > 0 {
+                    neighbours.push((cy, cx - 1));
+                }
+                if cx + 1 < GRID_WIDTH {
+                    neighbours.push((cy, cx + 1));
+                }
+
+                for (ny, nx) in neighbours {
+                    if !visited[ny][nx] && grid[ny][nx] == color {
+                        visited[ny][nx] = true;
+                        stack.push((ny, nx));
+                    }
+                }
+            }
+
+            groups.push(group);
+        }
+    }
+
+    groups
+}
+
+fn can_fall(grid: &Grid, group: &[(usize, usize)]) -> bool {
+    group.iter().all(|&(y, x)| {
+        y + 1 < GRID_HEIGHT && (grid[y + 1][x] == 0 || group.contains(&(y + 1, x)))
+    })
+}
+
+// Drops every group one row at a time until nothing can move, taking each cell's lock time
+// along with it. Returns whether anything fell.
+pub fn settle(grid: &mut Grid, locked_at: &mut AgeGrid) -> bool {
+    let mut anything_fell = false;
+
+    loop {
+        let mut groups = find_groups(grid);
+        // Let the lowest groups fall first so the ones resting on them can follow
+        groups.sort_by_key(|group| std::cmp::Reverse(group.iter().map(|&(y, _)| y).max()));
+
+        let mut moved = false;
+        for group in groups {
+            if !can_fall(grid, &group) {
+                continue;
+            }
+
+            let color = grid[group[0].0][group[0].1];
+            let ages: Vec<_> = group.iter().map(|&(y, x)| locked_at[y][x]).collect();
+            for &(y, x) in &group {
+                grid[y][x] = 0;
+                locked_at[y][x] = None;
+            }
+            for (&(y, x), &age) in group.iter().zip(&ages) {
+                grid[y + 1][x] = color;
+                locked_at[y + 1][x] = age;
+            }
+            moved = true;
+        }
+
+        if !moved {
+            return anything_fell;
+        }
+        anything_fell = true;
+    }
+}
+
+impl CrowsTetris {
+    // Runs the settle/clear loop after a line clear and returns the extra lines it cleared.
+    pub(crate) fn resolve_sticky_chain(&mut self) -> usize {
+        let mut chain_lines = 0;
+
+        while settle(&mut self.grid, &mut self.locked_at) {
+            let lines = self.clear_lines();
+            if lines == 0 {
+                break;
+            }
+            chain_lines += lines;
+        }
+
+        chain_lines
+    }
+}
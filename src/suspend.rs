@@ -0,0 +1,204 @@
+// Suspending a run: "Save & Quit" in the pause menu writes the run to
+// suspended.toml and "Continue" on the start screen picks it up again, once.
+// The piece sequence isn't stored as such; the run's seed and how many pieces
+// it has drawn are enough to put the randomizer back exactly where it was.
+// Runs with opponents, and modes with more going on than the board (the
+// daily, missions, party items), can't be suspended.
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use toml_edit::{value, Array, DocumentMut, Item, Table};
+
+use crate::board_text::{board_from_ascii, board_to_ascii};
+use crate::classic::Ruleset;
+use crate::randomizer::{PieceGenerator, Randomizer};
+use crate::settings::find_by_name;
+use crate::stats::PieceTally;
+use crate::{data_dir, Block, BlockType, CrowsTetris, GameMode, GameState, GRID_HEIGHT, GRID_WIDTH};
+
+pub const SUSPEND_FILE: &str = "suspended.toml";
+
+fn millis(times: &[Duration]) -> Array {
+    times.iter().map(|time| time.as_millis() as i64).collect()
+}
+
+fn read_millis(document: &DocumentMut, key: &str) -> Vec<Duration> {
+    let Some(array) = document.get(key).and_then(Item::as_array) else {
+        return Vec::new();
+    };
+    array.iter().filter_map(|ms| Some(Duration::from_millis(ms.as_integer()? as u64))).collect()
+}
+
+// The piece's cells as rows of 0s and 1s
+fn write_piece(block: &Block) -> Table {
+    let mut piece = Table::new();
+    piece["type"] = value(block.block_type.letter().to_string());
+    piece["x"] = value(block.position.0 as i64);
+    piece["y"] = value(block.position.1 as i64);
+    let shape: Array = block
+        .shape
+        .iter()
+        .map(|row| row.iter().map(|&cell| if cell != 0 { '1' } else { '0' }).collect::<String>())
+        .collect();
+    piece["shape"] = value(shape);
+    piece
+}
+
+fn read_piece(piece: &Table) -> Option<Block> {
+    let block_type = BlockType::from_letter(piece.get("type")?.as_str()?.chars().next()?)?;
+    let shape: Vec<Vec<u8>> = piece
+        .get("shape")?
+        .as_array()?
+        .iter()
+        .map(|row| Some(row.as_str()?.chars().map(|cell| (cell == '1') as u8).collect()))
+        .collect::<Option<_>>()?;
+    Some(Block {
+        block_type,
+        position: (piece.get("x")?.as_integer()? as i32, piece.get("y")?.as_integer()? as i32),
+        shape,
+    })
+}
+
+pub fn has_suspended_run() -> bool {
+    data_dir::path(SUSPEND_FILE).is_file()
+}
+
+impl CrowsTetris {
+    pub(crate) fn can_suspend(&self) -> bool {
+        !matches!(
+            self.mode,
+            GameMode::Battle
+                | GameMode::Versus
+                | GameMode::Online
+                | GameMode::Royale
+                | GameMode::Daily
+                | GameMode::Mission
+                | GameMode::Party
+        )
+    }
+
+    // Everything needed to pick the run up again, as written to the suspend and autosave files
+    pub(crate) fn run_snapshot(&self) -> DocumentMut {
+        let mut document = DocumentMut::new();
+        document["mode"] = value(format!("{:?}", self.mode));
+        document["ruleset"] = value(format!("{:?}", self.ruleset));
+        document["randomizer"] = value(format!("{:?}", self.randomizer));
+        document["start_level"] = value(self.start_level as i64);
+        // Seeds use all 64 bits, more than a TOML integer holds
+        document["seed"] = value(self.run_seed.to_string());
+        document["drawn"] = value(self.piece_generator.drawn() as i64);
+        document["queued"] = value(self.next_queue.len() as i64);
+        document["score"] = value(self.score as i64);
+        document["lines"] = value(self.lines as i64);
+        document["level"] = value(self.level as i64);
+        document["pieces_placed"] = value(self.pieces_placed as i64);
+        document["keys_pressed"] = value(self.keys_pressed as i64);
+        document["attack"] = value(self.attack as i64);
+        document["combo"] = value(self.combo as i64);
+        document["play_time_ms"] = value(self.play_time().as_millis() as i64);
+        document["section_started_ms"] = value(self.section_started.as_millis() as i64);
+        document["section_times_ms"] = value(millis(&self.section_times));
+        document["splits_ms"] = value(millis(&self.splits));
+        if let Some(next) = self.next_piece {
+            document["next_piece"] = value(next.letter().to_string());
+        }
+        if let Some(held) = self.held_piece {
+            document["held"] = value(held.letter().to_string());
+        }
+        document["hold_used"] = value(self.hold_used);
+        document["board"] = value(board_to_ascii(&self.grid));
+        if let Some(block) = &self.active_block {
+            document["piece"] = Item::Table(write_piece(block));
+        }
+        document
+    }
+
+    // If the run can't be saved the game stays paused, so it isn't lost
+    pub(crate) fn suspend_run(&mut self) {
+        if let Err(error) = data_dir::write_atomic(SUSPEND_FILE, &self.run_snapshot().to_string()) {
+            self.report_file_error(format!("{} {}", self.tr("Couldn't save the run:"), error), None);
+            return;
+        }
+        self.has_suspended_run = true;
+        self.state = GameState::StartScreen;
+    }
+
+    // Picks the suspended run back up after a countdown. The file is removed either way,
+    // so a run can only be continued once and a broken file doesn't stay around.
+    pub(crate) fn continue_suspended_run(&mut self) -> bool {
+        let path = data_dir::path(SUSPEND_FILE);
+        let document = fs::read_to_string(&path).ok().and_then(|text| text.parse::<DocumentMut>().ok());
+        fs::remove_file(&path).ok();
+        self.has_suspended_run = false;
+        let Some(document) = document else {
+            return false;
+        };
+        self.restore_run(&document).is_some()
+    }
+
+    pub(crate) fn restore_run(&mut self, document: &DocumentMut) -> Option<()> {
+        let text = |key| document.get(key)?.as_str();
+        let number = |key| document.get(key)?.as_integer();
+        let piece = |key| BlockType::from_letter(text(key)?.chars().next()?);
+
+        let mode = find_by_name(&GameMode::ALL, text("mode")?)?;
+        let ruleset = find_by_name(&Ruleset::ALL, text("ruleset")?)?;
+        let randomizer = find_by_name(&Randomizer::ALL, text("randomizer")?)?;
+        let seed: u64 = text("seed")?.parse().ok()?;
+        let grid = board_from_ascii(text("board")?).ok()?;
+        let active_block = read_piece(document.get("piece")?.as_table()?)?;
+
+        self.mode = mode;
+        self.ruleset = ruleset;
+        self.randomizer = randomizer;
+        self.start_level = number("start_level")? as u32;
+        self.reset_game();
+        // Only part of the run would be in the replay
+        self.replay_log = None;
+
+        // Draws the same pieces again to bring the randomizer back to where it was; the
+        // last few are the preview queue, and the rest were dealt already
+        self.seed_run(seed);
+        self.piece_generator = PieceGenerator::new(randomizer);
+        let drawn: Vec<BlockType> = (0..number("drawn")?)
+            .map(|_| self.piece_generator.next(&mut self.piece_rng))
+            .collect();
+        self.piece_tally = PieceTally::default();
+        let queued = (number("queued")? as usize).min(drawn.len());
+        for &block_type in &drawn[..drawn.len() - queued] {
+            self.count_dealt(block_type);
+        }
+        self.next_queue = drawn[drawn.len() - queued..].iter().copied().collect();
+
+        self.grid = grid;
+        self.locked_at = [[None; GRID_WIDTH]; GRID_HEIGHT];
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                if cell != 0 {
+                    self.locked_at[y][x] = Some(Instant::now());
+                }
+            }
+        }
+        self.active_block = Some(active_block);
+        self.next_piece = piece("next_piece");
+        self.held_piece = piece("held");
+        self.hold_used = document.get("hold_used").and_then(Item::as_bool).unwrap_or(false);
+        self.score = number("score")? as i32;
+        self.lines = number("lines")? as u32;
+        self.level = number("level")? as u32;
+        self.pieces_placed = number("pieces_placed")? as u32;
+        self.keys_pressed = number("keys_pressed").unwrap_or(0) as u32;
+        self.attack = number("attack").unwrap_or(0) as u32;
+        self.combo = number("combo").unwrap_or(0) as u32;
+        self.section_started = Duration::from_millis(number("section_started_ms").unwrap_or(0) as u64);
+        self.section_times = read_millis(document, "section_times_ms");
+        self.splits = read_millis(document, "splits_ms");
+        // The clock carries on from the saved play time once reset_game's countdown is over
+        let play_time = Duration::from_millis(number("play_time_ms")? as u64);
+        self.run_started = Instant::now() - play_time;
+        self.paused_time = Duration::ZERO;
+        self.paused_at = Some(Instant::now());
+        Some(())
+    }
+}
@@ -0,0 +1,259 @@
+// Color themes. The built-in crow theme is always available; more are read
+// from TOML files in the themes directory, e.g.
+//
+//     name = "Night"
+//     background = "#10101c"
+//     board = "#000000c0"
+//     grid_lines = "#ffffff10"
+//     border = "#a0a0b0"
+//     text = "#e0e0f0"
+//     garbage = "#707080"
+//
+//     backgrounds = ["night/dusk.png", "night/stars.png"]
+//
+//     [pieces]
+//     I = "#00f0f0"
+//     T = "#a000f0"
+//
+// Anything left out keeps the built-in color. Backgrounds are images (relative
+// to the themes directory) shown behind the board, one per level, starting
+// over after the last.
+
+use eframe::egui::{self, Color32};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use toml_edit::DocumentMut;
+
+use crate::file_errors::FileError;
+use crate::garbage::GARBAGE_CELL;
+use crate::skin::load_image;
+use crate::{BlockType, CrowsTetris};
+
+pub const THEMES_DIR: &str = "themes";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub background: Color32,
+    pub board: Color32,
+    pub grid_lines: Color32,
+    pub border: Color32,
+    pub text: Color32,
+    pub garbage: Color32,
+    pieces: [Color32; 7],
+    backgrounds: Vec<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            name: "Crow".to_string(),
+            background: Color32::DARK_RED,
+            board: Color32::from_black_alpha(140),
+            grid_lines: Color32::from_white_alpha(12),
+            border: Color32::from_gray(170),
+            text: Color32::from_gray(230),
+            garbage: Color32::GRAY,
+            pieces: BlockType::ALL.map(BlockType::color),
+            backgrounds: Vec::new(),
+        }
+    }
+}
+
+// "#rrggbb" or "#rrggbbaa"
+fn parse_color(text: &str) -> Option<Color32> {
+    let hex = text.strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color32::from_rgba_unmultiplied(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+// How a filled cell is drawn when no skin is in use
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum CellStyle {
+    Rounded,
+    Flat,
+    Beveled,
+    Outlined,
+}
+
+impl CellStyle {
+    pub const ALL: [CellStyle; 4] = [CellStyle::Rounded, CellStyle::Flat, CellStyle::Beveled, CellStyle::Outlined];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CellStyle::Rounded => "Rounded",
+            CellStyle::Flat => "Flat",
+            CellStyle::Beveled => "Beveled",
+            CellStyle::Outlined => "Outlined",
+        }
+    }
+
+    pub fn paint(self, painter: &egui::Painter, rect: egui::Rect, color: Color32) {
+        let size = rect.width();
+        match self {
+            CellStyle::Rounded => {
+                painter.rect_filled(rect.shrink(size * 0.06), size * 0.2, color);
+            }
+            CellStyle::Flat => {
+                painter.rect_filled(rect, 0.0, color);
+            }
+            CellStyle::Beveled => {
+                // Lit from the top left: light edges there, shaded ones opposite
+                painter.rect_filled(rect, 0.0, color);
+                let inner = rect.shrink(size * 0.15);
+                let alpha = color.a() as f32 / 255.0;
+                let light = Color32::WHITE.gamma_multiply(0.4 * alpha);
+                let shade = Color32::BLACK.gamma_multiply(0.4 * alpha);
+                for (points, color) in [
+                    ([rect.left_top(), rect.right_top(), inner.right_top(), inner.left_top()], light),
+                    ([rect.left_top(), inner.left_top(), inner.left_bottom(), rect.left_bottom()], light),
+                    ([rect.right_bottom(), rect.left_bottom(), inner.left_bottom(), inner.right_bottom()], shade),
+                    ([rect.right_bottom(), inner.right_bottom(), inner.right_top(), rect.right_top()], shade),
+                ] {
+                    painter.add(egui::Shape::convex_polygon(points.to_vec(), color, egui::Stroke::NONE));
+                }
+            }
+            CellStyle::Outlined => {
+                let width = (size * 0.1).max(1.0);
+                painter.rect_stroke(rect.shrink(size * 0.12), size * 0.1, egui::Stroke::new(width, color));
+            }
+        }
+    }
+}
+
+impl Theme {
+    pub fn piece_color(&self, block_type: BlockType) -> Color32 {
+        self.pieces[block_type as usize]
+    }
+
+    // What a grid cell is drawn in, or None for an empty cell
+    pub fn cell_color(&self, cell: u8) -> Option<Color32> {
+        match cell {
+            0 => None,
+            GARBAGE_CELL => Some(self.garbage),
+            _ => Some(BlockType::from_cell(cell).map_or(self.garbage, |block_type| self.piece_color(block_type))),
+        }
+    }
+
+    pub fn from_toml(text: &str, fallback_name: &str) -> Result<Theme, String> {
+        let document: DocumentMut = text.parse().map_err(|error| format!("{}", error))?;
+        let mut theme = Theme {
+            name: document
+                .get("name")
+                .and_then(|item| item.as_str())
+                .unwrap_or(fallback_name)
+                .to_string(),
+            ..Theme::default()
+        };
+
+        let color = |table: &dyn toml_edit::TableLike, key: &str, color: &mut Color32| -> Result<(), String> {
+            if let Some(item) = table.get(key) {
+                *color = item.as_str().and_then(parse_color).ok_or(format!("{} is not a #rrggbb color", key))?;
+            }
+            Ok(())
+        };
+        color(document.as_table(), "background", &mut theme.background)?;
+        color(document.as_table(), "board", &mut theme.board)?;
+        color(document.as_table(), "grid_lines", &mut theme.grid_lines)?;
+        color(document.as_table(), "border", &mut theme.border)?;
+        color(document.as_table(), "text", &mut theme.text)?;
+        color(document.as_table(), "garbage", &mut theme.garbage)?;
+        if let Some(backgrounds) = document.get("backgrounds") {
+            let backgrounds = backgrounds.as_array().ok_or("backgrounds is not a list of image files")?;
+            for background in backgrounds {
+                let file = background.as_str().ok_or("backgrounds is not a list of image files")?;
+                theme.backgrounds.push(format!("{}/{}", THEMES_DIR, file));
+            }
+        }
+        if let Some(pieces) = document.get("pieces").and_then(|item| item.as_table_like()) {
+            for block_type in BlockType::ALL {
+                color(pieces, &block_type.letter().to_string(), &mut theme.pieces[block_type as usize])?;
+            }
+        }
+        Ok(theme)
+    }
+
+    // The background image for a level, if the theme has any
+    pub fn background_for_level(&self, level: u32) -> Option<&str> {
+        if self.backgrounds.is_empty() {
+            return None;
+        }
+        Some(&self.backgrounds[level as usize % self.backgrounds.len()])
+    }
+}
+
+// Scales the image to cover the whole rect, cropping whatever sticks out
+fn paint_cover(painter: &egui::Painter, rect: egui::Rect, texture: &egui::TextureHandle) {
+    let image = texture.size_vec2();
+    let scale = (rect.width() / image.x).max(rect.height() / image.y);
+    let visible = rect.size() / (image * scale);
+    let uv = egui::Rect::from_center_size(egui::pos2(0.5, 0.5), visible);
+    painter.image(texture.id(), rect, uv, Color32::WHITE);
+}
+
+impl CrowsTetris {
+    // Reads the theme files again, staying on the current theme if it's still there
+    pub(crate) fn reload_themes(&mut self) {
+        let name = self.theme().name.clone();
+        let (themes, errors) = load_themes();
+        self.themes = themes;
+        for error in errors {
+            self.report_file_error(error, None);
+        }
+        self.background_textures.clear();
+        self.theme = self.themes.iter().position(|theme| theme.name == name).unwrap_or(0);
+    }
+
+    // Draws the current level's background behind everything. Images are loaded the first
+    // time they're needed and kept; one that fails to load is skipped from then on.
+    pub(crate) fn paint_level_background(&mut self, ctx: &egui::Context) -> bool {
+        let Some(path) = self.theme().background_for_level(self.level).map(str::to_string) else {
+            return false;
+        };
+        let texture = self
+            .background_textures
+            .entry(path.clone())
+            .or_insert_with(|| {
+                load_image(&path)
+                    .map_err(|error| self.file_errors.push(FileError::new(error, None)))
+                    .ok()
+                    .map(|image| ctx.load_texture(path.as_str(), image, egui::TextureOptions::LINEAR))
+            });
+
+        match texture {
+            Some(texture) => {
+                paint_cover(&ctx.layer_painter(egui::LayerId::background()), ctx.screen_rect(), texture);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// The built-in theme followed by every theme file that parses, by file name, and what was wrong
+// with the ones that didn't
+pub fn load_themes() -> (Vec<Theme>, Vec<String>) {
+    let mut paths: Vec<_> = fs::read_dir(THEMES_DIR)
+        .map(|entries| entries.filter_map(|entry| Some(entry.ok()?.path())).collect())
+        .unwrap_or_default();
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == "toml"));
+    paths.sort();
+
+    let mut themes = vec![Theme::default()];
+    let mut errors = Vec::new();
+    for path in paths {
+        let fallback_name = path.file_stem().map_or(String::new(), |stem| stem.to_string_lossy().into_owned());
+        match fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| Theme::from_toml(&text, &fallback_name))
+        {
+            Ok(theme) => themes.push(theme),
+            Err(error) => errors.push(format!("{}: {}", path.display(), error)),
+        }
+    }
+    (themes, errors)
+}
@@ -0,0 +1,34 @@
+// Time attack: reach each line checkpoint before its time limit runs out.
+// Missing one ends the run; clearing the last one finishes it.
+
+use std::time::Duration;
+
+use crate::{CrowsTetris, GameMode};
+
+// (lines to reach, play time they have to be reached by)
+const CHECKPOINTS: [(u32, Duration); 4] = [
+    (10, Duration::from_secs(45)),
+    (20, Duration::from_secs(90)),
+    (30, Duration::from_secs(130)),
+    (40, Duration::from_secs(165)),
+];
+
+pub const TIME_ATTACK_LINES: u32 = CHECKPOINTS[CHECKPOINTS.len() - 1].0;
+
+impl CrowsTetris {
+    // The checkpoint the player is racing towards, if any are left
+    pub(crate) fn next_checkpoint(&self) -> Option<(u32, Duration)> {
+        CHECKPOINTS.iter().copied().find(|&(lines, _)| self.lines < lines)
+    }
+
+    pub(crate) fn checkpoint_time_left(&self) -> Duration {
+        self.next_checkpoint()
+            .map_or(Duration::ZERO, |(_, limit)| limit.saturating_sub(self.play_time()))
+    }
+
+    pub(crate) fn checkpoint_missed(&self) -> bool {
+        self.mode == GameMode::TimeAttack
+            && self.next_checkpoint().is_some()
+            && self.checkpoint_time_left() == Duration::ZERO
+    }
+}
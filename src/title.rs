@@ -0,0 +1,126 @@
+// The title screen shown at launch: tetrominoes drifting down behind the crow
+// logo and a pulsing prompt. Any key or click goes on to the menu.
+
+use eframe::egui::{self, text::LayoutJob};
+use rand::Rng;
+use std::time::Instant;
+
+use crate::{profile, Block, BlockType, CrowsTetris};
+
+const TITLE_PIECES: usize = 14;
+// Cell size of the falling pieces, in points
+const TITLE_CELL_SIZE: f32 = 14.0;
+// A long gap between frames shouldn't make the pieces jump
+const MAX_FRAME_TIME: f32 = 0.1;
+
+// Positions are fractions of the screen so the pieces fill any window size
+pub struct TitlePiece {
+    block_type: BlockType,
+    x: f32,
+    y: f32,
+    // Screen heights per second
+    speed: f32,
+}
+
+impl TitlePiece {
+    fn random(rng: &mut impl Rng, y: f32) -> Self {
+        Self {
+            block_type: BlockType::ALL[rng.random_range(0..BlockType::ALL.len())],
+            x: rng.random_range(0.0..1.0),
+            y,
+            speed: rng.random_range(0.04..0.12),
+        }
+    }
+}
+
+impl CrowsTetris {
+    fn update_title_pieces(&mut self) {
+        let dt = self.title_updated.elapsed().as_secs_f32().min(MAX_FRAME_TIME);
+        self.title_updated = Instant::now();
+
+        let mut rng = rand::rng();
+        if self.title_pieces.is_empty() {
+            // Start with the screen already full rather than everything entering at once
+            self.title_pieces = (0..TITLE_PIECES)
+                .map(|_| {
+                    let y = rng.random_range(-0.1..1.0);
+                    TitlePiece::random(&mut rng, y)
+                })
+                .collect();
+        }
+        for piece in &mut self.title_pieces {
+            piece.y += piece.speed * dt;
+            if piece.y > 1.1 {
+                *piece = TitlePiece::random(&mut rng, -0.1);
+            }
+        }
+    }
+
+    fn paint_title_pieces(&self, painter: &egui::Painter, screen: egui::Rect) {
+        for piece in &self.title_pieces {
+            let origin = screen.min + egui::vec2(piece.x * screen.width(), piece.y * screen.height());
+            let color = self.theme().piece_color(piece.block_type).gamma_multiply(0.35);
+            for (y, row) in Block::new(piece.block_type).shape.iter().enumerate() {
+                for (x, &cell) in row.iter().enumerate() {
+                    if cell != 0 {
+                        let min = origin + egui::vec2(x as f32, y as f32) * TITLE_CELL_SIZE;
+                        let cell_rect = egui::Rect::from_min_size(min, egui::Vec2::splat(TITLE_CELL_SIZE));
+                        painter.rect_filled(cell_rect.shrink(1.0), 3.0, color);
+                    }
+                }
+            }
+        }
+    }
+
+    // "CROW'S" in the theme's text color over "TETRIS" with a letter in each piece color
+    fn title_logo(&self) -> LayoutJob {
+        let mut job = LayoutJob::default();
+        let format = |size: f32, color: egui::Color32| egui::TextFormat {
+            font_id: egui::FontId::proportional(size),
+            color,
+            ..Default::default()
+        };
+        job.append("CROW'S\n", 0.0, format(24.0, self.theme().text));
+        for (letter, block_type) in "TETRIS".chars().zip(BlockType::ALL) {
+            job.append(&letter.to_string(), 0.0, format(48.0, self.theme().piece_color(block_type)));
+        }
+        job.halign = egui::Align::Center;
+        job
+    }
+
+    pub(crate) fn render_title_screen(&mut self, ctx: &egui::Context) {
+        self.update_title_pieces();
+        let proceed = ctx.input(|i| {
+            i.events.iter().any(|event| {
+                matches!(
+                    event,
+                    egui::Event::Key { pressed: true, .. } | egui::Event::PointerButton { pressed: true, .. }
+                )
+            })
+        });
+
+        egui::CentralPanel::default()
+            .frame(egui::Frame::default().fill(self.theme().background))
+            .show(ctx, |ui| {
+                self.paint_title_pieces(ui.painter(), ui.max_rect());
+                ui.vertical_centered(|ui| {
+                    ui.add_space(ui.available_height() * 0.2);
+                    profile::draw_crow(ui, self.active_profile().avatar, 120.0);
+                    ui.label(self.title_logo());
+                    ui.add_space(40.0);
+
+                    let pulse = 0.6 + 0.4 * (ui.input(|i| i.time) as f32 * 3.0).sin();
+                    ui.label(
+                        egui::RichText::new(self.tr("Press any key"))
+                            .size(18.0)
+                            .color(self.theme().text.gamma_multiply(pulse)),
+                    );
+                });
+            });
+
+        if proceed {
+            self.at_title = false;
+        }
+        ctx.request_repaint();
+    }
+}
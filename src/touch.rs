@@ -0,0 +1,100 @@
+// Touch controls: dragging across the board moves the piece a column per
+// cell travelled, a tap rotates it, a swipe down hard drops and a swipe up
+// holds. A mouse drag works the same way, which is also how touches reach
+// egui. While they're on, buttons are made big enough for a finger.
+
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use crate::controls::Action;
+use crate::{CrowsTetris, GRID_WIDTH};
+
+// How far a finger can wander, in points, and how long it can stay down for a touch to still be a tap
+const TAP_SLOP: f32 = 10.0;
+const TAP_TIME: Duration = Duration::from_millis(300);
+// A swipe has to cover this many cells, mostly vertically, to drop or hold
+const SWIPE_CELLS: f32 = 3.0;
+const TOUCH_BUTTON_PADDING: egui::Vec2 = egui::vec2(16.0, 10.0);
+const TOUCH_TARGET_HEIGHT: f32 = 44.0;
+
+pub struct TouchGesture {
+    origin: egui::Pos2,
+    // Where the piece was last moved a column from
+    anchor_x: f32,
+    started: Instant,
+    moved: bool,
+}
+
+// What a finished touch did, given how far it went and for how long
+pub fn released_action(travel: egui::Vec2, held_for: Duration, moved: bool, cell_size: f32) -> Option<Action> {
+    if !moved && travel.length() < TAP_SLOP && held_for < TAP_TIME {
+        return Some(Action::Rotate);
+    }
+    if travel.y.abs() < SWIPE_CELLS * cell_size || travel.y.abs() < travel.x.abs() * 2.0 {
+        return None;
+    }
+    Some(if travel.y > 0.0 { Action::HardDrop } else { Action::Hold })
+}
+
+// Bigger buttons and list rows while touch controls are on, the normal ones otherwise
+pub fn apply_touch_style(ctx: &egui::Context, touch_controls: bool) {
+    let default = egui::style::Spacing::default();
+    let (padding, height) = if touch_controls {
+        (TOUCH_BUTTON_PADDING, TOUCH_TARGET_HEIGHT)
+    } else {
+        (default.button_padding, default.interact_size.y)
+    };
+    if ctx.style().spacing.button_padding != padding {
+        ctx.style_mut(|style| {
+            style.spacing.button_padding = padding;
+            style.spacing.interact_size.y = height;
+        });
+    }
+}
+
+impl CrowsTetris {
+    // Turns this frame's touches on the board into actions, which `action_pressed` reports
+    pub(crate) fn read_touch_gestures(&mut self, ctx: &egui::Context, board: egui::Rect) {
+        if !self.touch_controls {
+            return;
+        }
+        let cell_size = board.width() / GRID_WIDTH as f32;
+
+        let (pressed, released, position) = ctx.input(|i| {
+            (i.pointer.primary_pressed(), i.pointer.primary_released(), i.pointer.interact_pos())
+        });
+        let Some(position) = position else {
+            return;
+        };
+        if pressed && board.contains(position) {
+            self.touch_gesture = Some(TouchGesture {
+                origin: position,
+                anchor_x: position.x,
+                started: Instant::now(),
+                moved: false,
+            });
+        }
+        let Some(gesture) = self.touch_gesture.as_mut() else {
+            return;
+        };
+
+        if released {
+            let gesture = self.touch_gesture.take().unwrap();
+            let action = released_action(position - gesture.origin, gesture.started.elapsed(), gesture.moved, cell_size);
+            self.pointer_actions
+                .extend(action.filter(|&action| action != Action::HardDrop || self.hard_drop_allowed()));
+            return;
+        }
+
+        // One column a frame, so a fast swipe still takes the piece all the way
+        let dx = position.x - gesture.anchor_x;
+        if dx.abs() >= cell_size {
+            gesture.anchor_x += cell_size * dx.signum();
+            gesture.moved = true;
+            self.pointer_actions.push(if dx > 0.0 { Action::MoveRight } else { Action::MoveLeft });
+            if (position.x - gesture.anchor_x).abs() >= cell_size {
+                ctx.request_repaint();
+            }
+        }
+    }
+}